@@ -303,13 +303,17 @@ impl ApplicationRunner {
 
         self.context.process_visual_updates();
 
-        if self.context.style().needs_redraw {
+        if self.context.has_damage() {
             //     // TODO - Move this to EventManager
             self.should_redraw = true;
-            self.context.style().needs_redraw = false;
         }
     }
 
+    /// Returns true if part of the window needs to be repainted.
+    pub fn should_redraw(&self) -> bool {
+        self.should_redraw
+    }
+
     pub fn render(&mut self) {
         self.context.draw();
         self.should_redraw = false;
@@ -361,6 +365,8 @@ impl ApplicationRunner {
 
                     self.context.dispatch_system_event(WindowEvent::MouseScroll(lines_x, lines_y));
                 }
+                // This version of baseview doesn't report files dragged onto the window, so
+                // `FileHovered`, `FileDropped` and `FileHoverCancelled` are never sent from here.
                 _ => {}
             },
             baseview::Event::Keyboard(event) => {
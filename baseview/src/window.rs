@@ -200,6 +200,11 @@ impl WindowHandler for ViziaWindow {
 
         self.application.on_frame_update();
 
+        // The last frame stays on screen until part of the window needs to be repainted
+        if !self.application.should_redraw() {
+            return;
+        }
+
         unsafe { context.make_current() };
 
         self.application.render();
@@ -0,0 +1,188 @@
+//! Semantic information about views for assistive technology.
+//!
+//! Views describe themselves with a [`Role`], a name, a value and a set of states through the
+//! modifiers on [`Handle`](crate::handle::Handle). When accessibility is enabled with
+//! [`Context::enable_accessibility`], the context keeps a tree of [`AccessNode`]s in sync with the
+//! view tree and collects the changes into an [`AccessTreeUpdate`], which a platform adapter
+//! (such as one built on AccessKit) can forward to the operating system.
+//!
+//! Requests coming back from assistive technology are sent to views as [`AccessAction`] events.
+
+use fnv::FnvHashMap;
+
+use crate::cache::BoundingBox;
+use crate::prelude::*;
+
+/// The semantic role of a view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Unknown,
+    Window,
+    GenericContainer,
+    Group,
+    Label,
+    Image,
+    Button,
+    CheckBox,
+    RadioButton,
+    RadioGroup,
+    Switch,
+    Slider,
+    SpinButton,
+    TextField,
+    ComboBox,
+    List,
+    ListItem,
+    Menu,
+    MenuBar,
+    MenuItem,
+    Table,
+    Row,
+    Cell,
+    ColumnHeader,
+    Tree,
+    TreeItem,
+    TabList,
+    Tab,
+    TabPanel,
+    ScrollView,
+    ScrollBar,
+    Splitter,
+    Tooltip,
+    Dialog,
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::Unknown
+    }
+}
+
+impl Role {
+    /// Returns true if views with this role report a checked state.
+    pub fn is_checkable(&self) -> bool {
+        matches!(self, Role::CheckBox | Role::RadioButton | Role::Switch)
+    }
+
+    /// Returns true if views with this role take their name from the text of their descendants
+    /// when no name has been set.
+    pub fn name_from_contents(&self) -> bool {
+        matches!(
+            self,
+            Role::Button
+                | Role::ListItem
+                | Role::MenuItem
+                | Role::Cell
+                | Role::ColumnHeader
+                | Role::TreeItem
+                | Role::Tab
+                | Role::Tooltip
+        )
+    }
+}
+
+/// An action requested by assistive technology.
+///
+/// Actions are sent directly to the target view as an event. [`AccessAction::Focus`] is handled
+/// by the framework, all other actions are handled by the views which support them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessAction {
+    /// Activate the view, e.g. press a button or toggle a checkbox.
+    Press,
+    /// Move keyboard focus to the view.
+    Focus,
+    /// Replace the text value of the view.
+    SetValue(String),
+    /// Replace the numeric value of the view.
+    SetNumericValue(f64),
+    /// Increase the numeric value of the view by a step.
+    Increment,
+    /// Decrease the numeric value of the view by a step.
+    Decrement,
+    /// Show the children of the view, e.g. open a dropdown.
+    Expand,
+    /// Hide the children of the view, e.g. close a dropdown.
+    Collapse,
+}
+
+/// The semantic information exported for a single view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    pub entity: Entity,
+    pub role: Role,
+    pub name: Option<String>,
+    pub value: Option<String>,
+    pub numeric_value: Option<f64>,
+    /// `None` if the role of the node is not checkable.
+    pub checked: Option<bool>,
+    /// `None` if the node cannot be expanded.
+    pub expanded: Option<bool>,
+    pub selected: bool,
+    pub disabled: bool,
+    pub focusable: bool,
+    /// The bounds of the node in physical window coordinates.
+    pub bounds: BoundingBox,
+    /// The accessible children of the node, in tree order.
+    pub children: Vec<Entity>,
+}
+
+/// A set of changes to the accessibility tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessTreeUpdate {
+    /// Nodes which have been added or have changed since the last update.
+    pub nodes: Vec<AccessNode>,
+    /// Nodes which have been removed since the last update.
+    pub removed: Vec<Entity>,
+    /// The node which currently has keyboard focus.
+    pub focus: Entity,
+}
+
+impl AccessTreeUpdate {
+    fn merge(&mut self, other: AccessTreeUpdate) {
+        for node in other.nodes {
+            if let Some(existing) = self.nodes.iter_mut().find(|n| n.entity == node.entity) {
+                *existing = node;
+            } else {
+                self.nodes.push(node);
+            }
+        }
+
+        self.nodes.retain(|node| !other.removed.contains(&node.entity));
+
+        for entity in other.removed {
+            if !self.removed.contains(&entity) {
+                self.removed.push(entity);
+            }
+        }
+
+        self.focus = other.focus;
+    }
+}
+
+/// Receives accessibility tree updates from an application.
+///
+/// Windowing backends hand the updates produced by the context to an adapter after every frame.
+/// Adapters send requests from assistive technology back to the application as [`AccessAction`]
+/// events, for example with [`ContextProxy::emit_to`](crate::context::ContextProxy::emit_to).
+pub trait AccessibilityAdapter {
+    fn update(&mut self, update: AccessTreeUpdate);
+}
+
+/// The accessibility state stored in the context.
+#[derive(Default)]
+pub(crate) struct AccessibilityState {
+    pub enabled: bool,
+    pub nodes: FnvHashMap<Entity, AccessNode>,
+    pub focus: Entity,
+    pub pending: Option<AccessTreeUpdate>,
+}
+
+impl AccessibilityState {
+    pub fn push_update(&mut self, update: AccessTreeUpdate) {
+        if let Some(pending) = &mut self.pending {
+            pending.merge(update);
+        } else {
+            self.pending = Some(update);
+        }
+    }
+}
@@ -1,7 +1,9 @@
-use crate::animation::AnimationState;
+use crate::animation::{AnimationState, Interpolator, TimingFunction};
 use morphorm::Units;
 
 use crate::prelude::*;
+use crate::storage::animatable_set::AnimatableSet;
+use crate::style::Style;
 
 pub(crate) struct AnimationDescription {
     duration: instant::Duration,
@@ -100,6 +102,7 @@ pub struct KeyframeBuilder<'a> {
     id: Animation,
     cx: &'a mut Context,
     time: f32,
+    timing_function: TimingFunction,
     animation_description: AnimationDescription,
 }
 
@@ -110,7 +113,7 @@ impl<'a> KeyframeBuilder<'a> {
         time: f32,
         animation_description: AnimationDescription,
     ) -> Self {
-        Self { id, cx, time, animation_description }
+        Self { id, cx, time, timing_function: TimingFunction::default(), animation_description }
     }
 
     /// Finish building the animation, returning an [Animation] id.
@@ -125,6 +128,23 @@ impl<'a> KeyframeBuilder<'a> {
         self
     }
 
+    /// Sets the timing function used between this keyframe and the next.
+    ///
+    /// Like [`with_delay`](Self::with_delay), this applies to the properties which are set after it.
+    /// Keyframes are linear by default.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| {
+    ///     keyframe.with_timing_function(TimingFunction::EaseInOut).set_left(Pixels(0.0))
+    /// })
+    /// ```
+    pub fn with_timing_function(mut self, timing_function: TimingFunction) -> Self {
+        self.timing_function = timing_function;
+
+        self
+    }
+
     /// Add another keyframe to the animation.
     pub fn add_keyframe<F>(self, time: f32, keyframe: F) -> Self
     where
@@ -139,20 +159,8 @@ impl<'a> KeyframeBuilder<'a> {
     /// ```ignore
     /// .add_keyframe(0.0, |keyframe| keyframe.set_background_color(Color::red()))
     /// ```
-    pub fn set_background_color(self, color: Color) -> Self {
-        if let Some(anim_cx) = self.cx.style().background_color.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, color));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, color));
-
-            self.cx.style().background_color.insert_animation(self.id, anim_cx);
-        }
-
-        self
+    pub fn set_background_color(self, value: Color) -> Self {
+        self.set_property(|style| &mut style.background_color, value)
     }
 
     /// Adds a left property to the keyframe.
@@ -162,19 +170,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_left(Pixels(50.0)))
     /// ```
     pub fn set_left(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().left.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().left.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.left, value)
     }
 
     /// Adds a right property to the keyframe.
@@ -184,19 +180,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_right(Pixels(50.0)))
     /// ```
     pub fn set_right(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().right.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().right.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.right, value)
     }
 
     /// Adds a top property to the keyframe.
@@ -206,19 +190,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_top(Pixels(50.0)))
     /// ```
     pub fn set_top(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().top.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().top.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.top, value)
     }
 
     /// Adds a bottom property to the keyframe.
@@ -228,19 +200,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_bottom(Pixels(50.0)))
     /// ```
     pub fn set_bottom(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().bottom.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().bottom.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.bottom, value)
     }
 
     /// Adds a width property to the keyframe.
@@ -250,19 +210,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_width(Pixels(50.0)))
     /// ```
     pub fn set_width(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().width.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().width.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.width, value)
     }
 
     /// Adds a height property to the keyframe.
@@ -272,19 +220,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_height(Pixels(50.0)))
     /// ```
     pub fn set_height(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().height.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().height.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.height, value)
     }
 
     /// Adds a child-left property to the keyframe.
@@ -294,19 +230,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_child_left(Pixels(50.0)))
     /// ```
     pub fn set_child_left(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().child_left.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().child_left.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.child_left, value)
     }
 
     /// Adds a child-right property to the keyframe.
@@ -316,19 +240,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_child_right(Pixels(50.0)))
     /// ```
     pub fn set_child_right(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().child_right.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().child_right.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.child_right, value)
     }
 
     /// Adds a child-top property to the keyframe.
@@ -338,19 +250,7 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_child_top(Pixels(50.0)))
     /// ```
     pub fn set_child_top(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().child_top.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().child_top.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.child_top, value)
     }
 
     /// Adds a child-bottom property to the keyframe.
@@ -360,54 +260,231 @@ impl<'a> KeyframeBuilder<'a> {
     /// .add_keyframe(0.0, |keyframe| keyframe.set_child_bottom(Pixels(50.0)))
     /// ```
     pub fn set_child_bottom(self, value: Units) -> Self {
-        if let Some(anim_cx) = self.cx.style().child_bottom.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
-
-            self.cx.style().child_bottom.insert_animation(self.id, anim_cx);
-        }
-
-        self
+        self.set_property(|style| &mut style.child_bottom, value)
     }
 
     /// Adds a rotate transform property to the keyframe.
     ///
     /// # Example
     /// ```ignore
-    /// .add_keyframe(0.0, |keyframe| keyframe.set_rotate(Pixels(50.0)))
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_rotate(45.0))
     /// ```
     pub fn set_rotate(self, value: f32) -> Self {
-        if let Some(anim_cx) = self.cx.style().rotate.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, value));
-        } else {
-            let anim_cx = AnimationState::new(self.id)
-                .with_duration(self.animation_description.duration)
-                .with_delay(self.animation_description.delay)
-                .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, value));
+        self.set_property(|style| &mut style.rotate, value)
+    }
 
-            self.cx.style().rotate.insert_animation(self.id, anim_cx);
-        }
+    /// Adds a translate transform property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_translate((10.0, 0.0)))
+    /// ```
+    pub fn set_translate(self, value: (f32, f32)) -> Self {
+        self.set_property(|style| &mut style.translate, value)
+    }
 
-        self
+    /// Adds a scale transform property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_scale((1.1, 1.1)))
+    /// ```
+    pub fn set_scale(self, value: (f32, f32)) -> Self {
+        self.set_property(|style| &mut style.scale, value)
     }
 
+    /// Adds a border-width property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_border_width(Pixels(2.0)))
+    /// ```
+    pub fn set_border_width(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.border_width, value)
+    }
+
+    /// Adds a border-color property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_border_color(Color::blue()))
+    /// ```
+    pub fn set_border_color(self, value: Color) -> Self {
+        self.set_property(|style| &mut style.border_color, value)
+    }
+
+    /// Adds an outline-width property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outline_width(Pixels(2.0)))
+    /// ```
+    pub fn set_outline_width(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.outline_width, value)
+    }
+
+    /// Adds an outline-color property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outline_color(Color::blue()))
+    /// ```
+    pub fn set_outline_color(self, value: Color) -> Self {
+        self.set_property(|style| &mut style.outline_color, value)
+    }
+
+    /// Adds an outline-offset property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outline_offset(Pixels(2.0)))
+    /// ```
+    pub fn set_outline_offset(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.outline_offset, value)
+    }
+
+    /// Adds an outer-shadow-h-offset property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outer_shadow_h_offset(Pixels(4.0)))
+    /// ```
+    pub fn set_outer_shadow_h_offset(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.outer_shadow_h_offset, value)
+    }
+
+    /// Adds an outer-shadow-v-offset property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outer_shadow_v_offset(Pixels(4.0)))
+    /// ```
+    pub fn set_outer_shadow_v_offset(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.outer_shadow_v_offset, value)
+    }
+
+    /// Adds an outer-shadow-blur property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outer_shadow_blur(Pixels(8.0)))
+    /// ```
+    pub fn set_outer_shadow_blur(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.outer_shadow_blur, value)
+    }
+
+    /// Adds an outer-shadow-color property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_outer_shadow_color(Color::black()))
+    /// ```
+    pub fn set_outer_shadow_color(self, value: Color) -> Self {
+        self.set_property(|style| &mut style.outer_shadow_color, value)
+    }
+
+    /// Adds an inner-shadow-h-offset property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_inner_shadow_h_offset(Pixels(4.0)))
+    /// ```
+    pub fn set_inner_shadow_h_offset(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.inner_shadow_h_offset, value)
+    }
+
+    /// Adds an inner-shadow-v-offset property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_inner_shadow_v_offset(Pixels(4.0)))
+    /// ```
+    pub fn set_inner_shadow_v_offset(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.inner_shadow_v_offset, value)
+    }
+
+    /// Adds an inner-shadow-blur property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_inner_shadow_blur(Pixels(8.0)))
+    /// ```
+    pub fn set_inner_shadow_blur(self, value: Units) -> Self {
+        self.set_property(|style| &mut style.inner_shadow_blur, value)
+    }
+
+    /// Adds an inner-shadow-color property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_inner_shadow_color(Color::black()))
+    /// ```
+    pub fn set_inner_shadow_color(self, value: Color) -> Self {
+        self.set_property(|style| &mut style.inner_shadow_color, value)
+    }
+
+    /// Adds a color property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_font_color(Color::white()))
+    /// ```
+    pub fn set_font_color(self, value: Color) -> Self {
+        self.set_property(|style| &mut style.font_color, value)
+    }
+
+    /// Adds a font-size property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_font_size(24.0))
+    /// ```
+    pub fn set_font_size(self, value: f32) -> Self {
+        self.set_property(|style| &mut style.font_size, value)
+    }
+
+    /// Adds an opacity property to the keyframe.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_opacity(0.5))
+    /// ```
     pub fn set_opacity(self, value: f32) -> Self {
-        if let Some(anim_cx) = self.cx.style().opacity.get_animation_mut(self.id) {
-            anim_cx.keyframes.push((self.time, Opacity(value)));
+        self.set_property(|style| &mut style.opacity, Opacity(value))
+    }
+
+    /// Adds gradient background layers to the keyframe.
+    ///
+    /// Gradients are interpolated stop by stop, so the layers in each keyframe should have the
+    /// same kinds of gradients with the same number of stops.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .add_keyframe(0.0, |keyframe| keyframe.set_background_gradient(vec![gradient.into()]))
+    /// ```
+    pub fn set_background_gradient(self, value: Vec<Gradient>) -> Self {
+        self.set_property(|style| &mut style.background_gradient, value)
+    }
+
+    // Adds a value of a property to the keyframe, creating the animation of the property if needed
+    fn set_property<T>(self, property: fn(&mut Style) -> &mut AnimatableSet<T>, value: T) -> Self
+    where
+        T: 'static + Default + Clone + Interpolator + PartialEq + std::fmt::Debug,
+    {
+        let storage = property(self.cx.style());
+
+        if let Some(anim_cx) = storage.get_animation_mut(self.id) {
+            anim_cx.keyframes.push((self.time, value));
+            anim_cx.timing_functions.push(self.timing_function);
         } else {
             let anim_cx = AnimationState::new(self.id)
                 .with_duration(self.animation_description.duration)
                 .with_delay(self.animation_description.delay)
                 .set_persistent(self.animation_description.persistent)
-                .with_keyframe((self.time, Opacity(value)));
+                .with_keyframe((self.time, value))
+                .with_timing_function(self.timing_function);
 
-            self.cx.style().opacity.insert_animation(self.id, anim_cx);
+            storage.insert_animation(self.id, anim_cx);
         }
 
         self
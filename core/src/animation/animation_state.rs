@@ -1,4 +1,4 @@
-use crate::animation::Interpolator;
+use crate::animation::{Interpolator, TimingFunction};
 use crate::id::GenerationalId;
 use instant::{Duration, Instant};
 use std::collections::HashSet;
@@ -19,6 +19,8 @@ pub struct AnimationState<Prop: Interpolator> {
     pub delay: f32,
     /// List of animation keyframes as (normalized time, value).
     pub keyframes: Vec<(f32, Prop)>,
+    /// The timing function of the segment starting at each keyframe.
+    pub timing_functions: Vec<TimingFunction>,
     /// The output of value of the animation.
     pub output: Option<Prop>,
     /// Whether the animation should persist after finishing.
@@ -54,6 +56,7 @@ where
             duration: Duration::new(0, 0),
             delay: 0.0,
             keyframes: Vec::new(),
+            timing_functions: Vec::new(),
             output: None,
             persistent: false,
             t0: 0.0,
@@ -86,10 +89,50 @@ where
 
     pub fn with_keyframe(mut self, key: (f32, Prop)) -> Self {
         self.keyframes.push(key);
+        self.timing_functions.push(TimingFunction::default());
 
         self
     }
 
+    /// Sets the timing function of the segment starting at the last added keyframe.
+    pub fn with_timing_function(mut self, timing_function: TimingFunction) -> Self {
+        if let Some(last) = self.timing_functions.last_mut() {
+            *last = timing_function;
+        }
+
+        self
+    }
+
+    /// Returns the value of the animation at the normalized time `t`, easing each segment between
+    /// keyframes with the timing function of its first keyframe.
+    pub fn value_at(&self, t: f32) -> Option<Prop>
+    where
+        Prop: Clone,
+    {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+
+        if t <= first.0 {
+            return Some(first.1.clone());
+        }
+
+        if t >= last.0 {
+            return Some(last.1.clone());
+        }
+
+        let index = self.keyframes.windows(2).position(|pair| t < pair[1].0)?;
+        let (start, end) = (&self.keyframes[index], &self.keyframes[index + 1]);
+        let length = end.0 - start.0;
+        if length <= 0.0 {
+            return Some(end.1.clone());
+        }
+
+        let timing_function = self.timing_functions.get(index).copied().unwrap_or_default();
+        let progress =
+            timing_function.evaluate((t - start.0) / length, length * self.duration.as_secs_f32());
+
+        Some(Prop::interpolate(&start.1, &end.1, progress))
+    }
+
     pub fn interpolate(&mut self, current_time: Instant) -> bool {
         if current_time > self.start_time + self.duration {
             return false;
@@ -140,6 +183,7 @@ where
             duration: Duration::new(0, 0),
             delay: 0.0,
             keyframes: Vec::new(),
+            timing_functions: Vec::new(),
             output: None,
             persistent: true,
             t0: 0.0,
@@ -54,6 +54,9 @@ pub(crate) use interpolator::Interpolator;
 mod transition;
 pub(crate) use transition::Transition;
 
+mod timing_function;
+pub use timing_function::{StepPosition, TimingFunction};
+
 mod animation_builder;
 pub use animation_builder::*;
 
@@ -0,0 +1,227 @@
+/// Where the jumps of a [`TimingFunction::Steps`] function occur.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StepPosition {
+    /// The first jump happens at the start of the animation.
+    Start,
+    /// The last jump happens at the end of the animation.
+    End,
+}
+
+/// Describes how an animated value progresses between two keyframes.
+///
+/// Timing functions map the linear progress of a keyframe segment to an eased progress. Some
+/// functions, like [`TimingFunction::Spring`] or a cubic bezier with control points outside of the
+/// 0 to 1 range, overshoot the target value before settling.
+///
+/// # Example
+/// ```ignore
+/// cx.add_animation(std::time::Duration::from_secs(1))
+///     .add_keyframe(0.0, |keyframe| {
+///         keyframe.with_timing_function(TimingFunction::EaseOut).set_scale((1.0, 1.0))
+///     })
+///     .add_keyframe(1.0, |keyframe| keyframe.set_scale((1.2, 1.2)))
+///     .build();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimingFunction {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// A cubic bezier curve from (0, 0) to (1, 1) with the control points (x1, y1) and (x2, y2).
+    CubicBezier(f32, f32, f32, f32),
+    /// Jumps between the values in the given number of equal steps.
+    Steps(u32, StepPosition),
+    /// A damped spring which pulls the value towards the end of the segment.
+    ///
+    /// Unlike the other timing functions, the motion of a spring depends on the duration of the
+    /// segment, which should be long enough for the spring to settle.
+    Spring {
+        stiffness: f32,
+        damping: f32,
+        mass: f32,
+    },
+}
+
+impl Default for TimingFunction {
+    fn default() -> Self {
+        TimingFunction::Linear
+    }
+}
+
+impl TimingFunction {
+    /// Creates a spring with a mass of 1.
+    pub fn spring(stiffness: f32, damping: f32) -> Self {
+        TimingFunction::Spring { stiffness, damping, mass: 1.0 }
+    }
+
+    /// Returns the eased progress for a linear progress `t` between 0 and 1 through a segment lasting
+    /// `duration` seconds.
+    pub fn evaluate(&self, t: f32, duration: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match *self {
+            TimingFunction::Linear => t,
+            TimingFunction::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            TimingFunction::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            TimingFunction::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            TimingFunction::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+            TimingFunction::Steps(steps, position) => {
+                let steps = steps.max(1) as f32;
+                let step = match position {
+                    StepPosition::Start => (t * steps).ceil(),
+                    StepPosition::End => (t * steps).floor(),
+                };
+
+                (step / steps).min(1.0)
+            }
+            TimingFunction::Spring { stiffness, damping, mass } => {
+                spring(stiffness, damping, mass, t * duration)
+            }
+        }
+    }
+}
+
+// Returns the y value of a cubic bezier curve at the point where its x value is `x`
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    if x <= 0.0 || x >= 1.0 {
+        return x;
+    }
+
+    let sample = |p1: f32, p2: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    };
+
+    let slope = |p1: f32, p2: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    };
+
+    // Find the curve parameter for x with newton's method, falling back to bisection
+    let mut s = x;
+    for _ in 0..8 {
+        let error = sample(x1, x2, s) - x;
+        if error.abs() < 1e-6 {
+            return sample(y1, y2, s);
+        }
+
+        let derivative = slope(x1, x2, s);
+        if derivative.abs() < 1e-6 {
+            break;
+        }
+
+        s -= error / derivative;
+    }
+
+    let mut low = 0.0;
+    let mut high = 1.0;
+    s = x;
+    while high - low > 1e-6 {
+        if sample(x1, x2, s) < x {
+            low = s;
+        } else {
+            high = s;
+        }
+
+        s = (low + high) / 2.0;
+    }
+
+    sample(y1, y2, s)
+}
+
+// Returns the displacement of a damped spring released from 0 towards 1 after `time` seconds
+fn spring(stiffness: f32, damping: f32, mass: f32, time: f32) -> f32 {
+    let stiffness = stiffness.max(f32::EPSILON);
+    let mass = mass.max(f32::EPSILON);
+
+    let natural_frequency = (stiffness / mass).sqrt();
+    let damping_ratio = damping.max(0.0) / (2.0 * (stiffness * mass).sqrt());
+
+    if (damping_ratio - 1.0).abs() < 1e-4 {
+        // Critically damped
+        1.0 - (-natural_frequency * time).exp() * (1.0 + natural_frequency * time)
+    } else if damping_ratio < 1.0 {
+        // Under damped
+        let damped_frequency = natural_frequency * (1.0 - damping_ratio * damping_ratio).sqrt();
+        let decay = (-damping_ratio * natural_frequency * time).exp();
+
+        1.0 - decay
+            * ((damped_frequency * time).cos()
+                + (damping_ratio * natural_frequency / damped_frequency)
+                    * (damped_frequency * time).sin())
+    } else {
+        // Over damped
+        let root = (damping_ratio * damping_ratio - 1.0).sqrt();
+        let r1 = -natural_frequency * (damping_ratio - root);
+        let r2 = -natural_frequency * (damping_ratio + root);
+
+        1.0 - (r2 * (r1 * time).exp() - r1 * (r2 * time).exp()) / (r2 - r1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn end_points() {
+        let functions = [
+            TimingFunction::Linear,
+            TimingFunction::Ease,
+            TimingFunction::EaseIn,
+            TimingFunction::EaseOut,
+            TimingFunction::EaseInOut,
+            TimingFunction::CubicBezier(0.3, -0.5, 0.7, 1.5),
+            TimingFunction::Steps(4, StepPosition::End),
+        ];
+
+        for function in functions.iter() {
+            assert_eq!(function.evaluate(0.0, 1.0), 0.0);
+            assert_eq!(function.evaluate(1.0, 1.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn cubic_bezier() {
+        // A bezier with control points on the diagonal is linear
+        let linear = TimingFunction::CubicBezier(0.25, 0.25, 0.75, 0.75);
+        assert!((linear.evaluate(0.3, 1.0) - 0.3).abs() < 1e-4);
+
+        assert!(TimingFunction::EaseIn.evaluate(0.5, 1.0) < 0.5);
+        assert!(TimingFunction::EaseOut.evaluate(0.5, 1.0) > 0.5);
+        assert!((TimingFunction::EaseInOut.evaluate(0.5, 1.0) - 0.5).abs() < 1e-4);
+
+        // Control points outside of the unit square overshoot
+        assert!(TimingFunction::CubicBezier(0.3, 0.0, 0.7, 2.0).evaluate(0.8, 1.0) > 1.0);
+    }
+
+    #[test]
+    fn steps() {
+        let end = TimingFunction::Steps(4, StepPosition::End);
+        assert_eq!(end.evaluate(0.2, 1.0), 0.0);
+        assert_eq!(end.evaluate(0.3, 1.0), 0.25);
+        assert_eq!(end.evaluate(0.99, 1.0), 0.75);
+
+        let start = TimingFunction::Steps(4, StepPosition::Start);
+        assert_eq!(start.evaluate(0.1, 1.0), 0.25);
+        assert_eq!(start.evaluate(0.8, 1.0), 1.0);
+    }
+
+    #[test]
+    fn spring() {
+        let bouncy = TimingFunction::spring(200.0, 5.0);
+        assert_eq!(bouncy.evaluate(0.0, 2.0), 0.0);
+        assert!((0..100).any(|i| bouncy.evaluate(i as f32 / 100.0, 2.0) > 1.0));
+        assert!((bouncy.evaluate(1.0, 2.0) - 1.0).abs() < 0.01);
+
+        let critical = TimingFunction::spring(100.0, 20.0);
+        assert!((0..=100).all(|i| critical.evaluate(i as f32 / 100.0, 1.0) <= 1.0));
+
+        let overdamped = TimingFunction::spring(100.0, 40.0);
+        assert!((0..=100).all(|i| overdamped.evaluate(i as f32 / 100.0, 1.0) <= 1.0));
+        assert!(overdamped.evaluate(0.5, 1.0) < critical.evaluate(0.5, 1.0));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::animation::TimingFunction;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Transition {
     // List of properties affected by transition
@@ -6,10 +8,17 @@ pub struct Transition {
     pub duration: f32,
     // Delay of the transition
     pub delay: f32,
+    // Timing function of the transition
+    pub timing_function: TimingFunction,
 }
 
 impl Transition {
     pub fn new() -> Self {
-        Transition { property: String::new(), duration: 0.0, delay: 0.0 }
+        Transition {
+            property: String::new(),
+            duration: 0.0,
+            delay: 0.0,
+            timing_function: TimingFunction::default(),
+        }
     }
 }
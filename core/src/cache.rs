@@ -244,6 +244,29 @@ impl BoundingBox {
         let y_hit = other.y >= self.y && other.y < self.y + self.h;
         x_hit && y_hit
     }
+
+    /// Returns the smallest bounding box which contains both bounding boxes.
+    pub fn union(&self, other: &Self) -> BoundingBox {
+        BoundingBox::from_min_max(
+            self.left().min(other.left()),
+            self.top().min(other.top()),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+
+    /// Returns the area covered by both bounding boxes, which has a width or height of zero if
+    /// they don't overlap.
+    pub fn intersection(&self, other: &Self) -> BoundingBox {
+        let x = self.left().max(other.left());
+        let y = self.top().max(other.top());
+        BoundingBox {
+            x,
+            y,
+            w: (self.right().min(other.right()) - x).max(0.0),
+            h: (self.bottom().min(other.bottom()) - y).max(0.0),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -297,6 +320,10 @@ pub struct CachedData {
     stack_child: SparseSet<(bool, bool)>,
 
     pub(crate) geometry_changed: SparseSet<GeometryChanged>,
+
+    /// The region of the window which each entity covered when damage was last computed. Entities
+    /// which aren't drawn have no region.
+    pub(crate) painted: SparseSet<BoundingBox>,
     //pub(crate) text_lines: SparseSet<Vec<(Range<usize>, femtovg::TextMetrics)>>,
 }
 
@@ -370,6 +397,7 @@ impl CachedData {
         self.grid_col_max.remove(entity);
 
         self.geometry_changed.remove(entity);
+        self.painted.remove(entity);
 
         self.abilities.remove(entity);
     }
@@ -937,4 +965,24 @@ mod tests {
         let b = BoundingBox { x: 100f32, y: 75f32, w: 100f32, h: 150f32 };
         assert_eq!(a, b);
     }
+
+    #[test]
+    fn get_union() {
+        let rect = rect();
+        let a = rect.union(&BoundingBox { x: 150f32, y: 50f32, w: 100f32, h: 100f32 });
+        let b = BoundingBox { x: 100f32, y: 50f32, w: 150f32, h: 150f32 };
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn get_intersection() {
+        let rect = rect();
+        let a = rect.intersection(&BoundingBox { x: 150f32, y: 50f32, w: 100f32, h: 100f32 });
+        let b = BoundingBox { x: 150f32, y: 100f32, w: 50f32, h: 50f32 };
+        assert_eq!(a, b);
+
+        let a = rect.intersection(&BoundingBox { x: 300f32, y: 300f32, w: 100f32, h: 100f32 });
+        assert_eq!(a.w, 0f32);
+        assert_eq!(a.h, 0f32);
+    }
 }
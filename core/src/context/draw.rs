@@ -1,7 +1,7 @@
 use std::any::Any;
 use std::ops::Range;
 
-use femtovg::{ImageId, TextContext};
+use femtovg::{ImageId, RenderTarget, TextContext};
 use fnv::FnvHashMap;
 use morphorm::Units;
 
@@ -12,23 +12,38 @@ use crate::prelude::*;
 use crate::resource::ResourceManager;
 use crate::state::ModelDataStore;
 use crate::storage::sparse_set::SparseSet;
-use crate::style::{LinearGradient, Style};
+use crate::style::{Gradient, Style};
 use crate::text::Selection;
 
 /// Cached data used for drawing.
 pub struct DrawCache {
     pub shadow_image: SparseSet<(ImageId, ImageId)>,
     pub text_lines: SparseSet<Vec<(Range<usize>, femtovg::TextMetrics)>>,
+    pub conic_images: SparseSet<Vec<ConicImage>>,
+}
+
+/// An image which a conic gradient has been drawn into, along with the gradient, the logical size
+/// and the scale factor it was drawn for.
+pub struct ConicImage {
+    pub image: ImageId,
+    pub gradient: ConicGradient,
+    pub size: (f32, f32),
+    pub scale: f32,
 }
 
 impl DrawCache {
     pub fn new() -> Self {
-        Self { shadow_image: SparseSet::new(), text_lines: SparseSet::new() }
+        Self {
+            shadow_image: SparseSet::new(),
+            text_lines: SparseSet::new(),
+            conic_images: SparseSet::new(),
+        }
     }
 
     pub fn remove(&mut self, entity: Entity) {
         self.shadow_image.remove(entity);
         self.text_lines.remove(entity);
+        self.conic_images.remove(entity);
     }
 }
 
@@ -48,6 +63,9 @@ pub struct DrawContext<'a> {
     pub text_context: &'a TextContext,
     pub modifiers: &'a Modifiers,
     pub mouse: &'a MouseState,
+    pub(crate) render_target: RenderTarget,
+    /// The window whose canvas is drawn into.
+    pub(crate) window: Entity,
 }
 
 macro_rules! style_getter_units {
@@ -74,6 +92,7 @@ macro_rules! style_getter_untranslated {
 impl<'a> DrawContext<'a> {
     /// Creates a new `DrawContext` from the given `Context`.
     pub fn new(cx: &'a mut Context) -> Self {
+        let window = cx.window_of(cx.current);
         Self {
             current: cx.current,
             captured: &cx.captured,
@@ -89,6 +108,8 @@ impl<'a> DrawContext<'a> {
             text_context: &cx.text_context,
             modifiers: &cx.modifiers,
             mouse: &cx.mouse,
+            render_target: RenderTarget::Screen,
+            window,
         }
     }
 
@@ -96,6 +117,19 @@ impl<'a> DrawContext<'a> {
         self.cache.get_bounds(self.current)
     }
 
+    /// Returns the render target which the view is drawn into. Views which draw into their own
+    /// images should switch back to this target afterwards, rather than to the screen.
+    pub fn render_target(&self) -> RenderTarget {
+        self.render_target
+    }
+
+    /// Returns the id of a loaded image in the canvas which is drawn into, along with its size, or
+    /// `None` if the image hasn't been uploaded to the canvas.
+    pub fn image_id(&self, name: &str) -> Option<(ImageId, (u32, u32))> {
+        let image = self.resource_manager.images.get(name)?;
+        image.ids.get(&self.window).map(|id| (*id, image.dimensions()))
+    }
+
     /// Returns the name of the default font.
     pub fn default_font(&self) -> &str {
         &self.style.default_font
@@ -113,7 +147,7 @@ impl<'a> DrawContext<'a> {
 
     /// Function to convert physical pixels to logical points.
     pub fn physical_to_logical(&self, physical: f32) -> f32 {
-        physical * self.style.dpi_factor as f32
+        physical / self.style.dpi_factor as f32
     }
 
     style_getter_units!(border_width);
@@ -141,7 +175,7 @@ impl<'a> DrawContext<'a> {
     style_getter_untranslated!(Color, inner_shadow_color);
     style_getter_untranslated!(Color, selection_color);
     style_getter_untranslated!(Color, caret_color);
-    style_getter_untranslated!(LinearGradient, background_gradient);
+    style_getter_untranslated!(Vec<Gradient>, background_gradient);
     style_getter_untranslated!(BorderCornerShape, border_shape_top_right);
     style_getter_untranslated!(BorderCornerShape, border_shape_top_left);
     style_getter_untranslated!(BorderCornerShape, border_shape_bottom_right);
@@ -15,6 +15,7 @@ use crate::resource::ResourceManager;
 use crate::state::ModelDataStore;
 use crate::storage::sparse_set::SparseSet;
 use crate::style::Style;
+use crate::systems::drag_system::DragState;
 
 #[cfg(feature = "clipboard")]
 use copypasta::ClipboardProvider;
@@ -43,6 +44,7 @@ pub struct EventContext<'a> {
     #[cfg(feature = "clipboard")]
     clipboard: &'a mut Box<dyn ClipboardProvider>,
     event_proxy: &'a mut Option<Box<dyn crate::context::EventProxy>>,
+    pub(crate) drag: &'a mut DragState,
 }
 
 impl<'a> EventContext<'a> {
@@ -68,6 +70,7 @@ impl<'a> EventContext<'a> {
             #[cfg(feature = "clipboard")]
             clipboard: &mut cx.clipboard,
             event_proxy: &mut cx.event_proxy,
+            drag: &mut cx.drag,
         }
     }
 
@@ -122,9 +125,9 @@ impl<'a> EventContext<'a> {
             pseudo_classes.set(PseudoClass::ACTIVE, active);
         }
 
-        self.style.needs_restyle = true;
+        self.style.restyle(self.current);
+        self.style.redraw(self.current);
         self.style.needs_relayout = true;
-        self.style.needs_redraw = true;
     }
 
     pub fn capture(&mut self) {
@@ -135,6 +138,35 @@ impl<'a> EventContext<'a> {
         *self.captured = Entity::null();
     }
 
+    /// Starts dragging the current view, carrying the given payload.
+    ///
+    /// While the cursor moves, drop targets which accept the payload are sent [`DragEvent`]s. The
+    /// drag ends when the left mouse button is released, dropping the payload onto the drop target
+    /// under the cursor, or is cancelled when escape is pressed.
+    pub fn start_drag<T: 'static>(&mut self, payload: T) {
+        let (pressx, pressy) = self.mouse.left.pos_down;
+
+        self.drag.source = self.current;
+        self.drag.payload = Some(Box::new(payload));
+        self.drag.grab_offset = (
+            pressx - self.cache.get_posx(self.current),
+            pressy - self.cache.get_posy(self.current),
+        );
+        self.drag.preview_content = None;
+        self.drag.target = Entity::null();
+        self.drag.ended = false;
+    }
+
+    /// Returns true if a view is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_dragging()
+    }
+
+    /// Returns the payload of the current drag if there is one and it has the type `T`.
+    pub fn drag_payload<T: 'static>(&self) -> Option<&T> {
+        self.drag.payload.as_ref()?.downcast_ref::<T>()
+    }
+
     /// Sets application focus to the current entity.
     pub fn focus(&mut self) {
         let old_focus = *self.focused;
@@ -151,9 +183,11 @@ impl<'a> EventContext<'a> {
             pseudo_classes.set(PseudoClass::FOCUS, true);
         }
 
+        self.style.restyle(old_focus);
+        self.style.restyle(new_focus);
+        self.style.redraw(old_focus);
+        self.style.redraw(new_focus);
         self.style.needs_relayout = true;
-        self.style.needs_redraw = true;
-        self.style.needs_restyle = true;
     }
 
     pub fn hovered(&self) -> Entity {
@@ -199,9 +233,9 @@ impl<'a> EventContext<'a> {
             pseudo_classes.set(PseudoClass::HOVER, flag);
         }
 
-        self.style.needs_restyle = true;
+        self.style.restyle(self.current);
+        self.style.redraw(self.current);
         self.style.needs_relayout = true;
-        self.style.needs_redraw = true;
     }
 
     /// Sets the checked flag of the current entity.
@@ -211,9 +245,9 @@ impl<'a> EventContext<'a> {
             pseudo_classes.set(PseudoClass::CHECKED, flag);
         }
 
-        self.style.needs_restyle = true;
+        self.style.restyle(self.current);
+        self.style.redraw(self.current);
         self.style.needs_relayout = true;
-        self.style.needs_redraw = true;
     }
 
     /// Sets the checked flag of the current entity.
@@ -223,9 +257,9 @@ impl<'a> EventContext<'a> {
             pseudo_classes.set(PseudoClass::SELECTED, flag);
         }
 
-        self.style.needs_restyle = true;
+        self.style.restyle(self.current);
+        self.style.redraw(self.current);
         self.style.needs_relayout = true;
-        self.style.needs_redraw = true;
     }
 
     /// Get the contents of the system clipboard. This may fail for a variety of backend-specific
@@ -259,9 +293,9 @@ impl<'a> EventContext<'a> {
             self.style.classes.insert(current, class_list).expect("Failed to insert class name");
         }
 
-        self.style.needs_restyle = true;
+        self.style.restyle(self.current);
+        self.style.redraw(self.current);
         self.style.needs_relayout = true;
-        self.style.needs_redraw = true;
     }
 
     pub fn play_animation(&mut self, animation: Animation) {
@@ -272,8 +306,10 @@ impl<'a> EventContext<'a> {
         self.data::<Environment>().unwrap()
     }
 
+    /// Marks the current view to be redrawn on the next frame, after a change to something which
+    /// its `draw` method depends on.
     pub fn needs_redraw(&mut self) {
-        self.style.needs_redraw = true;
+        self.style.redraw(self.current);
     }
 
     pub fn spawn<F>(&self, target: F)
@@ -11,8 +11,8 @@ use std::sync::Mutex;
 
 #[cfg(feature = "clipboard")]
 use copypasta::{nop_clipboard::NopClipboardContext, ClipboardContext, ClipboardProvider};
-use femtovg::TextContext;
-use fnv::FnvHashMap;
+use femtovg::{ImageFlags, ImageId, Paint, Path, PixelFormat, RenderTarget, TextContext};
+use fnv::{FnvHashMap, FnvHashSet};
 use keyboard_types::Code;
 use morphorm::layout;
 use unic_langid::LanguageIdentifier;
@@ -21,7 +21,8 @@ pub use draw::*;
 pub use event::*;
 pub use proxy::*;
 
-use crate::cache::CachedData;
+use crate::accessibility::{AccessNode, AccessTreeUpdate, AccessibilityState};
+use crate::cache::{BoundingBox, CachedData};
 use crate::environment::Environment;
 use crate::events::ViewHandler;
 use crate::hover_system::apply_hover;
@@ -29,15 +30,19 @@ use crate::id::{GenerationalId, IdManager};
 use crate::input::{Modifiers, MouseState};
 use crate::layout::geometry_changed;
 use crate::prelude::*;
-use crate::resource::{FontOrId, ImageOrId, ImageRetentionPolicy, ResourceManager, StoredImage};
+use crate::resource::{FontOrId, ImageRetentionPolicy, ResourceManager, StoredImage};
 use crate::state::ModelDataStore;
 use crate::storage::sparse_set::SparseSet;
-use crate::style::{apply_transform, Style};
+use crate::style::{apply_transform, MediaEnvironment, Style};
 use crate::style_system::{
     apply_clipping, apply_inline_inheritance, apply_shared_inheritance, apply_styles,
     apply_text_constraints, apply_visibility, apply_z_ordering,
 };
+use crate::systems::accessibility_system::accessibility_system;
+use crate::systems::damage_system::{damage_system, DamageState};
+use crate::systems::drag_system::{drag_system, end_drag, update_drop_target, DragState};
 use crate::systems::image_system::image_system;
+use crate::systems::tooltip_system::{position_tooltip, tooltip_system, TooltipState};
 use crate::tree::{
     focus_backward, focus_forward, is_navigatable, TreeDepthIterator, TreeExt, TreeIterator,
 };
@@ -45,6 +50,9 @@ use crate::tree::{
 static DEFAULT_THEME: &str = include_str!("../../resources/themes/default_theme.css");
 static DEFAULT_LAYOUT: &str = include_str!("../../resources/themes/default_layout.css");
 const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+// The tints of repainted regions when paint flashing is enabled
+const PAINT_FLASHING_COLORS: [(u8, u8, u8); 4] =
+    [(255, 0, 0), (0, 255, 0), (0, 0, 255), (255, 255, 0)];
 
 /// The main storage and control object for a Vizia application.
 ///
@@ -54,17 +62,31 @@ pub struct Context {
     pub(crate) tree: Tree,
     current: Entity,
     /// TODO make this private when there's no longer a need to mutate views after building
+    ///
+    /// Changing a view through this map doesn't mark it as changed, so the bindings to its lenses
+    /// aren't updated. Use [`Handle::modify`] to change a view after building it instead.
     pub views: FnvHashMap<Entity, Box<dyn ViewHandler>>,
     pub(crate) data: SparseSet<ModelDataStore>,
+    /// Entities whose models or view may have changed since the last data update, and whose
+    /// lenses need to be checked.
+    pub(crate) dirty_data: FnvHashSet<Entity>,
     pub(crate) event_queue: VecDeque<Event>,
     pub(crate) listeners:
         HashMap<Entity, Box<dyn Fn(&mut dyn ViewHandler, &mut EventContext, &mut Event)>>,
     pub(crate) global_listeners: Vec<Box<dyn Fn(&mut EventContext, &mut Event)>>,
     pub(crate) style: Style,
-    cache: CachedData,
+    pub(crate) cache: CachedData,
     pub draw_cache: DrawCache,
 
     pub canvases: HashMap<Entity, crate::prelude::Canvas>,
+    /// Windows created with the [`Window`](crate::views::Window) view, along with the properties
+    /// used to create them. The main window at the root is not included.
+    pub(crate) windows: FnvHashMap<Entity, WindowDescription>,
+    /// The views and canvases of windows which have been removed. They are kept until the
+    /// windowing backend has released the canvas in the graphics context it belongs to.
+    closed_windows: Vec<(Box<dyn ViewHandler>, Option<Canvas>)>,
+    /// The window which the last mouse and keyboard input came from.
+    pub(crate) input_window: Entity,
     //environment: Environment,
     mouse: MouseState,
     modifiers: Modifiers,
@@ -76,6 +98,10 @@ pub struct Context {
 
     pub(crate) resource_manager: ResourceManager,
 
+    pub(crate) accessibility: AccessibilityState,
+
+    pub(crate) damage: DamageState,
+
     text_context: TextContext,
 
     event_proxy: Option<Box<dyn EventProxy>>,
@@ -87,6 +113,13 @@ pub struct Context {
     double_click: bool,
     click_pos: (f32, f32),
 
+    // The view which files dragged onto the window are sent to, and whether they have been dropped
+    file_target: Option<(Entity, Propagation)>,
+    files_dropped: bool,
+
+    pub(crate) tooltip: TooltipState,
+    pub(crate) drag: DragState,
+
     pub ignore_default_theme: bool,
 }
 
@@ -101,10 +134,14 @@ impl Context {
             current: Entity::root(),
             views: FnvHashMap::default(),
             data: SparseSet::new(),
+            dirty_data: FnvHashSet::default(),
             style: Style::default(),
             cache,
             draw_cache: DrawCache::new(),
             canvases: HashMap::new(),
+            windows: FnvHashMap::default(),
+            closed_windows: Vec::new(),
+            input_window: Entity::root(),
             // environment: Environment::new(),
             event_queue: VecDeque::new(),
             listeners: HashMap::default(),
@@ -116,6 +153,8 @@ impl Context {
             focused: Entity::root(),
             cursor_icon_locked: false,
             resource_manager: ResourceManager::new(),
+            accessibility: AccessibilityState::default(),
+            damage: DamageState::default(),
             text_context: TextContext::default(),
 
             event_proxy: None,
@@ -130,6 +169,12 @@ impl Context {
             double_click: false,
             click_pos: (0.0, 0.0),
 
+            file_target: None,
+            files_dropped: false,
+
+            tooltip: TooltipState::default(),
+            drag: DragState::default(),
+
             ignore_default_theme: false,
         };
 
@@ -222,11 +267,21 @@ impl Context {
         &mut self.modifiers
     }
 
-    /// Mark the application as needing to rerun the draw method
+    /// Mark the application as needing to repaint every window in full
     pub fn need_redraw(&mut self) {
         self.style.needs_redraw = true;
     }
 
+    /// Set how long the cursor has to rest on a view before its tooltip is shown
+    pub fn set_tooltip_delay(&mut self, delay: Duration) {
+        self.tooltip.delay = delay;
+    }
+
+    /// Returns the time at which a pending tooltip should be shown, if there is one
+    pub fn tooltip_deadline(&self) -> Option<Instant> {
+        self.tooltip.deadline()
+    }
+
     /// Mark the application as needing to recompute view styles
     pub fn need_restyle(&mut self) {
         self.style.needs_restyle = true;
@@ -281,9 +336,11 @@ impl Context {
             pseudo_classes.set(PseudoClass::FOCUS, true);
         }
 
+        self.style().restyle(old_focus);
+        self.style().restyle(new_focus);
+        self.style().redraw(old_focus);
+        self.style().redraw(new_focus);
         self.style().needs_relayout = true;
-        self.style().needs_redraw = true;
-        self.style().needs_restyle = true;
     }
 
     /// Sets the active flag of the current entity
@@ -293,9 +350,9 @@ impl Context {
             pseudo_classes.set(PseudoClass::ACTIVE, flag);
         }
 
-        self.style().needs_restyle = true;
+        self.style().restyle(current);
+        self.style().redraw(current);
         self.style().needs_relayout = true;
-        self.style().needs_redraw = true;
     }
 
     /// Sets the hover flag of the current entity
@@ -305,9 +362,9 @@ impl Context {
             pseudo_classes.set(PseudoClass::HOVER, flag);
         }
 
-        self.style().needs_restyle = true;
+        self.style().restyle(current);
+        self.style().redraw(current);
         self.style().needs_relayout = true;
-        self.style().needs_redraw = true;
     }
 
     /// Sets the checked flag of the current entity
@@ -317,9 +374,9 @@ impl Context {
             pseudo_classes.set(PseudoClass::CHECKED, flag);
         }
 
-        self.style().needs_restyle = true;
+        self.style().restyle(current);
+        self.style().redraw(current);
         self.style().needs_relayout = true;
-        self.style().needs_redraw = true;
     }
 
     /// Sets the checked flag of the current entity
@@ -329,9 +386,9 @@ impl Context {
             pseudo_classes.set(PseudoClass::SELECTED, flag);
         }
 
-        self.style().needs_restyle = true;
+        self.style().restyle(current);
+        self.style().redraw(current);
         self.style().needs_relayout = true;
-        self.style().needs_redraw = true;
     }
 
     pub fn toggle_class(&mut self, class_name: &str, applied: bool) {
@@ -348,9 +405,9 @@ impl Context {
             self.style().classes.insert(current, class_list).expect("Failed to insert class name");
         }
 
-        self.need_restyle();
+        self.style().restyle(current);
+        self.style().redraw(current);
         self.style().needs_relayout = true;
-        self.style().needs_redraw = true;
     }
 
     /// Returns true if the current entity is disabled
@@ -375,6 +432,73 @@ impl Context {
         self.captured
     }
 
+    pub fn focused(&self) -> Entity {
+        self.focused
+    }
+
+    /// Starts keeping an accessibility tree for the application.
+    ///
+    /// Windowing backends call this when an accessibility adapter has been attached. Once enabled,
+    /// changes to the tree can be retrieved with [`take_accessibility_update`](Self::take_accessibility_update).
+    pub fn enable_accessibility(&mut self) {
+        self.accessibility.enabled = true;
+        self.style.needs_redraw = true;
+    }
+
+    /// Returns the changes to the accessibility tree since the last call, if there are any.
+    pub fn take_accessibility_update(&mut self) -> Option<AccessTreeUpdate> {
+        self.accessibility.pending.take()
+    }
+
+    /// Returns the accessibility node of an entity, if accessibility is enabled and the entity is
+    /// exported to the accessibility tree.
+    pub fn accessibility_node(&self, entity: Entity) -> Option<&AccessNode> {
+        self.accessibility.nodes.get(&entity)
+    }
+
+    /// Returns true if the entity is the root or a [`Window`](crate::views::Window) view.
+    pub fn is_window(&self, entity: Entity) -> bool {
+        entity == Entity::root() || self.windows.contains_key(&entity)
+    }
+
+    /// Returns the window which an entity is displayed in.
+    pub fn window_of(&self, entity: Entity) -> Entity {
+        if self.windows.is_empty() {
+            return Entity::root();
+        }
+
+        entity
+            .parent_iter(&self.tree)
+            .find(|ancestor| self.windows.contains_key(ancestor))
+            .unwrap_or(Entity::root())
+    }
+
+    /// Returns the windows created with the [`Window`](crate::views::Window) view, along with the
+    /// properties to create them with. The main window is not included.
+    ///
+    /// Windowing backends use this to open a window for every entry which doesn't have one yet.
+    pub fn windows(&self) -> impl Iterator<Item = (Entity, &WindowDescription)> {
+        self.windows.iter().map(|(entity, description)| (*entity, description))
+    }
+
+    /// Adds the canvas of a window, loading any fonts which have already been loaded into the
+    /// other canvases.
+    pub fn add_canvas(&mut self, window: Entity, mut canvas: Canvas) {
+        for data in self.resource_manager.loaded_fonts.iter() {
+            canvas.add_font_mem(data).expect("Failed to load font into canvas");
+        }
+
+        self.canvases.insert(window, canvas);
+    }
+
+    /// Returns the views and canvases of the windows which have been removed since the last call.
+    ///
+    /// Windowing backends use this to close the windows. The canvas must be dropped while the
+    /// graphics context of its window is current, before the window view itself is dropped.
+    pub fn take_closed_windows(&mut self) -> Vec<(Box<dyn ViewHandler>, Option<Canvas>)> {
+        std::mem::take(&mut self.closed_windows)
+    }
+
     /// You should not call this method unless you are writing a windowing backend, in which case
     /// you should consult the existing windowing backends for usage information.
     pub fn set_event_proxy(&mut self, proxy: Box<dyn EventProxy>) {
@@ -426,9 +550,18 @@ impl Context {
         let delete_list = entity.branch_iter(&self.tree).collect::<Vec<_>>();
 
         if !delete_list.is_empty() {
-            self.style.needs_restyle = true;
+            if let Some(parent) = self.tree.get_parent(entity) {
+                self.style.restyle_children(parent);
+            }
             self.style.needs_relayout = true;
-            self.style.needs_redraw = true;
+
+            // The region the removed views were drawn into is repainted without them
+            let window = self.window_of(entity);
+            for removed in delete_list.iter() {
+                if let Some(region) = self.cache.painted.get(*removed).copied() {
+                    self.damage.add(window, region);
+                }
+            }
         }
 
         for entity in delete_list.iter().rev() {
@@ -449,11 +582,44 @@ impl Context {
                 image.observers.remove(entity);
             }
 
+            if self.windows.remove(entity).is_some() {
+                // The canvas of a window belongs to the graphics context owned by the window view,
+                // so both are handed back to the windowing backend to be released together
+                let canvas = self.canvases.remove(entity);
+                if let Some(view) = self.views.remove(entity) {
+                    self.closed_windows.push((view, canvas));
+                }
+                self.damage.remove(*entity);
+                self.resource_manager.remove_window(*entity);
+
+                if self.input_window == *entity {
+                    self.input_window = Entity::root();
+                }
+            }
+
+            self.tooltip.content.remove(entity);
+
+            if self.file_target.map_or(false, |(target, _)| target == *entity) {
+                self.file_target = None;
+            }
+
+            self.drag.targets.remove(entity);
+            if self.drag.target == *entity {
+                self.drag.target = Entity::null();
+            }
+            if self.drag.preview == *entity {
+                self.drag.preview = Entity::null();
+            }
+            if self.drag.source == *entity {
+                self.drag.ended = true;
+            }
+
             self.tree.remove(*entity).expect("");
             self.cache.remove(*entity);
             self.draw_cache.remove(*entity);
             self.style.remove(*entity);
             self.data.remove(*entity);
+            self.dirty_data.remove(entity);
             self.views.remove(entity);
             self.entity_manager.destroy(*entity);
 
@@ -539,25 +705,30 @@ impl Context {
     }
 
     /// Ensure all FontOrId entires are loaded into the contexts and become Ids.
+    ///
+    /// Fonts are loaded into the text context and every canvas. If there is no canvas (e.g. when
+    /// running headless) the text context is enough for text measurement and layout.
     pub fn synchronize_fonts(&mut self) {
-        if let Some(canvas) = self.canvases.get_mut(&Entity::root()) {
-            for (name, font) in self.resource_manager.fonts.iter_mut() {
-                match font {
-                    FontOrId::Font(data) => {
-                        let id1 = canvas
-                            .add_font_mem(&data.clone())
-                            .expect(&format!("Failed to load font file for: {}", name));
-                        let id2 = self.text_context.add_font_mem(&data.clone()).expect("failed");
-                        if id1 != id2 {
-                            panic!(
-                                "Fonts in canvas must have the same id as fonts in the text context"
-                            );
-                        }
-                        *font = FontOrId::Id(id1);
+        for (name, font) in self.resource_manager.fonts.iter_mut() {
+            if let FontOrId::Font(data) = font {
+                let id = self
+                    .text_context
+                    .add_font_mem(data)
+                    .expect(&format!("Failed to load font file for: {}", name));
+
+                for canvas in self.canvases.values_mut() {
+                    let canvas_id = canvas
+                        .add_font_mem(data)
+                        .expect(&format!("Failed to load font file for: {}", name));
+                    if canvas_id != id {
+                        panic!(
+                            "Fonts in canvas must have the same id as fonts in the text context"
+                        );
                     }
-
-                    _ => {}
                 }
+
+                self.resource_manager.loaded_fonts.push(std::mem::take(data));
+                *font = FontOrId::Id(id);
             }
         }
     }
@@ -604,9 +775,14 @@ impl Context {
             | self.style.outer_shadow_v_offset.has_animations()
             | self.style.outer_shadow_blur.has_animations()
             | self.style.outer_shadow_color.has_animations()
+            | self.style.inner_shadow_h_offset.has_animations()
+            | self.style.inner_shadow_v_offset.has_animations()
+            | self.style.inner_shadow_blur.has_animations()
+            | self.style.inner_shadow_color.has_animations()
+            | self.style.background_gradient.has_animations()
             | self.style.font_color.has_animations()
-            | self.style.font_size.has_animations()
-            | if self.style.left.has_animations()
+            | if self.style.font_size.has_animations()
+                | self.style.left.has_animations()
                 | self.style.right.has_animations()
                 | self.style.top.has_animations()
                 | self.style.bottom.has_animations()
@@ -641,52 +817,60 @@ impl Context {
     pub fn apply_animations(&mut self) {
         let time = instant::Instant::now();
 
-        self.style.display.tick(time);
-        self.style.visibility.tick(time);
-        self.style.opacity.tick(time);
-        self.style.rotate.tick(time);
-        self.style.translate.tick(time);
-        self.style.scale.tick(time);
-        self.style.border_width.tick(time);
-        self.style.border_color.tick(time);
-        self.style.border_radius_top_left.tick(time);
-        self.style.border_radius_top_right.tick(time);
-        self.style.border_radius_bottom_left.tick(time);
-        self.style.border_radius_bottom_right.tick(time);
-        self.style.outline_width.tick(time);
-        self.style.outline_color.tick(time);
-        self.style.outline_offset.tick(time);
-        self.style.background_color.tick(time);
-        self.style.outer_shadow_h_offset.tick(time);
-        self.style.outer_shadow_v_offset.tick(time);
-        self.style.outer_shadow_blur.tick(time);
-        self.style.outer_shadow_color.tick(time);
-        self.style.font_color.tick(time);
-        self.style.font_size.tick(time);
-        self.style.left.tick(time);
-        self.style.right.tick(time);
-        self.style.top.tick(time);
-        self.style.bottom.tick(time);
-        self.style.width.tick(time);
-        self.style.height.tick(time);
-        self.style.max_width.tick(time);
-        self.style.max_height.tick(time);
-        self.style.min_width.tick(time);
-        self.style.min_height.tick(time);
-        self.style.min_left.tick(time);
-        self.style.max_left.tick(time);
-        self.style.min_right.tick(time);
-        self.style.max_right.tick(time);
-        self.style.min_top.tick(time);
-        self.style.max_top.tick(time);
-        self.style.min_bottom.tick(time);
-        self.style.max_bottom.tick(time);
-        self.style.row_between.tick(time);
-        self.style.col_between.tick(time);
-        self.style.child_left.tick(time);
-        self.style.child_right.tick(time);
-        self.style.child_top.tick(time);
-        self.style.child_bottom.tick(time);
+        // Views with a playing animation are redrawn, including on the frame the animation ends
+        let redraw = &mut self.style.redraw_entities;
+
+        self.style.display.tick(time, redraw);
+        self.style.visibility.tick(time, redraw);
+        self.style.opacity.tick(time, redraw);
+        self.style.rotate.tick(time, redraw);
+        self.style.translate.tick(time, redraw);
+        self.style.scale.tick(time, redraw);
+        self.style.border_width.tick(time, redraw);
+        self.style.border_color.tick(time, redraw);
+        self.style.border_radius_top_left.tick(time, redraw);
+        self.style.border_radius_top_right.tick(time, redraw);
+        self.style.border_radius_bottom_left.tick(time, redraw);
+        self.style.border_radius_bottom_right.tick(time, redraw);
+        self.style.outline_width.tick(time, redraw);
+        self.style.outline_color.tick(time, redraw);
+        self.style.outline_offset.tick(time, redraw);
+        self.style.background_color.tick(time, redraw);
+        self.style.outer_shadow_h_offset.tick(time, redraw);
+        self.style.outer_shadow_v_offset.tick(time, redraw);
+        self.style.outer_shadow_blur.tick(time, redraw);
+        self.style.outer_shadow_color.tick(time, redraw);
+        self.style.inner_shadow_h_offset.tick(time, redraw);
+        self.style.inner_shadow_v_offset.tick(time, redraw);
+        self.style.inner_shadow_blur.tick(time, redraw);
+        self.style.inner_shadow_color.tick(time, redraw);
+        self.style.background_gradient.tick(time, redraw);
+        self.style.font_color.tick(time, redraw);
+        self.style.font_size.tick(time, redraw);
+        self.style.left.tick(time, redraw);
+        self.style.right.tick(time, redraw);
+        self.style.top.tick(time, redraw);
+        self.style.bottom.tick(time, redraw);
+        self.style.width.tick(time, redraw);
+        self.style.height.tick(time, redraw);
+        self.style.max_width.tick(time, redraw);
+        self.style.max_height.tick(time, redraw);
+        self.style.min_width.tick(time, redraw);
+        self.style.min_height.tick(time, redraw);
+        self.style.min_left.tick(time, redraw);
+        self.style.max_left.tick(time, redraw);
+        self.style.min_right.tick(time, redraw);
+        self.style.max_right.tick(time, redraw);
+        self.style.min_top.tick(time, redraw);
+        self.style.max_top.tick(time, redraw);
+        self.style.min_bottom.tick(time, redraw);
+        self.style.max_bottom.tick(time, redraw);
+        self.style.row_between.tick(time, redraw);
+        self.style.col_between.tick(time, redraw);
+        self.style.child_left.tick(time, redraw);
+        self.style.child_right.tick(time, redraw);
+        self.style.child_top.tick(time, redraw);
+        self.style.child_bottom.tick(time, redraw);
     }
 
     /// Adds a new property animation returning an animation builder
@@ -751,24 +935,20 @@ impl Context {
     ) {
         match self.resource_manager.images.entry(path) {
             Entry::Occupied(mut occ) => {
-                occ.get_mut().image = ImageOrId::Image(
-                    image,
-                    femtovg::ImageFlags::REPEAT_X | femtovg::ImageFlags::REPEAT_Y,
-                );
-                occ.get_mut().dirty = true;
-                occ.get_mut().retention_policy = policy;
+                let stored = occ.get_mut();
+                stored.image = image;
+                stored.flags = femtovg::ImageFlags::REPEAT_X | femtovg::ImageFlags::REPEAT_Y;
+                // The old image is deleted from each canvas when its window is next drawn
+                self.resource_manager.released_images.extend(stored.ids.drain());
+                stored.dirty = true;
+                stored.retention_policy = policy;
             }
             Entry::Vacant(vac) => {
-                vac.insert(StoredImage {
-                    image: ImageOrId::Image(
-                        image,
-                        femtovg::ImageFlags::REPEAT_X | femtovg::ImageFlags::REPEAT_Y,
-                    ),
-                    retention_policy: policy,
-                    used: true,
-                    dirty: false,
-                    observers: HashSet::new(),
-                });
+                vac.insert(StoredImage::new(
+                    image,
+                    femtovg::ImageFlags::REPEAT_X | femtovg::ImageFlags::REPEAT_Y,
+                    policy,
+                ));
             }
         }
         self.style.needs_redraw = true;
@@ -792,12 +972,12 @@ impl Context {
         std::thread::spawn(move || target(&mut cxp));
     }
 
-    /// For each binding or data observer, check if its data has changed, and if so, rerun its
-    /// builder/body.
+    /// For each binding or data observer of a model or view which may have changed since the last
+    /// update, check if its data has changed, and if so, rerun its builder/body.
     pub fn process_data_updates(&mut self) {
-        let mut observers: Vec<Entity> = Vec::new();
+        let mut observers: HashSet<Entity> = HashSet::new();
 
-        for entity in self.tree.into_iter() {
+        for entity in std::mem::take(&mut self.dirty_data) {
             if let Some(model_store) = self.data.get_mut(entity) {
                 for (_, model) in model_store.data.iter() {
                     for lens in model_store.lenses_dup.iter_mut() {
@@ -813,16 +993,14 @@ impl Context {
                     }
                 }
 
-                for lens in model_store.lenses_dup.iter_mut() {
-                    if let Some(view_handler) = self.views.get(&entity) {
+                if let Some(view_handler) = self.views.get(&entity) {
+                    for lens in model_store.lenses_dup.iter_mut() {
                         if lens.update_view(view_handler) {
                             observers.extend(lens.observers().iter())
                         }
                     }
-                }
 
-                for (_, lens) in model_store.lenses_dedup.iter_mut() {
-                    if let Some(view_handler) = self.views.get(&entity) {
+                    for (_, lens) in model_store.lenses_dedup.iter_mut() {
                         if lens.update_view(view_handler) {
                             observers.extend(lens.observers().iter())
                         }
@@ -838,6 +1016,11 @@ impl Context {
             }
         }
 
+        // Observers are updated in tree order, so an outer binding is rebuilt before the bindings
+        // inside of it, which are skipped if the rebuild removed them
+        let mut observers = observers.into_iter().collect::<Vec<_>>();
+        self.tree.sort_in_tree_order(&mut observers);
+
         for observer in observers.iter() {
             if let Some(mut view) = self.views.remove(observer) {
                 let prev = self.current;
@@ -850,32 +1033,38 @@ impl Context {
     }
 
     pub fn process_style_updates(&mut self) {
-        // Not ideal
-        let tree = self.tree.clone();
+        tooltip_system(self);
+        drag_system(self);
+
+        apply_inline_inheritance(self);
 
-        apply_inline_inheritance(self, &tree);
+        // Media queries are re-evaluated whenever the window is resized or its scale factor changes
+        let resolution = if self.style.dpi_factor > 0.0 { self.style.dpi_factor } else { 1.0 };
+        let environment = MediaEnvironment {
+            width: self.cache.get_width(Entity::root()) / resolution as f32,
+            height: self.cache.get_height(Entity::root()) / resolution as f32,
+            resolution,
+        };
 
-        if self.style.needs_restyle {
-            apply_styles(self, &tree);
-            self.style.needs_restyle = false;
+        if self.style.update_media_queries(environment) {
+            self.style.needs_restyle = true;
         }
 
-        apply_shared_inheritance(self, &tree);
+        apply_styles(self);
+
+        apply_shared_inheritance(self);
     }
 
     /// Massages the style system until everything is coherent
     pub fn process_visual_updates(&mut self) {
-        // Not ideal
-        let tree = self.tree.clone();
-
         image_system(self);
 
-        apply_z_ordering(self, &tree);
-        apply_visibility(self, &tree);
+        apply_z_ordering(self);
+        apply_visibility(self);
 
         // Layout
         if self.style.needs_relayout {
-            apply_text_constraints(self, &tree);
+            apply_text_constraints(self);
 
             // hack!
             let mut store = (Style::default(), TextContext::default(), ResourceManager::default());
@@ -888,52 +1077,252 @@ impl Context {
             std::mem::swap(&mut store.1, &mut self.text_context);
             std::mem::swap(&mut store.2, &mut self.resource_manager);
             self.style.needs_relayout = false;
+
+            // Windows are laid out as part of the tree but drawn into their own canvas, so their
+            // contents are moved to the origin of the window
+            for window in self.tree.into_iter().filter(|entity| self.windows.contains_key(entity)) {
+                let offset_x = self.cache.get_posx(window);
+                let offset_y = self.cache.get_posy(window);
+
+                for entity in window.branch_iter(&self.tree) {
+                    let posx = self.cache.get_posx(entity);
+                    let posy = self.cache.get_posy(entity);
+                    self.cache.set_posx(entity, posx - offset_x);
+                    self.cache.set_posy(entity, posy - offset_y);
+                }
+            }
+
+            position_tooltip(self);
         }
 
-        apply_transform(self, &tree);
+        apply_transform(self);
         apply_hover(self);
-        apply_clipping(self, &tree);
+        apply_clipping(self);
 
         // Emit any geometry changed events
-        geometry_changed(self, &tree);
+        geometry_changed(self);
+
+        if self.accessibility.enabled
+            && (self.style.needs_redraw || !self.style.redraw_entities.is_empty())
+        {
+            accessibility_system(self);
+        }
+
+        damage_system(self);
+    }
+
+    /// Returns true if any window has a region which needs to be repainted.
+    ///
+    /// Windowing backends use this after updating to decide whether to draw.
+    pub fn has_damage(&self) -> bool {
+        self.damage.regions.values().any(|regions| !regions.is_empty())
     }
 
+    /// Returns the regions of a window which will be repainted when it's next drawn, which is empty
+    /// if the window is unchanged. The regions don't overlap.
+    pub fn damage(&self, window: Entity) -> &[BoundingBox] {
+        self.damage.regions.get(&window).map_or(&[], |regions| regions.as_slice())
+    }
+
+    /// Sets the age of the screen buffer of a window, which is the number of draws since it last
+    /// held the contents of the window, or `None` if it isn't known.
+    ///
+    /// Only the regions repainted since a buffer of known age was last drawn are copied to it from
+    /// the image of the window, which is only correct if the buffer keeps its contents between
+    /// draws. A buffer which is drawn on every frame has an age of 1. The age is not known by
+    /// default, and the whole image is copied on every draw.
+    pub fn set_buffer_age(&mut self, window: Entity, age: Option<usize>) {
+        match age {
+            Some(age) => self.damage.buffer_ages.insert(window, age),
+            None => self.damage.buffer_ages.remove(&window),
+        };
+    }
+
+    /// Tints the regions of the windows which are repainted on each frame, cycling through a few
+    /// colors so that repeated repaints of the same region flash. This is meant for debugging.
+    pub fn set_paint_flashing(&mut self, enabled: bool) {
+        self.damage.paint_flashing = enabled;
+        self.style.needs_redraw = true;
+    }
+
+    /// Draws the main window.
+    ///
+    /// Use [`Context::draw_window`] to also get the regions which were repainted.
     pub fn draw(&mut self) {
-        let canvas = self.canvases.get_mut(&Entity::root()).unwrap();
+        self.draw_window(Entity::root());
+    }
+
+    /// Draws the contents of a window into its canvas, returning the regions of the window which
+    /// were repainted.
+    ///
+    /// The window is painted into an image which keeps its contents between frames, so only the
+    /// regions damaged since the last draw are repainted, along with every view which overlaps
+    /// them. The image is then copied to the window, in full unless the age of its screen buffer
+    /// was set with [`Context::set_buffer_age`]. Backends which can present part of a window only
+    /// need to present the returned regions, which are empty if nothing was repainted.
+    ///
+    /// The graphics context of the window must be current.
+    pub fn draw_window(&mut self, window: Entity) -> Vec<BoundingBox> {
+        let window_width = self.cache.get_width(window);
+        let window_height = self.cache.get_height(window);
+        let window_bounds = BoundingBox { x: 0.0, y: 0.0, w: window_width, h: window_height };
+
+        let mut damage = self.damage.regions.remove(&window).unwrap_or_default();
 
-        let window_width = self.cache.get_width(Entity::root());
-        let window_height = self.cache.get_height(Entity::root());
+        let uploads =
+            self.resource_manager.images_to_upload(window, |entity| self.window_of(entity));
 
+        let canvas = match self.canvases.get_mut(&window) {
+            Some(canvas) => canvas,
+            None => return Vec::new(),
+        };
         canvas.set_size(window_width as u32, window_height as u32, 1.0);
-        let clear_color =
-            self.style.background_color.get(Entity::root()).cloned().unwrap_or(Color::white());
-        canvas.clear_rect(0, 0, window_width as u32, window_height as u32, clear_color.into());
 
+        // Images are uploaded to the canvas of each window which shows them, and deleted from it
+        // once they're released, while its graphics context is current
+        self.resource_manager.released_images.retain(|(released, image)| {
+            if *released == window {
+                canvas.delete_image(*image);
+            }
+
+            *released != window
+        });
+
+        for name in uploads {
+            if let Some(image) = self.resource_manager.images.get_mut(&name) {
+                image.id(window, canvas);
+            }
+        }
+
+        // The image loses its contents when it's recreated for a new window size
+        let size = (window_width.max(1.0) as usize, window_height.max(1.0) as usize);
+        let image = match self.damage.images.get(&window).copied() {
+            Some(image) if canvas.image_size(image).ok() == Some(size) => image,
+            existing => {
+                if let Some(image) = existing {
+                    canvas.delete_image(image);
+                }
+
+                let image = canvas
+                    .create_image_empty(
+                        size.0,
+                        size.1,
+                        PixelFormat::Rgba8,
+                        ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED,
+                    )
+                    .expect("Failed to create image");
+
+                self.damage.images.insert(window, image);
+                self.damage.history.remove(&window);
+                damage = vec![window_bounds];
+                image
+            }
+        };
+
+        // Partly damaged pixels are repainted in full
+        let damage: Vec<BoundingBox> = damage
+            .iter()
+            .map(|damage| {
+                let damage = damage.intersection(&window_bounds);
+                BoundingBox::from_min_max(
+                    damage.left().floor(),
+                    damage.top().floor(),
+                    damage.right().ceil(),
+                    damage.bottom().ceil(),
+                )
+            })
+            .filter(|damage| damage.w > 0.0 && damage.h > 0.0)
+            .collect();
+
+        for region in damage.iter() {
+            self.repaint(window, image, *region);
+        }
+
+        let copied = self.damage.present(window, &damage).unwrap_or_else(|| vec![window_bounds]);
+
+        let canvas = match self.canvases.get_mut(&window) {
+            Some(canvas) => canvas,
+            None => return Vec::new(),
+        };
+        canvas.set_render_target(RenderTarget::Screen);
+        canvas.reset_transform();
+        canvas.reset_scissor();
+
+        for region in copied {
+            canvas.clear_rect(
+                region.x as u32,
+                region.y as u32,
+                region.w as u32,
+                region.h as u32,
+                femtovg::Color::rgba(0, 0, 0, 0),
+            );
+
+            let mut path = Path::new();
+            path.rect(region.x, region.y, region.w, region.h);
+            let mut paint = Paint::image(image, 0.0, 0.0, window_width, window_height, 0.0, 1.0);
+            paint.set_anti_alias(false);
+            canvas.fill_path(&mut path, paint);
+        }
+
+        // The tint is only drawn over the screen, as the image keeps the contents of the window
+        if self.damage.paint_flashing {
+            for region in damage.iter() {
+                let (r, g, b) =
+                    PAINT_FLASHING_COLORS[self.damage.flashes % PAINT_FLASHING_COLORS.len()];
+                self.damage.flashes += 1;
+
+                let mut path = Path::new();
+                path.rect(region.x, region.y, region.w, region.h);
+                canvas.fill_path(&mut path, Paint::color(femtovg::Color::rgba(r, g, b, 64)));
+            }
+        }
+
+        canvas.flush();
+
+        damage
+    }
+
+    // Repaints the views of a window which overlap a damaged region into the image of the window
+    fn repaint(&mut self, window: Entity, image: ImageId, damage: BoundingBox) {
         // filter for widgets that should be drawn
         let tree_iter = self.tree.into_iter();
         let mut draw_tree: Vec<Entity> = tree_iter
             .filter(|&entity| {
-                entity != Entity::root()
-                    && self.cache.get_visibility(entity) != Visibility::Invisible
-                    && self.cache.get_display(entity) != Display::None
-                    && !self.tree.is_ignored(entity)
-                    && self.cache.get_opacity(entity) > 0.0
-                    && {
-                        let bounds = self.cache.get_bounds(entity);
-                        !(bounds.x > window_width
-                            || bounds.y > window_height
-                            || bounds.x + bounds.w <= 0.0
-                            || bounds.y + bounds.h <= 0.0)
-                    }
+                entity != window
+                    && self.window_of(entity) == window
+                    && self
+                        .cache
+                        .painted
+                        .get(entity)
+                        .map_or(false, |region| region.intersects(&damage))
             })
             .collect();
 
+        let canvas = if let Some(canvas) = self.canvases.get_mut(&window) {
+            canvas
+        } else {
+            return;
+        };
+
+        let render_target = RenderTarget::Image(image);
+        canvas.set_render_target(render_target);
+
+        let clear_color =
+            self.style.background_color.get(window).cloned().unwrap_or(Color::white());
+        canvas.clear_rect(
+            damage.x as u32,
+            damage.y as u32,
+            damage.w as u32,
+            damage.h as u32,
+            clear_color.into(),
+        );
+
         // Sort the tree by z order
         draw_tree.sort_by_cached_key(|entity| self.cache.get_z_index(*entity));
 
         for entity in draw_tree.into_iter() {
-            // Apply clipping
-            let clip_region = self.cache.get_clip_region(entity);
+            // Apply clipping, which includes the damaged region
+            let clip_region = self.cache.get_clip_region(entity).intersection(&damage);
 
             // Skips drawing views with zero-sized clip regions
             // This skips calling the `draw` method of the view
@@ -973,6 +1362,8 @@ impl Context {
                         text_context: &self.text_context,
                         modifiers: &self.modifiers,
                         mouse: &self.mouse,
+                        render_target,
+                        window,
                     },
                     canvas,
                 );
@@ -990,8 +1381,28 @@ impl Context {
             // paint.set_line_width(1.0);
             // canvas.stroke_path(&mut path, paint);
         }
+    }
 
-        canvas.flush();
+    /// Dispatches an event which came from the OS window of `window`.
+    ///
+    /// Mouse positions are relative to that window, and keyboard input moves focus into the window
+    /// if the focused view is in a different one.
+    pub fn dispatch_window_event(&mut self, window: Entity, event: WindowEvent) {
+        self.input_window = window;
+
+        if matches!(
+            event,
+            WindowEvent::KeyDown(_, _)
+                | WindowEvent::KeyUp(_, _)
+                | WindowEvent::CharInput(_)
+                | WindowEvent::ImePreedit(_, _)
+                | WindowEvent::ImeCommit(_)
+        ) && self.window_of(self.focused) != window
+        {
+            self.with_current(window, |cx| cx.focus());
+        }
+
+        self.dispatch_system_event(event);
     }
 
     /// This method is in charge of receiving raw WindowEvents and dispatching them to the
@@ -1005,6 +1416,7 @@ impl Context {
                 self.mouse.cursory = *y;
 
                 apply_hover(self);
+                update_drop_target(self);
 
                 self.dispatch_direct_or_hovered(event, self.captured, false);
             }
@@ -1016,6 +1428,9 @@ impl Context {
                     _ => {}
                 }
 
+                // Pressing a mouse button hides the tooltip until the cursor moves to another view
+                self.tooltip.dismissed = true;
+
                 let new_click_time = Instant::now();
                 let click_duration = new_click_time - self.click_time;
                 let new_click_pos = (self.mouse.cursorx, self.mouse.cursory);
@@ -1060,6 +1475,8 @@ impl Context {
                         self.mouse.left.pos_up = (self.mouse.cursorx, self.mouse.cursory);
                         self.mouse.left.released = self.hovered;
                         self.mouse.left.state = MouseButtonState::Released;
+
+                        end_drag(self, true);
                     }
                     MouseButton::Right => {
                         self.mouse.right.pos_up = (self.mouse.cursorx, self.mouse.cursory);
@@ -1075,6 +1492,28 @@ impl Context {
                 }
                 self.dispatch_direct_or_hovered(event, self.captured, true);
             }
+            WindowEvent::FileHovered(_) => {
+                // The files of a new drag go to the view under the cursor, and every file of the
+                // same drag goes to that view, since the cursor doesn't move during an OS drag
+                if self.files_dropped {
+                    self.file_target = None;
+                    self.files_dropped = false;
+                }
+
+                let (target, propagation) = self.file_event_target();
+                self.event_queue.push_back(Event::new(event).target(target).propagate(propagation));
+            }
+            WindowEvent::FileDropped(_) => {
+                let (target, propagation) = self.file_event_target();
+                self.files_dropped = true;
+                self.event_queue.push_back(Event::new(event).target(target).propagate(propagation));
+            }
+            WindowEvent::FileHoverCancelled => {
+                let (target, propagation) = self.file_event_target();
+                self.file_target = None;
+                self.files_dropped = false;
+                self.event_queue.push_back(Event::new(event).target(target).propagate(propagation));
+            }
             WindowEvent::MouseScroll(_, _) => {
                 self.event_queue.push_back(Event::new(event).target(self.hovered));
             }
@@ -1106,6 +1545,11 @@ impl Context {
                     }
                 }
 
+                if *code == Code::Escape && self.drag.is_dragging() {
+                    end_drag(self, false);
+                    return;
+                }
+
                 if *code == Code::F5 {
                     self.reload_styles().unwrap();
                 }
@@ -1115,6 +1559,8 @@ impl Context {
                     if let Some(pseudo_classes) = self.style().pseudo_classes.get_mut(focused) {
                         pseudo_classes.set(PseudoClass::FOCUS, false);
                     }
+                    self.style().restyle(focused);
+                    self.style().redraw(focused);
 
                     if self.modifiers.contains(Modifiers::SHIFT) {
                         let prev_focused = if let Some(prev_focused) =
@@ -1161,20 +1607,37 @@ impl Context {
                         pseudo_classes.set(PseudoClass::FOCUS, true);
                     }
 
+                    self.style().restyle(focused);
+                    self.style().redraw(focused);
                     self.style().needs_relayout = true;
-                    self.style().needs_redraw = true;
-                    self.style().needs_restyle = true;
                 }
 
                 self.event_queue.push_back(Event::new(event).target(self.focused));
             }
-            WindowEvent::KeyUp(_, _) | WindowEvent::CharInput(_) => {
+            WindowEvent::KeyUp(_, _)
+            | WindowEvent::CharInput(_)
+            | WindowEvent::ImePreedit(_, _)
+            | WindowEvent::ImeCommit(_) => {
                 self.event_queue.push_back(Event::new(event).target(self.focused));
             }
             _ => {}
         }
     }
 
+    // Returns the view which files dragged onto the window are sent to, choosing it if the files
+    // haven't been sent anywhere yet
+    fn file_event_target(&mut self) -> (Entity, Propagation) {
+        let captured = self.captured;
+        let hovered = self.hovered;
+        *self.file_target.get_or_insert_with(|| {
+            if captured != Entity::null() {
+                (captured, Propagation::Direct)
+            } else {
+                (hovered, Propagation::Up)
+            }
+        })
+    }
+
     fn dispatch_direct_or_hovered(&mut self, event: WindowEvent, target: Entity, root: bool) {
         if target != Entity::null() {
             self.event_queue
@@ -0,0 +1,25 @@
+/// Events sent to the views taking part in a drag and drop.
+///
+/// A drag is started by a view with [`EventContext::start_drag`], usually through the
+/// [`draggable`](crate::prelude::Actions::draggable) modifier. Views become drop targets with the
+/// [`on_drop`](crate::prelude::Actions::on_drop) or [`accept_drop`](crate::prelude::Handle::accept_drop)
+/// modifiers. Drop targets are sent these events directly, with the source of the drag as the
+/// origin, and have the `:drop-target` pseudo-class while the cursor is over them.
+///
+/// This type is part of the prelude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragEvent {
+    /// Sent to a drop target when the cursor moves onto it with a payload it accepts.
+    Enter,
+    /// Sent to the drop target under the cursor when the cursor moves, with the position of the
+    /// cursor in window coordinates.
+    Over(f32, f32),
+    /// Sent to a drop target when the cursor moves off of it or the drag is cancelled.
+    Leave,
+    /// Sent to the drop target under the cursor when the payload is dropped onto it. No
+    /// [`DragEvent::Leave`] is sent after a drop.
+    Drop,
+    /// Sent to the source of a drag when it ends, with whether the payload was dropped onto a drop
+    /// target.
+    End(bool),
+}
@@ -51,6 +51,9 @@ pub struct Event {
     pub meta: EventMeta,
     /// The message of the event
     message: Box<dyn Message>,
+    /// Whether the message was mapped by a handler the event was last sent to, which means the
+    /// handler may have changed its state
+    pub(crate) matched: bool,
 }
 
 impl Debug for Event {
@@ -74,7 +77,7 @@ impl Event {
     where
         M: Message,
     {
-        Event { meta: Default::default(), message: Box::new(message) }
+        Event { meta: Default::default(), message: Box::new(message), matched: false }
     }
 
     /// Sets the target of the event.
@@ -115,6 +118,7 @@ impl Event {
         F: FnOnce(&M, &mut EventMeta),
     {
         if let Some(message) = self.message.downcast() {
+            self.matched = true;
             (f)(message, &mut self.meta);
         }
     }
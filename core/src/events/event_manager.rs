@@ -47,6 +47,14 @@ impl EventManager {
                 }
             });
 
+            // Focus requests from assistive technology are handled here so that every view can
+            // be focused without having to handle the action itself
+            event.map(|access_action, meta| {
+                if let AccessAction::Focus = access_action {
+                    context.with_current(meta.target, |cx| cx.focus());
+                }
+            });
+
             // if event.trace {
             //     println!("Event: {:?}", event);
             // }
@@ -66,6 +74,7 @@ impl EventManager {
             for entity in listeners {
                 if let Some(listener) = context.listeners.remove(&entity) {
                     if let Some(mut event_handler) = context.views.remove(&entity) {
+                        event.matched = false;
                         context.with_current(entity, |context| {
                             (listener)(
                                 event_handler.as_mut(),
@@ -75,6 +84,10 @@ impl EventManager {
                         });
 
                         context.views.insert(entity, event_handler);
+
+                        if event.matched {
+                            context.dirty_data.insert(entity);
+                        }
                     }
 
                     context.listeners.insert(entity, listener);
@@ -140,7 +153,11 @@ impl EventManager {
     }
 }
 
+// Sends an event to the view and models of an entity. If any of them handle the message, the
+// entity is marked so that the lenses on its data are checked in the next data update.
 fn visit_entity(cx: &mut Context, entity: Entity, event: &mut Event) {
+    event.matched = false;
+
     if let Some(mut view) = cx.views.remove(&entity) {
         cx.with_current(entity, |cx| {
             view.event(&mut EventContext::new(cx), event);
@@ -167,4 +184,8 @@ fn visit_entity(cx: &mut Context, entity: Entity, event: &mut Event) {
             }
         }
     }
+
+    if event.matched {
+        cx.dirty_data.insert(entity);
+    }
 }
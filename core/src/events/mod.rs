@@ -43,3 +43,6 @@ pub use event::{Event, EventMeta, Message, Propagation};
 
 mod event_handler;
 pub use event_handler::ViewHandler;
+
+mod drag;
+pub use drag::DragEvent;
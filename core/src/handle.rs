@@ -1,4 +1,6 @@
+use std::any::Any;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 use morphorm::{LayoutType, PositionType, Units};
 
@@ -13,7 +15,7 @@ macro_rules! set_style {
 
                 // TODO - Split this out
                 cx.need_relayout();
-                cx.need_redraw();
+                cx.style().redraw(entity);
             });
 
             // self.cx.style().$name.insert(self.entity, value.get_val(self.cx).into());
@@ -43,6 +45,10 @@ impl<'a, T> Handle<'a, T> {
 
     pub fn ignore(self) -> Self {
         self.cx.tree().set_ignored(self.entity, true);
+        // The children of the view become the layout children of its parent
+        if let Some(parent) = self.cx.tree().get_parent(self.entity) {
+            self.cx.style().restyle_children(parent);
+        }
         self.focusable(false)
     }
 
@@ -58,6 +64,7 @@ impl<'a, T> Handle<'a, T> {
             .and_then(|view_handler| view_handler.downcast_mut::<T>())
         {
             (f)(view);
+            self.cx.dirty_data.insert(self.entity);
         }
 
         self
@@ -83,6 +90,8 @@ impl<'a, T> Handle<'a, T> {
     {
         let entity = self.entity();
         Binding::new(self.cx, lens, move |cx, data| {
+            // The closure may modify the view, which could be the source of other bindings
+            cx.dirty_data.insert(entity);
             let new_handle = Handle { entity, p: Default::default(), cx };
 
             new_handle.cx.set_current(new_handle.entity);
@@ -93,7 +102,7 @@ impl<'a, T> Handle<'a, T> {
 
     pub fn id(self, id: &str) -> Self {
         self.cx.style().ids.insert(self.entity, id.to_owned()).expect("Could not insert id");
-        self.cx.need_restyle();
+        self.cx.style().restyle(self.entity);
 
         self
     }
@@ -101,7 +110,7 @@ impl<'a, T> Handle<'a, T> {
     pub fn cursor(self, cursor_icon: CursorIcon) -> Self {
         self.cx.style().cursor.insert(self.entity, cursor_icon);
 
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -111,7 +120,7 @@ impl<'a, T> Handle<'a, T> {
             class_list.insert(name.to_string());
         }
 
-        self.cx.need_restyle();
+        self.cx.style().restyle(self.entity);
 
         self
     }
@@ -127,7 +136,42 @@ impl<'a, T> Handle<'a, T> {
                 }
             }
 
-            cx.need_restyle();
+            cx.style().restyle(entity);
+        });
+
+        self
+    }
+
+    /// Sets a custom property on the view, which stylesheets can reference with `var()` on the
+    /// view and its descendants. The name includes the leading `--`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // With `.swatch { background-color: var(--accent, gray); }` in a stylesheet
+    /// VStack::new(cx, |cx| {
+    ///     Element::new(cx).class("swatch");
+    /// })
+    /// .variable("--accent", "#3a86ff");
+    /// ```
+    pub fn variable<U: ToString>(self, name: &str, value: impl Res<U>) -> Self {
+        let name = name.to_owned();
+        value.set_or_bind(self.cx, self.entity, move |cx, entity, value| {
+            let value = value.to_string();
+
+            if let Some(variables) = cx.style().inline_variables.get_mut(entity) {
+                if let Some(variable) = variables.iter_mut().find(|(n, _)| *n == name) {
+                    variable.1 = value;
+                } else {
+                    variables.push((name.clone(), value));
+                }
+            } else {
+                cx.style()
+                    .inline_variables
+                    .insert(entity, vec![(name.clone(), value)])
+                    .expect("Failed to set variable");
+            }
+
+            cx.style().restyle(entity);
         });
 
         self
@@ -136,7 +180,7 @@ impl<'a, T> Handle<'a, T> {
     pub fn font(self, font_name: &str) -> Self {
         self.cx.style().font.insert(self.entity, font_name.to_owned());
 
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -151,7 +195,7 @@ impl<'a, T> Handle<'a, T> {
                 cx.style().pseudo_classes.insert(entity, pseudoclass).unwrap();
             }
 
-            cx.need_restyle();
+            cx.style().restyle(entity);
         });
 
         // let state = state.get_val(self.cx);
@@ -171,7 +215,7 @@ impl<'a, T> Handle<'a, T> {
     pub fn disabled(self, state: impl Res<bool>) -> Self {
         state.set_or_bind(self.cx, self.entity, |cx, entity, val| {
             cx.style().disabled.insert(entity, val);
-            cx.need_restyle();
+            cx.style().restyle(entity);
         });
 
         self
@@ -184,13 +228,13 @@ impl<'a, T> Handle<'a, T> {
                     cx.style().text.insert(entity, val.to_string());
 
                     cx.need_relayout();
-                    cx.need_redraw();
+                    cx.style().redraw(entity);
                 }
             } else {
                 cx.style().text.insert(entity, val.to_string());
 
                 cx.need_relayout();
-                cx.need_redraw();
+                cx.style().redraw(entity);
             }
         });
 
@@ -204,22 +248,62 @@ impl<'a, T> Handle<'a, T> {
                 if prev_data != &val {
                     cx.style().image.insert(entity, val);
 
-                    cx.need_redraw();
+                    cx.style().redraw(entity);
                 }
             } else {
                 cx.style().image.insert(entity, val);
 
-                cx.need_redraw();
+                cx.style().redraw(entity);
             }
         });
 
         self
     }
 
+    /// Makes the view a drop target which accepts payloads of type `P` for which the predicate
+    /// returns true.
+    ///
+    /// The view is sent [`DragEvent`]s while an accepted payload is dragged over it.
+    pub fn accept_drop<P, F>(self, predicate: F) -> Self
+    where
+        P: 'static,
+        F: 'static + Fn(&P) -> bool,
+    {
+        self.cx.drag.targets.insert(
+            self.entity,
+            Box::new(move |payload: &dyn Any| {
+                payload.downcast_ref::<P>().map_or(false, |p| (predicate)(p))
+            }),
+        );
+
+        self
+    }
+
+    /// Shows a tooltip with the given text when the cursor rests on the view.
+    pub fn tooltip<U: ToString>(self, value: impl Res<U>) -> Self {
+        value.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.style().tooltip.insert(entity, val.to_string()).expect("Failed to set tooltip");
+        });
+
+        self
+    }
+
+    /// Shows a tooltip built by the given closure when the cursor rests on the view.
+    ///
+    /// The closure is called each time the tooltip is shown, with the tooltip as the current view.
+    pub fn tooltip_content<F>(self, content: F) -> Self
+    where
+        F: 'static + Fn(&mut Context),
+    {
+        self.cx.tooltip.content.insert(self.entity, Rc::new(content));
+
+        self
+    }
+
     pub fn z_order(self, value: i32) -> Self {
         self.cx.style().z_order.insert(self.entity, value);
 
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -227,7 +311,7 @@ impl<'a, T> Handle<'a, T> {
     pub fn overflow(self, value: Overflow) -> Self {
         self.cx.style().overflow.insert(self.entity, value);
 
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -237,7 +321,7 @@ impl<'a, T> Handle<'a, T> {
             cx.style().display.insert(entity, val.into());
 
             cx.need_relayout();
-            cx.need_redraw();
+            cx.style().redraw(entity);
         });
 
         self
@@ -247,7 +331,7 @@ impl<'a, T> Handle<'a, T> {
         value.set_or_bind(self.cx, self.entity, move |cx, entity, v| {
             cx.style().visibility.insert(entity, v.into());
 
-            cx.need_redraw();
+            cx.style().redraw(entity);
         });
 
         self
@@ -259,8 +343,6 @@ impl<'a, T> Handle<'a, T> {
             abilities.set(Abilities::HOVERABLE, state);
         }
 
-        self.cx.need_restyle();
-
         self
     }
 
@@ -273,8 +355,6 @@ impl<'a, T> Handle<'a, T> {
             }
         }
 
-        self.cx.need_restyle();
-
         self
     }
 
@@ -283,7 +363,86 @@ impl<'a, T> Handle<'a, T> {
             abilities.set(Abilities::KEYBOARD_NAVIGATABLE, state);
         }
 
-        self.cx.need_restyle();
+        self
+    }
+
+    // Accessibility
+
+    /// Sets the role of the view in the accessibility tree. Views without a role are not exported.
+    pub fn role(self, role: Role) -> Self {
+        self.cx.style().role.insert(self.entity, role).expect("Failed to set role");
+
+        self.cx.style().redraw(self.entity);
+
+        self
+    }
+
+    /// Sets the name read out by assistive technology. If no name is set, buttons and similar
+    /// views use the text of their contents.
+    pub fn accessible_name<U: ToString>(self, value: impl Res<U>) -> Self {
+        value.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.style().name.insert(entity, val.to_string());
+
+            cx.style().redraw(entity);
+        });
+
+        self
+    }
+
+    /// Sets the value of the view as text, e.g. the contents of a textbox.
+    pub fn accessible_value<U: ToString>(self, value: impl Res<U>) -> Self {
+        value.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.style()
+                .accessible_value
+                .insert(entity, val.to_string())
+                .expect("Failed to set accessible value");
+
+            cx.style().redraw(entity);
+        });
+
+        self
+    }
+
+    /// Sets the value of the view as a number, e.g. the position of a slider.
+    pub fn numeric_value<U: Into<f64>>(self, value: impl Res<U>) -> Self {
+        value.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.style()
+                .numeric_value
+                .insert(entity, val.into())
+                .expect("Failed to set numeric value");
+
+            cx.style().redraw(entity);
+        });
+
+        self
+    }
+
+    /// Marks the view as expandable and sets whether it is currently expanded.
+    pub fn expanded(self, state: impl Res<bool>) -> Self {
+        state.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.style().expanded.insert(entity, val).expect("Failed to set expanded state");
+
+            cx.style().redraw(entity);
+        });
+
+        self
+    }
+
+    /// Sets whether the view is selected, which is matched by the `:selected` pseudo-class and
+    /// reported to assistive technologies.
+    pub fn selected(self, state: impl Res<bool>) -> Self {
+        state.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            if let Some(pseudo_classes) = cx.style().pseudo_classes.get_mut(entity) {
+                pseudo_classes.set(PseudoClass::SELECTED, val);
+            } else {
+                let mut pseudoclass = PseudoClass::empty();
+                pseudoclass.set(PseudoClass::SELECTED, val);
+                cx.style().pseudo_classes.insert(entity, pseudoclass).unwrap();
+            }
+
+            cx.style().restyle(entity);
+            cx.style().redraw(entity);
+        });
 
         self
     }
@@ -295,7 +454,7 @@ impl<'a, T> Handle<'a, T> {
         self.cx.style().child_bottom.insert(self.entity, value);
 
         self.cx.need_relayout();
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -306,7 +465,7 @@ impl<'a, T> Handle<'a, T> {
         self.cx.style().border_radius_bottom_left.insert(self.entity, value);
         self.cx.style().border_radius_bottom_right.insert(self.entity, value);
 
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -318,7 +477,7 @@ impl<'a, T> Handle<'a, T> {
         self.cx.style().bottom.insert(self.entity, value);
 
         self.cx.need_relayout();
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -328,7 +487,7 @@ impl<'a, T> Handle<'a, T> {
         self.cx.style().height.insert(self.entity, value);
 
         self.cx.need_relayout();
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -338,7 +497,7 @@ impl<'a, T> Handle<'a, T> {
         self.cx.style().min_height.insert(self.entity, value);
 
         self.cx.need_relayout();
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -348,7 +507,7 @@ impl<'a, T> Handle<'a, T> {
         self.cx.style().max_height.insert(self.entity, value);
 
         self.cx.need_relayout();
-        self.cx.need_redraw();
+        self.cx.style().redraw(self.entity);
 
         self
     }
@@ -374,6 +533,15 @@ impl<'a, T> Handle<'a, T> {
     set_style!(background_color, Color);
     set_style!(background_image, String);
 
+    /// Sets the gradient background layers of the view, with the first layer drawn on top.
+    pub fn background_gradient(self, layers: Vec<Gradient>) -> Self {
+        self.cx.style().background_gradient.insert(self.entity, layers);
+
+        self.cx.style().redraw(self.entity);
+
+        self
+    }
+
     set_style!(layout_type, LayoutType);
     set_style!(position_type, PositionType);
 
@@ -9,7 +9,10 @@ pub fn apply_hover(cx: &mut Context) {
     let cursorx = cx.mouse().cursorx;
     let cursory = cx.mouse().cursory;
 
-    let mut hovered_widget = Entity::root();
+    // Only views in the window the cursor is in can be hovered
+    let window = cx.input_window;
+
+    let mut hovered_widget = window;
 
     for entity in draw_tree.into_iter() {
         // Skip invisible widgets
@@ -53,7 +56,8 @@ pub fn apply_hover(cx: &mut Context) {
 
         let clip_region = cx.cache().get_clip_region(entity);
 
-        if tx >= posx
+        if cx.window_of(entity) == window
+            && tx >= posx
             && tx >= clip_region.x
             && tx < (posx + width)
             && tx < (clip_region.x + clip_region.w)
@@ -81,6 +85,7 @@ pub fn apply_hover(cx: &mut Context) {
                 if let Some(pseudo_class) = cx.style().pseudo_classes.get_mut(entity) {
                     pseudo_class.set(PseudoClass::OVER, true);
                 }
+                cx.style().restyle(entity);
             }
         } else {
             if cx
@@ -99,6 +104,7 @@ pub fn apply_hover(cx: &mut Context) {
                 if let Some(pseudo_class) = cx.style().pseudo_classes.get_mut(entity) {
                     pseudo_class.set(PseudoClass::OVER, false);
                 }
+                cx.style().restyle(entity);
             }
         }
     }
@@ -125,7 +131,7 @@ pub fn apply_hover(cx: &mut Context) {
         if !cx.is_cursor_icon_locked()
             && !cx.style().disabled.get(hovered_widget).cloned().unwrap_or_default()
         {
-            cx.emit(WindowEvent::SetCursor(cursor));
+            cx.emit_to(window, WindowEvent::SetCursor(cursor));
         }
 
         // Set current hovered pseudoclass to true
@@ -143,7 +149,9 @@ pub fn apply_hover(cx: &mut Context) {
         cx.event_queue.push_back(Event::new(WindowEvent::MouseLeave).target(cx.hovered()));
 
         cx.hovered = hovered_widget;
+        cx.tooltip.hover_time = instant::Instant::now();
 
-        cx.style().needs_restyle = true;
+        cx.style().restyle(hovered_widget);
+        cx.style().restyle(hovered);
     }
 }
@@ -26,3 +26,36 @@ impl KeyChord {
         Self { modifiers, code }
     }
 }
+
+impl std::fmt::Display for KeyChord {
+    /// Formats the key chord the way it's shown next to menu entries, such as `Ctrl+Shift+S`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use vizia_core::prelude::*;
+    /// #
+    /// let key_chord = KeyChord::new(Modifiers::CTRL | Modifiers::SHIFT, Code::KeyS);
+    /// assert_eq!(key_chord.to_string(), "Ctrl+Shift+S");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (modifier, name) in [
+            (Modifiers::CTRL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::LOGO, "Super"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+
+        let code = self.code.to_string();
+        let key = code
+            .strip_prefix("Key")
+            .or_else(|| code.strip_prefix("Digit"))
+            .or_else(|| code.strip_prefix("Arrow"))
+            .unwrap_or(&code);
+        write!(f, "{}", key)
+    }
+}
@@ -140,4 +140,41 @@ mod test {
         let vec: Vec<Entity> = iter.collect();
         assert_eq!(vec, ground);
     }
+
+    #[test]
+    fn test_layout_siblings() {
+        let mut tree = Tree::new();
+        let mut mgr: IdManager<Entity> = IdManager::new();
+
+        let a = mgr.create();
+        let b = mgr.create();
+        let ba = mgr.create();
+        let bb = mgr.create();
+        let bc = mgr.create();
+        let c = mgr.create();
+        let baa = mgr.create();
+
+        tree.add(a, Entity::root()).unwrap();
+        tree.add(b, Entity::root()).unwrap();
+        tree.add(ba, b).unwrap();
+        tree.add(baa, ba).unwrap();
+        tree.add(bb, b).unwrap();
+        tree.add(bc, b).unwrap();
+        tree.add(c, Entity::root()).unwrap();
+        tree.set_ignored(b, true);
+        tree.set_ignored(ba, true);
+        // An ignored entity without children is skipped over
+        tree.set_ignored(bc, true);
+
+        let ground = vec![a, baa, bb, c];
+        let forward =
+            std::iter::successors(Some(a), |entity| tree.get_next_layout_sibling(*entity))
+                .collect::<Vec<_>>();
+        assert_eq!(forward, ground);
+
+        let backward =
+            std::iter::successors(Some(c), |entity| tree.get_prev_layout_sibling(*entity))
+                .collect::<Vec<_>>();
+        assert_eq!(backward, ground.into_iter().rev().collect::<Vec<_>>());
+    }
 }
@@ -12,20 +12,23 @@ pub(crate) use iter::{LayoutChildIterator, LayoutTreeIterator};
 pub use morphorm::GeometryChanged;
 use morphorm::{Cache, Hierarchy};
 
-pub(crate) fn geometry_changed(cx: &mut Context, tree: &Tree) {
-    for node in tree.down_iter() {
-        let geometry_changed = cx.cache().geometry_changed(node);
+pub(crate) fn geometry_changed(cx: &mut Context) {
+    for node in cx.tree.down_iter() {
+        let geometry_changed = cx.cache.geometry_changed(node);
         if !geometry_changed.is_empty() {
             cx.event_queue.push_back(
                 Event::new(WindowEvent::GeometryChanged(geometry_changed))
                     .target(node)
                     .propagate(Propagation::Up),
             );
+
+            // Views which moved or were resized are repainted where they were and where they are
+            cx.style.redraw(node);
         }
 
-        cx.cache().set_geo_changed(node, morphorm::GeometryChanged::POSX_CHANGED, false);
-        cx.cache().set_geo_changed(node, morphorm::GeometryChanged::POSY_CHANGED, false);
-        cx.cache().set_geo_changed(node, morphorm::GeometryChanged::WIDTH_CHANGED, false);
-        cx.cache().set_geo_changed(node, morphorm::GeometryChanged::HEIGHT_CHANGED, false);
+        cx.cache.set_geo_changed(node, morphorm::GeometryChanged::POSX_CHANGED, false);
+        cx.cache.set_geo_changed(node, morphorm::GeometryChanged::POSY_CHANGED, false);
+        cx.cache.set_geo_changed(node, morphorm::GeometryChanged::WIDTH_CHANGED, false);
+        cx.cache.set_geo_changed(node, morphorm::GeometryChanged::HEIGHT_CHANGED, false);
     }
 }
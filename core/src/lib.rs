@@ -2,6 +2,7 @@
 #[macro_use]
 extern crate serde;
 
+pub mod accessibility;
 pub mod animation;
 pub mod cache;
 pub mod context;
@@ -41,13 +42,16 @@ pub mod image {
 
 /// Members which we recommend you wildcard-import.
 pub mod prelude {
-    pub use super::animation::{AnimExt, Animation, AnimationBuilder};
+    pub use super::accessibility::{AccessAction, Role};
+    pub use super::animation::{
+        AnimExt, Animation, AnimationBuilder, StepPosition, TimingFunction,
+    };
     pub use super::context::{
         Context, ContextProxy, DataContext, DrawContext, EventContext, ProxyEmitError,
     };
     pub use super::entity::Entity;
     pub use super::environment::{Environment, EnvironmentEvent};
-    pub use super::events::{Event, Message, Propagation};
+    pub use super::events::{DragEvent, Event, Message, Propagation};
     pub use super::handle::Handle;
     pub use super::input::{
         KeyChord, Keymap, KeymapEntry, KeymapEvent, Modifiers, MouseButton, MouseButtonState,
@@ -65,8 +69,9 @@ pub mod prelude {
     pub use vizia_derive::{Data, Lens};
 
     pub use super::style::{
-        Abilities, BorderCornerShape, Color, Display, GradientDirection, GradientStop,
-        LinearGradient, Opacity, Overflow, PseudoClass, Visibility,
+        Abilities, BorderCornerShape, Color, ConicGradient, Display, Gradient, GradientDirection,
+        GradientStop, LinearGradient, Opacity, Overflow, PseudoClass, RadialGradient,
+        RadialGradientSize, Visibility,
     };
 
     pub use keyboard_types::{Code, Key};
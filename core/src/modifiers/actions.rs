@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
 
+use super::{Draggable, DropTarget};
 use crate::events::ViewHandler;
 use morphorm::GeometryChanged;
 
@@ -546,6 +547,19 @@ pub trait Actions<'a> {
     fn on_geo_changed<F>(self, action: F) -> Handle<'a, Geo<Self::View>>
     where
         F: 'static + Fn(&mut EventContext, GeometryChanged);
+
+    /// Lets the view be dragged, carrying the payload returned by the closure when the drag starts.
+    fn draggable<T, F>(self, payload: F) -> Handle<'a, Draggable<Self::View>>
+    where
+        T: 'static,
+        F: 'static + Fn(&mut EventContext) -> T;
+
+    /// Makes the view a drop target for payloads of type `T`, calling the action when one is dropped
+    /// onto it.
+    fn on_drop<T, F>(self, action: F) -> Handle<'a, DropTarget<Self::View, T>>
+    where
+        T: 'static,
+        F: 'static + Fn(&mut EventContext, &T);
 }
 
 impl<'a, V: View> Actions<'a> for Handle<'a, V> {
@@ -612,6 +626,25 @@ impl<'a, V: View> Actions<'a> for Handle<'a, V> {
     {
         Geo::new(self, action)
     }
+
+    fn draggable<T, F>(self, payload: F) -> Handle<'a, Draggable<Self::View>>
+    where
+        T: 'static,
+        F: 'static + Fn(&mut EventContext) -> T,
+    {
+        Draggable::new(self, move |cx| {
+            let payload = (payload)(cx);
+            cx.start_drag(payload);
+        })
+    }
+
+    fn on_drop<T, F>(self, action: F) -> Handle<'a, DropTarget<Self::View, T>>
+    where
+        T: 'static,
+        F: 'static + Fn(&mut EventContext, &T),
+    {
+        DropTarget::new(self, action)
+    }
 }
 
 // pub trait ViewModifers {
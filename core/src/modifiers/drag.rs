@@ -0,0 +1,184 @@
+use std::any::Any;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::events::ViewHandler;
+use crate::prelude::*;
+use crate::systems::drag_system::DRAG_THRESHOLD;
+
+// Draggable
+pub struct Draggable<V: View> {
+    view: Box<dyn ViewHandler>,
+    start: Option<Box<dyn Fn(&mut EventContext)>>,
+    preview: Option<Rc<dyn Fn(&mut Context)>>,
+    // Where the left mouse button was pressed on the view, until it's released
+    press: Option<(f32, f32)>,
+    dragged: bool,
+
+    p: PhantomData<V>,
+}
+
+impl<V: View> Draggable<V> {
+    pub fn new<'a, F>(handle: Handle<'a, V>, start: F) -> Handle<'a, Draggable<V>>
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(mut view) = handle.cx.views.remove(&handle.entity) {
+            if view.downcast_ref::<V>().is_some() {
+                let item = Self {
+                    view,
+                    start: Some(Box::new(start)),
+                    preview: None,
+                    press: None,
+                    dragged: false,
+                    p: Default::default(),
+                };
+
+                handle.cx.views.insert(handle.entity, Box::new(item));
+            } else {
+                if let Some(draggable) = view.downcast_mut::<Draggable<V>>() {
+                    draggable.start = Some(Box::new(start));
+                }
+                handle.cx.views.insert(handle.entity, view);
+            }
+        }
+
+        Handle { entity: handle.entity, p: Default::default(), cx: handle.cx }
+    }
+}
+
+impl<V: View> View for Draggable<V> {
+    fn element(&self) -> Option<&'static str> {
+        self.view.element()
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        self.view.event(cx, event);
+
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                if cx.current() != cx.hovered()
+                    && !cx.hovered().is_descendant_of(cx.tree, cx.current())
+                {
+                    return;
+                }
+
+                self.press = Some((cx.mouse.cursorx, cx.mouse.cursory));
+                self.dragged = false;
+                cx.capture();
+            }
+
+            WindowEvent::MouseMove(x, y) => {
+                if let Some((pressx, pressy)) = self.press {
+                    let threshold = DRAG_THRESHOLD * cx.style.dpi_factor as f32;
+                    let distance = ((*x - pressx).powi(2) + (*y - pressy).powi(2)).sqrt();
+                    if !self.dragged && distance >= threshold {
+                        if let Some(start) = &self.start {
+                            (start)(cx);
+                        }
+
+                        cx.drag.preview_content = self.preview.clone();
+                        self.dragged = true;
+                    }
+                }
+            }
+
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if self.press.take().is_some() {
+                    cx.release();
+                }
+            }
+
+            _ => {}
+        });
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        self.view.draw(cx, canvas);
+    }
+}
+
+impl<'a, V: View> Handle<'a, Draggable<V>> {
+    /// Sets the contents of the preview which follows the cursor while the view is dragged.
+    ///
+    /// By default the preview is an empty box the size of the view.
+    pub fn drag_preview<F>(self, content: F) -> Self
+    where
+        F: 'static + Fn(&mut Context),
+    {
+        if let Some(view) = self.cx.views.get_mut(&self.entity) {
+            if let Some(draggable) = view.downcast_mut::<Draggable<V>>() {
+                draggable.preview = Some(Rc::new(content));
+            }
+        }
+
+        self
+    }
+}
+
+// DropTarget
+pub struct DropTarget<V: View, T: 'static> {
+    view: Box<dyn ViewHandler>,
+    action: Option<Box<dyn Fn(&mut EventContext, &T)>>,
+
+    p: PhantomData<V>,
+}
+
+impl<V: View, T: 'static> DropTarget<V, T> {
+    pub fn new<'a, F>(handle: Handle<'a, V>, action: F) -> Handle<'a, DropTarget<V, T>>
+    where
+        F: 'static + Fn(&mut EventContext, &T),
+    {
+        if let Some(mut view) = handle.cx.views.remove(&handle.entity) {
+            if view.downcast_ref::<V>().is_some() {
+                let item = Self { view, action: Some(Box::new(action)), p: Default::default() };
+
+                handle.cx.views.insert(handle.entity, Box::new(item));
+            } else {
+                if let Some(drop_target) = view.downcast_mut::<DropTarget<V, T>>() {
+                    drop_target.action = Some(Box::new(action));
+                }
+                handle.cx.views.insert(handle.entity, view);
+            }
+        }
+
+        // Accept any payload of the right type unless the view already has a filter
+        if !handle.cx.drag.targets.contains_key(&handle.entity) {
+            handle
+                .cx
+                .drag
+                .targets
+                .insert(handle.entity, Box::new(|payload: &dyn Any| payload.is::<T>()));
+        }
+
+        Handle { entity: handle.entity, p: Default::default(), cx: handle.cx }
+    }
+}
+
+impl<V: View, T: 'static> View for DropTarget<V, T> {
+    fn element(&self) -> Option<&'static str> {
+        self.view.element()
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        self.view.event(cx, event);
+
+        event.map(|drag_event, _| match drag_event {
+            DragEvent::Drop => {
+                if let Some(payload) = cx.drag.payload.take() {
+                    if let (Some(action), Some(payload)) =
+                        (&self.action, payload.downcast_ref::<T>())
+                    {
+                        (action)(cx, payload);
+                    }
+                }
+            }
+
+            _ => {}
+        });
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        self.view.draw(cx, canvas);
+    }
+}
@@ -2,3 +2,6 @@
 
 mod actions;
 pub use actions::*;
+
+mod drag;
+pub use drag::{Draggable, DropTarget};
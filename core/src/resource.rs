@@ -10,38 +10,47 @@ use std::collections::{HashMap, HashSet};
 use unic_langid::LanguageIdentifier;
 
 pub(crate) struct StoredImage {
-    pub image: ImageOrId,
+    /// The decoded image, which is kept so that it can be uploaded to the canvases of windows
+    /// which show it later.
+    pub image: image::DynamicImage,
+    pub flags: femtovg::ImageFlags,
+    /// The id of the image in the canvas of each window it has been uploaded to.
+    pub ids: HashMap<Entity, femtovg::ImageId>,
     pub retention_policy: ImageRetentionPolicy,
     pub used: bool,
     pub dirty: bool,
     pub observers: HashSet<Entity>,
 }
 
-pub enum ImageOrId {
-    Image(image::DynamicImage, femtovg::ImageFlags),
-    Id(femtovg::ImageId, (u32, u32)), // need to be able to get dimensions without a canvas
-}
-
-impl ImageOrId {
-    pub fn id(&mut self, canvas: &mut Canvas) -> femtovg::ImageId {
-        match self {
-            ImageOrId::Image(image, flags) => {
-                let image_ref: &image::DynamicImage = image.borrow();
-                let res = canvas
-                    .create_image(femtovg::ImageSource::try_from(image_ref).unwrap(), *flags)
-                    .unwrap();
-                *self = ImageOrId::Id(res, image.dimensions());
-                res
-            }
-            ImageOrId::Id(i, _) => *i,
+impl StoredImage {
+    pub fn new(
+        image: image::DynamicImage,
+        flags: femtovg::ImageFlags,
+        retention_policy: ImageRetentionPolicy,
+    ) -> Self {
+        Self {
+            image,
+            flags,
+            ids: HashMap::new(),
+            retention_policy,
+            used: true,
+            dirty: false,
+            observers: HashSet::new(),
         }
     }
 
+    /// Returns the id of the image in the canvas of a window, uploading it to the canvas if it
+    /// hasn't been yet. The graphics context of the window must be current.
+    pub fn id(&mut self, window: Entity, canvas: &mut Canvas) -> femtovg::ImageId {
+        let image: &image::DynamicImage = self.image.borrow();
+        let flags = self.flags;
+        *self.ids.entry(window).or_insert_with(|| {
+            canvas.create_image(femtovg::ImageSource::try_from(image).unwrap(), flags).unwrap()
+        })
+    }
+
     pub fn dimensions(&self) -> (u32, u32) {
-        match self {
-            ImageOrId::Image(image, _) => image.dimensions(),
-            ImageOrId::Id(_, dim) => *dim,
-        }
+        self.image.dimensions()
     }
 }
 
@@ -65,7 +74,13 @@ pub struct ResourceManager {
     pub stylesheets: Vec<String>, // Stylesheets refer to a file path
     pub themes: Vec<String>,      // Themes are the string content stylesheets
     pub(crate) images: HashMap<String, StoredImage>,
+    /// Images which were replaced or evicted, along with the windows whose canvases they still
+    /// have to be deleted from.
+    pub(crate) released_images: Vec<(Entity, femtovg::ImageId)>,
     pub fonts: HashMap<String, FontOrId>,
+    /// The data of the fonts which have been loaded, in the order they were loaded in, so that
+    /// canvases created later get the same font ids.
+    pub(crate) loaded_fonts: Vec<Vec<u8>>,
     pub translations: HashMap<LanguageIdentifier, FluentBundle<FluentResource>>,
     pub language: LanguageIdentifier,
 
@@ -82,7 +97,9 @@ impl ResourceManager {
             stylesheets: Vec::new(),
             themes: Vec::new(),
             fonts: HashMap::new(),
+            loaded_fonts: Vec::new(),
             images: HashMap::new(),
+            released_images: Vec::new(),
             translations: HashMap::from([(
                 LanguageIdentifier::default(),
                 FluentBundle::new(vec![LanguageIdentifier::default()]),
@@ -150,17 +167,52 @@ impl ResourceManager {
     }
 
     pub fn evict_unused_images(&mut self) {
-        self.images.retain(|name, img| match img.retention_policy {
-            ImageRetentionPolicy::DropWhenUnusedForOneFrame => {
-                if !img.used {
-                    println!("Evict image: {}", name);
+        let released_images = &mut self.released_images;
+        self.images.retain(|name, img| {
+            let keep = match img.retention_policy {
+                ImageRetentionPolicy::DropWhenUnusedForOneFrame => {
+                    if !img.used {
+                        println!("Evict image: {}", name);
+                    }
+                    img.used
                 }
-                img.used
-            }
 
-            ImageRetentionPolicy::DropWhenNoObservers => !img.observers.is_empty(),
+                ImageRetentionPolicy::DropWhenNoObservers => !img.observers.is_empty(),
 
-            ImageRetentionPolicy::Forever => true,
+                ImageRetentionPolicy::Forever => true,
+            };
+
+            if !keep {
+                released_images.extend(img.ids.drain());
+            }
+
+            keep
         });
     }
+
+    /// Returns the names of the images shown in a window which haven't been uploaded to its
+    /// canvas yet.
+    pub(crate) fn images_to_upload(
+        &self,
+        window: Entity,
+        window_of: impl Fn(Entity) -> Entity,
+    ) -> Vec<String> {
+        self.images
+            .iter()
+            .filter(|(_, img)| {
+                !img.ids.contains_key(&window)
+                    && img.observers.iter().any(|observer| window_of(*observer) == window)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Forgets the images uploaded to the canvas of a window which has been closed.
+    pub(crate) fn remove_window(&mut self, window: Entity) {
+        for img in self.images.values_mut() {
+            img.ids.remove(&window);
+        }
+
+        self.released_images.retain(|(released, _)| *released != window);
+    }
 }
@@ -44,6 +44,7 @@ where
         cx.tree().add(id, current).expect("Failed to add to tree");
         cx.cache().add(id).expect("Failed to add to cache");
         cx.style().add(id);
+        cx.style().restyle_children(current);
 
         let ancestors = cx.current().parent_iter(cx.tree()).collect::<HashSet<_>>();
         let new_ancestors = id.parent_iter(cx.tree()).collect::<Vec<_>>();
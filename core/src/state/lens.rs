@@ -1,7 +1,9 @@
 use std::any::TypeId;
+use std::cell::Cell;
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 use std::ops::Deref;
+use std::rc::Rc;
 
 use crate::prelude::*;
 
@@ -229,6 +231,93 @@ where
     }
 }
 
+/// `Lens` to the item of a `Vec<T>` with a given key, which keeps pointing to the same item when
+/// the items of the vector are reordered.
+pub struct ByKey<T, K> {
+    key: K,
+    key_fn: Rc<dyn Fn(&T) -> K>,
+    // Where the item was last found, which is checked first
+    hint: Rc<Cell<usize>>,
+}
+
+impl<T, K> ByKey<T, K> {
+    pub fn new(key: K, key_fn: Rc<dyn Fn(&T) -> K>) -> Self {
+        Self { key, key_fn, hint: Rc::new(Cell::new(0)) }
+    }
+
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<T, K: Clone> Clone for ByKey<T, K> {
+    fn clone(&self) -> Self {
+        Self { key: self.key.clone(), key_fn: self.key_fn.clone(), hint: self.hint.clone() }
+    }
+}
+
+impl<T: 'static, K: 'static + Clone + PartialEq> Lens for ByKey<T, K> {
+    type Source = Vec<T>;
+    type Target = T;
+
+    fn view<O, F: FnOnce(Option<&Self::Target>) -> O>(&self, source: &Self::Source, map: F) -> O {
+        let hint = self.hint.get();
+        let index = match source.get(hint) {
+            Some(item) if (self.key_fn)(item) == self.key => Some(hint),
+            _ => source.iter().position(|item| (self.key_fn)(item) == self.key),
+        };
+
+        if let Some(index) = index {
+            self.hint.set(index);
+        }
+
+        map(index.and_then(|index| source.get(index)))
+    }
+}
+
+/// `Lens` to a node of a tree stored as a `Vec<T>` of root nodes, where `children` returns the
+/// children of a node. The node is found by its index among the roots followed by its index among
+/// the children of each of its ancestors.
+pub struct ByPath<T> {
+    path: Rc<Vec<usize>>,
+    children: Rc<dyn Fn(&T) -> &Vec<T>>,
+}
+
+impl<T> ByPath<T> {
+    pub fn new(path: Vec<usize>, children: Rc<dyn Fn(&T) -> &Vec<T>>) -> Self {
+        Self { path: Rc::new(path), children }
+    }
+
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+}
+
+impl<T> Clone for ByPath<T> {
+    fn clone(&self) -> Self {
+        Self { path: self.path.clone(), children: self.children.clone() }
+    }
+}
+
+impl<T: 'static> Lens for ByPath<T> {
+    type Source = Vec<T>;
+    type Target = T;
+
+    fn view<O, F: FnOnce(Option<&Self::Target>) -> O>(&self, source: &Self::Source, map: F) -> O {
+        let mut nodes = source;
+        let mut node = None;
+        for index in self.path.iter() {
+            node = nodes.get(*index);
+            match node {
+                Some(n) => nodes = (self.children)(n),
+                None => break,
+            }
+        }
+
+        map(node)
+    }
+}
+
 pub struct StaticLens<T: 'static> {
     data: &'static T,
 }
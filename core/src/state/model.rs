@@ -61,6 +61,8 @@ pub trait Model: 'static + Sized {
     /// }
     /// ```
     fn build(self, cx: &mut Context) {
+        cx.dirty_data.insert(cx.current());
+
         if let Some(data_list) = cx.data.get_mut(cx.current()) {
             data_list.data.insert(TypeId::of::<Self>(), Box::new(self));
         } else {
@@ -81,6 +83,9 @@ pub trait Model: 'static + Sized {
 
     /// Respond to events in order to mutate the model data.
     ///
+    /// Bindings to the model are only checked for changes after it has been given a message which
+    /// it maps with [`Event::map`], so the model should only be mutated in response to a message.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1,3 +1,5 @@
+use fnv::FnvHashSet;
+
 use crate::animation::{AnimationState, Interpolator};
 use crate::id::GenerationalId;
 use crate::prelude::*;
@@ -369,17 +371,21 @@ where
         }
     }
 
-    pub fn tick(&mut self, time: instant::Instant) {
+    /// Advances the playing animations to the given time, and adds the entities they're playing on
+    /// to `redraw`.
+    pub fn tick(&mut self, time: instant::Instant, redraw: &mut FnvHashSet<Entity>) {
         for state in self.active_animations.iter_mut() {
             // If the animation is already finished then return false
             if state.t0 == 1.0 {
                 continue;
             }
 
+            redraw.extend(state.entities.iter());
+
             let start = state.keyframes.first().unwrap();
             let end = state.keyframes.last().unwrap();
 
-            if start.1 == end.1 {
+            if state.keyframes.iter().all(|keyframe| keyframe.1 == start.1) {
                 state.t0 = 1.0;
                 state.output = Some(end.1.clone());
                 continue;
@@ -395,7 +401,7 @@ where
 
             if state.t >= 1.0 {
                 //Animation is finished
-                state.output = Some(T::interpolate(&start.1, &end.1, 1.0));
+                state.output = state.value_at(1.0);
 
                 if !state.persistent {
                     //state.output = Some(T::interpolate(&start.1, &end.1, 0.0));
@@ -405,9 +411,9 @@ where
                     state.t = 1.0;
                 }
             } else if state.t <= 0.0 {
-                state.output = Some(start.1.clone());
+                state.output = state.value_at(0.0);
             } else {
-                state.output = Some(T::interpolate(&start.1, &end.1, state.t));
+                state.output = state.value_at(state.t);
             }
 
             //println!("Tick: {:?}", state.get_output());
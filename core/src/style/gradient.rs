@@ -1,12 +1,15 @@
+use crate::animation::Interpolator;
 use crate::prelude::*;
 
 /// A stop in a gradient, defined by a position and a color.
 ///
+/// The position is a percentage or a distance in pixels along the gradient line. Stops with an
+/// `Auto` position are spaced evenly between the stops around them.
+///
 /// This type is part of the prelude.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct GradientStop {
     // Position of the gradient stop
-    // TODO - it doesn't make sense for this to be in Units
     pub position: Units,
     // Colour of the gradient stop
     pub color: Color,
@@ -27,6 +30,8 @@ pub enum GradientDirection {
     RightToLeft,
     TopToBottom,
     BottomToTop,
+    /// An angle in degrees, measured clockwise from pointing up.
+    Angle(f32),
 }
 
 impl Default for GradientDirection {
@@ -35,6 +40,19 @@ impl Default for GradientDirection {
     }
 }
 
+impl GradientDirection {
+    /// Returns the angle of the direction in degrees, measured clockwise from pointing up.
+    pub fn angle(&self) -> f32 {
+        match self {
+            GradientDirection::LeftToRight => 90.0,
+            GradientDirection::RightToLeft => 270.0,
+            GradientDirection::TopToBottom => 180.0,
+            GradientDirection::BottomToTop => 0.0,
+            GradientDirection::Angle(angle) => *angle,
+        }
+    }
+}
+
 /// Describes a linear gradient.
 ///
 /// This type is part of the prelude.
@@ -57,13 +75,446 @@ impl LinearGradient {
         self
     }
 
-    pub fn get_stops(&self, _parent_length: f32) -> Vec<(f32, Color)> {
-        self.stops
-            .iter()
-            .map(|stop| {
-                //println!("Stop: {:?}", stop.position.value_or(parent_length, 0.0));
-                (stop.position.value_or(1.0, 0.0), stop.color)
-            })
-            .collect::<Vec<_>>()
+    /// Returns the start and end points of the gradient line for a box of the given size,
+    /// relative to the top-left corner of the box.
+    ///
+    /// As in CSS, the line is long enough for the corners of the box to get the colors of the
+    /// first and last stops.
+    pub fn line(&self, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        let angle = self.direction.angle().to_radians();
+        let (dx, dy) = (angle.sin(), -angle.cos());
+        let half_length = (width * dx.abs() + height * dy.abs()) / 2.0;
+
+        let (center_x, center_y) = (width / 2.0, height / 2.0);
+
+        (
+            center_x - dx * half_length,
+            center_y - dy * half_length,
+            center_x + dx * half_length,
+            center_y + dy * half_length,
+        )
+    }
+
+    /// Returns the stops as offsets between 0 and 1 along a gradient line of the given length.
+    pub fn get_stops(&self, length: f32) -> Vec<(f32, Color)> {
+        resolve_stops(&self.stops, length)
+    }
+}
+
+/// How far a radial gradient extends from its center.
+///
+/// This type is part of the prelude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RadialGradientSize {
+    ClosestSide,
+    FarthestSide,
+    ClosestCorner,
+    FarthestCorner,
+    Radius(Units),
+}
+
+impl Default for RadialGradientSize {
+    fn default() -> Self {
+        RadialGradientSize::FarthestCorner
+    }
+}
+
+/// Describes a radial gradient.
+///
+/// Radial gradients are always circular. Stop positions are measured outwards from the center.
+///
+/// This type is part of the prelude.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient {
+    // Size of the gradient
+    pub size: RadialGradientSize,
+    // Position of the center of the gradient, relative to the top-left corner of the view
+    pub position: (Units, Units),
+    // Stops of the gradient
+    pub stops: Vec<GradientStop>,
+}
+
+impl Default for RadialGradient {
+    fn default() -> Self {
+        Self {
+            size: RadialGradientSize::default(),
+            position: (Units::Percentage(50.0), Units::Percentage(50.0)),
+            stops: Vec::new(),
+        }
+    }
+}
+
+impl RadialGradient {
+    pub fn new(size: RadialGradientSize) -> Self {
+        Self { size, ..Default::default() }
+    }
+
+    pub fn position(mut self, x: Units, y: Units) -> Self {
+        self.position = (x, y);
+
+        self
+    }
+
+    pub fn add_stop(mut self, stop: GradientStop) -> Self {
+        self.stops.push(stop);
+
+        self
+    }
+
+    /// Returns the center and radius of the gradient for a box of the given size, relative to the
+    /// top-left corner of the box. Pixel values are multiplied by `scale`.
+    pub fn circle(&self, width: f32, height: f32, scale: f32) -> (f32, f32, f32) {
+        let (x, y) = resolve_position(self.position, width, height, scale);
+
+        let (dx_min, dx_max) = (x.abs().min((width - x).abs()), x.abs().max((width - x).abs()));
+        let (dy_min, dy_max) = (y.abs().min((height - y).abs()), y.abs().max((height - y).abs()));
+
+        let radius = match self.size {
+            RadialGradientSize::ClosestSide => dx_min.min(dy_min),
+            RadialGradientSize::FarthestSide => dx_max.max(dy_max),
+            RadialGradientSize::ClosestCorner => dx_min.hypot(dy_min),
+            RadialGradientSize::FarthestCorner => dx_max.hypot(dy_max),
+            RadialGradientSize::Radius(radius) => resolve_length(radius, width, scale),
+        };
+
+        (x, y, radius)
+    }
+
+    /// Returns the stops as offsets between 0 and 1 along a gradient ray of the given length.
+    pub fn get_stops(&self, radius: f32) -> Vec<(f32, Color)> {
+        resolve_stops(&self.stops, radius)
+    }
+}
+
+/// Describes a conic gradient, which sweeps clockwise around a center point.
+///
+/// Stop positions are percentages of a full turn.
+///
+/// This type is part of the prelude.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicGradient {
+    // Angle the gradient starts at in degrees, measured clockwise from pointing up
+    pub angle: f32,
+    // Position of the center of the gradient, relative to the top-left corner of the view
+    pub position: (Units, Units),
+    // Stops of the gradient
+    pub stops: Vec<GradientStop>,
+}
+
+impl Default for ConicGradient {
+    fn default() -> Self {
+        Self {
+            angle: 0.0,
+            position: (Units::Percentage(50.0), Units::Percentage(50.0)),
+            stops: Vec::new(),
+        }
+    }
+}
+
+impl ConicGradient {
+    pub fn new(angle: f32) -> Self {
+        Self { angle, ..Default::default() }
+    }
+
+    pub fn position(mut self, x: Units, y: Units) -> Self {
+        self.position = (x, y);
+
+        self
+    }
+
+    pub fn add_stop(mut self, stop: GradientStop) -> Self {
+        self.stops.push(stop);
+
+        self
+    }
+
+    /// Returns the center of the gradient for a box of the given size, relative to the top-left
+    /// corner of the box. Pixel values are multiplied by `scale`.
+    pub fn center(&self, width: f32, height: f32, scale: f32) -> (f32, f32) {
+        resolve_position(self.position, width, height, scale)
+    }
+
+    /// Returns the stops as offsets between 0 and 1 of a full turn.
+    pub fn get_stops(&self) -> Vec<(f32, Color)> {
+        resolve_stops(&self.stops, 1.0)
+    }
+}
+
+/// A gradient which can be used as a background layer.
+///
+/// This type is part of the prelude.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gradient {
+    Linear(LinearGradient),
+    Radial(RadialGradient),
+    Conic(ConicGradient),
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Gradient::Linear(LinearGradient::default())
+    }
+}
+
+impl From<LinearGradient> for Gradient {
+    fn from(gradient: LinearGradient) -> Self {
+        Gradient::Linear(gradient)
+    }
+}
+
+impl From<RadialGradient> for Gradient {
+    fn from(gradient: RadialGradient) -> Self {
+        Gradient::Radial(gradient)
+    }
+}
+
+impl From<ConicGradient> for Gradient {
+    fn from(gradient: ConicGradient) -> Self {
+        Gradient::Conic(gradient)
+    }
+}
+
+impl Interpolator for GradientStop {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        GradientStop {
+            position: Units::interpolate(&start.position, &end.position, t),
+            color: Color::interpolate(start.color, end.color, t as f64),
+        }
+    }
+}
+
+// Stops are interpolated pairwise, gradients with a different number of stops jump to the end
+fn interpolate_stops(start: &[GradientStop], end: &[GradientStop], t: f32) -> Vec<GradientStop> {
+    if start.len() == end.len() {
+        start.iter().zip(end.iter()).map(|(s, e)| GradientStop::interpolate(s, e, t)).collect()
+    } else {
+        end.to_vec()
+    }
+}
+
+fn interpolate_position(start: (Units, Units), end: (Units, Units), t: f32) -> (Units, Units) {
+    (Units::interpolate(&start.0, &end.0, t), Units::interpolate(&start.1, &end.1, t))
+}
+
+impl Interpolator for LinearGradient {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        let direction = if start.direction == end.direction {
+            end.direction.clone()
+        } else {
+            GradientDirection::Angle(f32::interpolate(
+                &start.direction.angle(),
+                &end.direction.angle(),
+                t,
+            ))
+        };
+
+        LinearGradient { direction, stops: interpolate_stops(&start.stops, &end.stops, t) }
+    }
+}
+
+impl Interpolator for RadialGradient {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        let size = match (start.size, end.size) {
+            (RadialGradientSize::Radius(s), RadialGradientSize::Radius(e)) => {
+                RadialGradientSize::Radius(Units::interpolate(&s, &e, t))
+            }
+            (_, size) => size,
+        };
+
+        RadialGradient {
+            size,
+            position: interpolate_position(start.position, end.position, t),
+            stops: interpolate_stops(&start.stops, &end.stops, t),
+        }
+    }
+}
+
+impl Interpolator for ConicGradient {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        ConicGradient {
+            angle: f32::interpolate(&start.angle, &end.angle, t),
+            position: interpolate_position(start.position, end.position, t),
+            stops: interpolate_stops(&start.stops, &end.stops, t),
+        }
+    }
+}
+
+impl Interpolator for Gradient {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        match (start, end) {
+            (Gradient::Linear(s), Gradient::Linear(e)) => {
+                Gradient::Linear(LinearGradient::interpolate(s, e, t))
+            }
+            (Gradient::Radial(s), Gradient::Radial(e)) => {
+                Gradient::Radial(RadialGradient::interpolate(s, e, t))
+            }
+            (Gradient::Conic(s), Gradient::Conic(e)) => {
+                Gradient::Conic(ConicGradient::interpolate(s, e, t))
+            }
+            _ => end.clone(),
+        }
+    }
+}
+
+// Background layers are interpolated pairwise, a different number of layers jumps to the end
+impl Interpolator for Vec<Gradient> {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        if start.len() == end.len() {
+            start.iter().zip(end.iter()).map(|(s, e)| Gradient::interpolate(s, e, t)).collect()
+        } else {
+            end.clone()
+        }
+    }
+}
+
+/// Returns the color at an offset between 0 and 1 given the resolved stops of a gradient.
+pub(crate) fn color_at(stops: &[(f32, Color)], offset: f32) -> Color {
+    let index = stops.iter().position(|(position, _)| *position > offset);
+
+    match index {
+        Some(0) => stops[0].1,
+        Some(index) => {
+            let (start, start_color) = stops[index - 1];
+            let (end, end_color) = stops[index];
+            let t = if end > start { (offset - start) / (end - start) } else { 1.0 };
+            Color::interpolate(start_color, end_color, t as f64)
+        }
+        None => stops.last().map(|(_, color)| *color).unwrap_or_default(),
+    }
+}
+
+// Resolves the stop positions to offsets along a gradient line of the given length, following
+// the CSS rules for missing and out of order positions
+fn resolve_stops(stops: &[GradientStop], length: f32) -> Vec<(f32, Color)> {
+    let mut offsets = stops
+        .iter()
+        .map(|stop| match stop.position {
+            Units::Percentage(percentage) => Some(percentage / 100.0),
+            Units::Pixels(pixels) if length > 0.0 => Some(pixels / length),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(first) = offsets.first_mut() {
+        first.get_or_insert(0.0);
+    }
+
+    if let Some(last) = offsets.last_mut() {
+        last.get_or_insert(1.0);
+    }
+
+    // A stop can't come before the stops in front of it
+    let mut max = 0.0f32;
+    for offset in offsets.iter_mut().flatten() {
+        max = max.max(*offset);
+        *offset = max;
+    }
+
+    // Stops without a position are spaced evenly between the stops around them
+    let mut index = 0;
+    while index < offsets.len() {
+        if offsets[index].is_none() {
+            let start = index - 1;
+            let end = index + offsets[index..].iter().position(|offset| offset.is_some()).unwrap();
+            let (from, to) = (offsets[start].unwrap(), offsets[end].unwrap());
+
+            for i in index..end {
+                let t = (i - start) as f32 / (end - start) as f32;
+                offsets[i] = Some(from + (to - from) * t);
+            }
+
+            index = end;
+        }
+
+        index += 1;
+    }
+
+    offsets.into_iter().flatten().zip(stops.iter().map(|stop| stop.color)).collect()
+}
+
+fn resolve_length(length: Units, parent: f32, scale: f32) -> f32 {
+    match length {
+        Units::Pixels(pixels) => pixels * scale,
+        _ => length.value_or(parent, 0.0),
+    }
+}
+
+fn resolve_position(position: (Units, Units), width: f32, height: f32, scale: f32) -> (f32, f32) {
+    (resolve_length(position.0, width, scale), resolve_length(position.1, height, scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_without_positions() {
+        let gradient = LinearGradient::new(GradientDirection::LeftToRight)
+            .add_stop(GradientStop::new(Units::Auto, Color::red()))
+            .add_stop(GradientStop::new(Units::Auto, Color::green()))
+            .add_stop(GradientStop::new(Units::Percentage(80.0), Color::blue()))
+            .add_stop(GradientStop::new(Units::Auto, Color::white()));
+
+        let offsets = gradient.get_stops(100.0).iter().map(|stop| stop.0).collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0.0, 0.4, 0.8, 1.0]);
+    }
+
+    #[test]
+    fn stops_out_of_order() {
+        let gradient = LinearGradient::new(GradientDirection::LeftToRight)
+            .add_stop(GradientStop::new(Units::Pixels(50.0), Color::red()))
+            .add_stop(GradientStop::new(Units::Percentage(20.0), Color::blue()));
+
+        let offsets = gradient.get_stops(200.0).iter().map(|stop| stop.0).collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0.25, 0.25]);
+    }
+
+    #[test]
+    fn angled_line() {
+        let close = |a: (f32, f32, f32, f32), b: (f32, f32, f32, f32)| {
+            (a.0 - b.0).abs() < 1e-4
+                && (a.1 - b.1).abs() < 1e-4
+                && (a.2 - b.2).abs() < 1e-4
+                && (a.3 - b.3).abs() < 1e-4
+        };
+
+        let gradient = LinearGradient::new(GradientDirection::Angle(90.0));
+        assert!(close(gradient.line(100.0, 50.0), (0.0, 25.0, 100.0, 25.0)));
+
+        let gradient = LinearGradient::new(GradientDirection::TopToBottom);
+        assert!(close(gradient.line(100.0, 50.0), (50.0, 0.0, 50.0, 50.0)));
+
+        let gradient = LinearGradient::new(GradientDirection::Angle(45.0));
+        assert!(close(gradient.line(100.0, 100.0), (0.0, 100.0, 100.0, 0.0)));
+    }
+
+    #[test]
+    fn interpolate_stops() {
+        let start = LinearGradient::new(GradientDirection::BottomToTop)
+            .add_stop(GradientStop::new(Units::Percentage(0.0), Color::black()))
+            .add_stop(GradientStop::new(Units::Percentage(50.0), Color::white()));
+        let end = LinearGradient::new(GradientDirection::LeftToRight)
+            .add_stop(GradientStop::new(Units::Percentage(50.0), Color::black()))
+            .add_stop(GradientStop::new(Units::Percentage(100.0), Color::white()));
+
+        let gradient = LinearGradient::interpolate(&start, &end, 0.5);
+        assert_eq!(gradient.direction, GradientDirection::Angle(45.0));
+        assert_eq!(gradient.stops[0].position, Units::Percentage(25.0));
+        assert_eq!(gradient.stops[1].position, Units::Percentage(75.0));
+
+        let fewer_stops = LinearGradient::new(GradientDirection::LeftToRight)
+            .add_stop(GradientStop::new(Units::Auto, Color::red()));
+        assert_eq!(LinearGradient::interpolate(&start, &fewer_stops, 0.5).stops.len(), 1);
+    }
+
+    #[test]
+    fn radial_sizes() {
+        let gradient = RadialGradient::new(RadialGradientSize::ClosestSide)
+            .position(Units::Pixels(20.0), Units::Percentage(50.0));
+        assert_eq!(gradient.circle(100.0, 100.0, 1.0), (20.0, 50.0, 20.0));
+
+        let gradient = RadialGradient::new(RadialGradientSize::FarthestCorner);
+        let (_, _, radius) = gradient.circle(60.0, 80.0, 1.0);
+        assert_eq!(radius, 50.0);
     }
 }
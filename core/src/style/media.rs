@@ -0,0 +1,95 @@
+/// The properties of the main window which media queries are evaluated against.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct MediaEnvironment {
+    /// The logical width of the window.
+    pub width: f32,
+    /// The logical height of the window.
+    pub height: f32,
+    /// The scale factor of the window, in device pixels per logical pixel.
+    pub resolution: f64,
+}
+
+/// A single condition of a media query, e.g. `(max-width: 600px)`.
+///
+/// Lengths are in logical pixels and resolutions in device pixels per logical pixel (`dppx`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum MediaFeature {
+    Width(f32),
+    MinWidth(f32),
+    MaxWidth(f32),
+    Height(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    Resolution(f64),
+    MinResolution(f64),
+    MaxResolution(f64),
+}
+
+impl MediaFeature {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        match *self {
+            MediaFeature::Width(width) => environment.width == width,
+            MediaFeature::MinWidth(width) => environment.width >= width,
+            MediaFeature::MaxWidth(width) => environment.width <= width,
+            MediaFeature::Height(height) => environment.height == height,
+            MediaFeature::MinHeight(height) => environment.height >= height,
+            MediaFeature::MaxHeight(height) => environment.height <= height,
+            MediaFeature::Resolution(resolution) => environment.resolution == resolution,
+            MediaFeature::MinResolution(resolution) => environment.resolution >= resolution,
+            MediaFeature::MaxResolution(resolution) => environment.resolution <= resolution,
+        }
+    }
+}
+
+/// A media query, which matches when all of its features match.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MediaQuery {
+    pub features: Vec<MediaFeature>,
+}
+
+impl MediaQuery {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        self.features.iter().all(|feature| feature.matches(environment))
+    }
+}
+
+/// A comma separated list of media queries, which matches when any of its queries match.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MediaQueryList {
+    pub queries: Vec<MediaQuery>,
+}
+
+impl MediaQueryList {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        self.queries.iter().any(|query| query.matches(environment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVIRONMENT: MediaEnvironment =
+        MediaEnvironment { width: 800.0, height: 600.0, resolution: 2.0 };
+
+    #[test]
+    fn features() {
+        assert!(MediaFeature::MaxWidth(800.0).matches(&ENVIRONMENT));
+        assert!(!MediaFeature::MaxWidth(600.0).matches(&ENVIRONMENT));
+        assert!(MediaFeature::MinHeight(600.0).matches(&ENVIRONMENT));
+        assert!(!MediaFeature::MinHeight(700.0).matches(&ENVIRONMENT));
+        assert!(MediaFeature::MinResolution(1.5).matches(&ENVIRONMENT));
+        assert!(!MediaFeature::Resolution(1.0).matches(&ENVIRONMENT));
+    }
+
+    #[test]
+    fn query_list() {
+        let narrow = MediaQuery { features: vec![MediaFeature::MaxWidth(600.0)] };
+        let hidpi = MediaQuery {
+            features: vec![MediaFeature::MinWidth(400.0), MediaFeature::MinResolution(2.0)],
+        };
+
+        assert!(!MediaQueryList { queries: vec![narrow.clone()] }.matches(&ENVIRONMENT));
+        assert!(MediaQueryList { queries: vec![narrow, hidpi] }.matches(&ENVIRONMENT));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::id::GenerationalId;
+use fnv::{FnvHashMap, FnvHashSet};
 use morphorm::{LayoutType, PositionType, Units};
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use cssparser::{Parser, ParserInput};
 
@@ -42,9 +44,13 @@ pub use gradient::*;
 mod shadow;
 use shadow::*;
 
+mod media;
+pub(crate) use media::*;
+
 // mod prop;
 // pub use prop::*;
 
+use crate::accessibility::Role;
 use crate::animation::{AnimationState, Interpolator, Transition};
 use crate::id::IdManager;
 use crate::storage::animatable_set::AnimatableSet;
@@ -74,6 +80,10 @@ impl Default for Abilities {
     }
 }
 
+// The number of resolved rules which are no longer used by any entity that are kept before they
+// are freed
+pub(crate) const MAX_UNUSED_RESOLVED_RULES: usize = 256;
+
 /// Stores the style properties of all entities in the application.
 #[derive(Default)]
 pub struct Style {
@@ -86,8 +96,48 @@ pub struct Style {
 
     pub transitions: HashMap<Rule, Animation>,
 
+    // Custom Properties
+    /// The custom properties declared by each rule, in order.
+    pub(crate) rule_variables: HashMap<Rule, Vec<(String, String)>>,
+    /// The declarations of each rule which reference custom properties.
+    pub(crate) unresolved_declarations: HashMap<Rule, Vec<(String, String)>>,
+    /// Rules holding the declarations of a rule after substituting custom properties, keyed by the
+    /// rule and the substituted declarations.
+    pub(crate) resolved_rules: HashMap<(Rule, String), Rule>,
+    /// The resolved rules matched by each entity.
+    pub(crate) entity_resolved_rules: SparseSet<Vec<Rule>>,
+    /// The number of entities matching each resolved rule. Resolved rules which aren't in here are
+    /// unused.
+    pub(crate) resolved_rule_users: HashMap<Rule, usize>,
+    /// Custom properties set inline on entities.
+    pub(crate) inline_variables: SparseSet<Vec<(String, String)>>,
+    /// The custom properties of each entity, including inherited ones.
+    pub(crate) variables: SparseSet<Rc<FnvHashMap<String, String>>>,
+
+    // Media Queries
+    /// The window properties the media queries were last evaluated against.
+    pub(crate) media_environment: Option<MediaEnvironment>,
+    /// Rules which don't apply because their media queries don't match.
+    pub(crate) disabled_rules: HashSet<Rule>,
+
     pub default_font: String,
 
+    // Restyling
+    /// Entities whose ids, classes or pseudo-classes changed since the last restyle.
+    pub(crate) restyle_entities: FnvHashSet<Entity>,
+    /// Entities whose children were added, removed or moved since the last restyle.
+    pub(crate) restyle_parents: FnvHashSet<Entity>,
+    /// Entities which matched a selector that the styles of their descendants depend on, the last
+    /// time they were restyled.
+    pub(crate) affects_descendants: FnvHashSet<Entity>,
+    /// Entities which matched a selector that the styles of their later siblings depend on, the
+    /// last time they were restyled.
+    pub(crate) affects_siblings: FnvHashSet<Entity>,
+
+    // Redrawing
+    /// Entities whose visual state changed since the last frame.
+    pub(crate) redraw_entities: FnvHashSet<Entity>,
+
     pub elements: SparseSet<String>,
     pub ids: SparseSet<String>,
     pub classes: SparseSet<HashSet<String>>,
@@ -145,7 +195,8 @@ pub struct Style {
     // Background
     pub background_color: AnimatableSet<Color>,
     pub background_image: StyleSet<String>,
-    pub background_gradient: StyleSet<LinearGradient>,
+    /// Gradient background layers, with the first layer drawn on top.
+    pub background_gradient: AnimatableSet<Vec<Gradient>>,
 
     // Outer Shadow
     pub outer_shadow_h_offset: AnimatableSet<Units>,
@@ -174,6 +225,12 @@ pub struct Style {
 
     pub tooltip: SparseSet<String>,
 
+    // Accessibility
+    pub role: SparseSet<Role>,
+    pub accessible_value: SparseSet<String>,
+    pub numeric_value: SparseSet<f64>,
+    pub expanded: SparseSet<bool>,
+
     // LAYOUT
 
     // Layout Type
@@ -233,6 +290,8 @@ pub struct Style {
 
     pub needs_restyle: bool,
     pub needs_relayout: bool,
+    /// Set to repaint every window in full. Changes to a single view should use `redraw` instead,
+    /// which only repaints the region of the window covered by the view.
     pub needs_redraw: bool,
 
     pub dpi_factor: f64,
@@ -274,10 +333,7 @@ impl Style {
             .into_iter()
             .filter_map(|rule| {
                 match rule {
-                    Ok(mut style_rule) => {
-                        style_rule.id = self.rule_manager.create();
-                        Some(style_rule)
-                    }
+                    Ok(style_rules) => Some(style_rules),
                     Err(parse_error) => {
                         let style_parse_error = StyleParseError(parse_error.0);
                         println!("{}", style_parse_error);
@@ -286,6 +342,11 @@ impl Style {
                 }
                 //rule.ok()
             })
+            .flatten()
+            .map(|mut style_rule| {
+                style_rule.id = self.rule_manager.create();
+                style_rule
+            })
             .collect();
 
         self.rules.append(&mut rule_list);
@@ -293,626 +354,876 @@ impl Style {
         self.rules.sort_by_key(|rule| rule.specificity());
         self.rules.reverse();
 
+        // Evaluate the media queries of the new rules before the next restyle
+        self.media_environment = None;
+
         // for rule in self.rules.iter() {
         //     print!("{}", rule);
         // }
 
         self.clear_style_rules();
         self.set_style_properties();
+
+        // Every view is matched against the new rules
+        self.needs_restyle = true;
     }
 
     fn set_style_properties(&mut self) {
-        for rule in self.rules.iter() {
-            let rule_id = rule.id;
+        let rules =
+            self.rules.iter().map(|rule| (rule.id, rule.properties.clone())).collect::<Vec<_>>();
 
-            for property in rule.properties.clone() {
-                match property {
-                    Property::Display(value) => {
-                        self.display.insert_rule(rule_id, value);
-                    }
+        for (rule_id, properties) in rules {
+            // Transitions can only be attached to properties which the rule has already set
+            let (transitions, properties): (Vec<_>, Vec<_>) = properties
+                .into_iter()
+                .partition(|property| matches!(property, Property::Transition(_)));
 
-                    Property::Visibility(value) => {
-                        self.visibility.insert_rule(rule_id, value);
-                    }
+            for property in properties.into_iter().chain(transitions) {
+                self.insert_property(rule_id, property);
+            }
+        }
+        // println!("{:?}", self.rules);
+        // println!("{:?}", self.child_left.shared_data);
+    }
 
-                    Property::Opacity(value) => {
-                        self.opacity.insert_rule(rule_id, Opacity(value));
-                    }
+    /// Evaluates the media queries of the rules against the size and scale factor of the main
+    /// window, returning true if any rule has been enabled or disabled as a result.
+    pub(crate) fn update_media_queries(&mut self, environment: MediaEnvironment) -> bool {
+        if self.media_environment == Some(environment) {
+            return false;
+        }
 
-                    Property::Overflow(value) => {
-                        self.overflow.insert_rule(rule_id, value);
-                    }
+        self.media_environment = Some(environment);
 
-                    // Property::BackgroundGradient(value) => {
-                    //     self.background_gradient.insert_rule(rule_id, value);
-                    // }
-                    Property::PositionType(value) => {
-                        self.position_type.insert_rule(rule_id, value);
-                    }
+        let disabled_rules = self
+            .rules
+            .iter()
+            .filter(|rule| !rule.media.iter().all(|media| media.matches(&environment)))
+            .map(|rule| rule.id)
+            .collect::<HashSet<_>>();
 
-                    Property::Space(value) => {
-                        self.left.insert_rule(rule_id, value);
-                        self.right.insert_rule(rule_id, value);
-                        self.top.insert_rule(rule_id, value);
-                        self.bottom.insert_rule(rule_id, value);
-                    }
+        if disabled_rules == self.disabled_rules {
+            return false;
+        }
 
-                    Property::Left(value) => {
-                        self.left.insert_rule(rule_id, value);
-                    }
+        self.disabled_rules = disabled_rules;
 
-                    Property::Right(value) => {
-                        self.right.insert_rule(rule_id, value);
-                    }
+        true
+    }
 
-                    Property::Top(value) => {
-                        self.top.insert_rule(rule_id, value);
-                    }
+    /// Returns a rule holding the declarations of a rule after substituting custom properties,
+    /// creating it if needed.
+    pub(crate) fn resolved_rule(&mut self, rule: Rule, declarations: String) -> Rule {
+        let key = (rule, declarations);
 
-                    Property::Bottom(value) => {
-                        self.bottom.insert_rule(rule_id, value);
-                    }
+        if let Some(resolved_rule) = self.resolved_rules.get(&key) {
+            return *resolved_rule;
+        }
 
-                    // Position Constraints
-                    Property::MinLeft(value) => {
-                        self.min_left.insert_rule(rule_id, value);
-                    }
+        let resolved_rule = self.rule_manager.create();
+        for property in parser::parse_declarations(&key.1) {
+            self.insert_property(resolved_rule, property);
+        }
 
-                    Property::MaxLeft(value) => {
-                        self.max_left.insert_rule(rule_id, value);
-                    }
+        self.resolved_rules.insert(key, resolved_rule);
 
-                    Property::MinRight(value) => {
-                        self.min_right.insert_rule(rule_id, value);
-                    }
+        resolved_rule
+    }
 
-                    Property::MaxRight(value) => {
-                        self.max_right.insert_rule(rule_id, value);
-                    }
+    /// Records the resolved rules matched by an entity, replacing the ones it matched before.
+    pub(crate) fn set_resolved_rules(&mut self, entity: Entity, rules: Vec<Rule>) {
+        for rule in rules.iter() {
+            *self.resolved_rule_users.entry(*rule).or_default() += 1;
+        }
 
-                    Property::MinTop(value) => {
-                        self.min_top.insert_rule(rule_id, value);
-                    }
+        let previous = self.entity_resolved_rules.remove(entity).unwrap_or_default();
+        for rule in previous {
+            if let Some(users) = self.resolved_rule_users.get_mut(&rule) {
+                *users -= 1;
+                if *users == 0 {
+                    self.resolved_rule_users.remove(&rule);
+                }
+            }
+        }
 
-                    Property::MaxTop(value) => {
-                        self.max_top.insert_rule(rule_id, value);
-                    }
+        if !rules.is_empty() {
+            self.entity_resolved_rules.insert(entity, rules).expect("Failed to set resolved rules");
+        }
+    }
 
-                    Property::MinBottom(value) => {
-                        self.min_left.insert_rule(rule_id, value);
-                    }
+    /// Frees the resolved rules which are no longer matched by any entity, once enough of them
+    /// have built up, e.g. from a custom property being animated.
+    ///
+    /// Removing rules changes where the data of the remaining rules is stored, so all of the rules
+    /// are rebuilt and every entity is restyled.
+    pub(crate) fn collect_resolved_rules(&mut self) {
+        let unused = self.resolved_rules.len() - self.resolved_rule_users.len();
+        if unused < MAX_UNUSED_RESOLVED_RULES {
+            return;
+        }
 
-                    Property::MaxBottom(value) => {
-                        self.max_left.insert_rule(rule_id, value);
-                    }
+        self.clear_style_rules();
+        self.set_style_properties();
 
-                    // Size
-                    Property::Width(value) => {
-                        self.width.insert_rule(rule_id, value);
-                    }
+        self.needs_restyle = true;
+    }
 
-                    Property::Height(value) => {
-                        self.height.insert_rule(rule_id, value);
-                    }
+    fn insert_property(&mut self, rule_id: Rule, property: Property) {
+        match property {
+            Property::Variable(name, value) => {
+                self.rule_variables.entry(rule_id).or_default().push((name, value));
+            }
 
-                    // Size Constraints
-                    Property::MaxWidth(value) => {
-                        self.max_width.insert_rule(rule_id, value);
-                    }
+            Property::Unresolved(name, value) => {
+                self.unresolved_declarations.entry(rule_id).or_default().push((name, value));
+            }
 
-                    Property::MinWidth(value) => {
-                        self.min_width.insert_rule(rule_id, value);
-                    }
+            Property::Display(value) => {
+                self.display.insert_rule(rule_id, value);
+            }
 
-                    Property::MaxHeight(value) => {
-                        self.max_height.insert_rule(rule_id, value);
-                    }
+            Property::Visibility(value) => {
+                self.visibility.insert_rule(rule_id, value);
+            }
 
-                    Property::MinHeight(value) => {
-                        self.min_height.insert_rule(rule_id, value);
-                    }
+            Property::Opacity(value) => {
+                self.opacity.insert_rule(rule_id, Opacity(value));
+            }
 
-                    // Border
-                    Property::BorderWidth(value) => {
-                        self.border_width.insert_rule(rule_id, value);
-                    }
+            Property::Overflow(value) => {
+                self.overflow.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderColor(value) => {
-                        self.border_color.insert_rule(rule_id, value);
-                    }
+            Property::BackgroundGradient(value) => {
+                self.background_gradient.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderCornerShape(shape) => {
-                        self.border_shape_top_left.insert_rule(rule_id, shape);
-                        self.border_shape_top_right.insert_rule(rule_id, shape);
-                        self.border_shape_bottom_left.insert_rule(rule_id, shape);
-                        self.border_shape_bottom_right.insert_rule(rule_id, shape);
-                    }
+            Property::PositionType(value) => {
+                self.position_type.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderTopLeftShape(shape) => {
-                        self.border_shape_top_left.insert_rule(rule_id, shape);
-                    }
+            Property::Space(value) => {
+                self.left.insert_rule(rule_id, value);
+                self.right.insert_rule(rule_id, value);
+                self.top.insert_rule(rule_id, value);
+                self.bottom.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderTopRightShape(shape) => {
-                        self.border_shape_top_right.insert_rule(rule_id, shape);
-                    }
+            Property::Left(value) => {
+                self.left.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderBottomLeftShape(shape) => {
-                        self.border_shape_bottom_left.insert_rule(rule_id, shape);
-                    }
+            Property::Right(value) => {
+                self.right.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderBottomRightShape(shape) => {
-                        self.border_shape_bottom_right.insert_rule(rule_id, shape);
-                    }
+            Property::Top(value) => {
+                self.top.insert_rule(rule_id, value);
+            }
 
-                    // Border Radius
-                    Property::BorderRadius(value) => {
-                        self.border_radius_top_left.insert_rule(rule_id, value);
-                        self.border_radius_top_right.insert_rule(rule_id, value);
-                        self.border_radius_bottom_left.insert_rule(rule_id, value);
-                        self.border_radius_bottom_right.insert_rule(rule_id, value);
-                    }
+            Property::Bottom(value) => {
+                self.bottom.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderTopLeftRadius(value) => {
-                        self.border_radius_top_left.insert_rule(rule_id, value);
-                    }
+            // Position Constraints
+            Property::MinLeft(value) => {
+                self.min_left.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderTopRightRadius(value) => {
-                        self.border_radius_top_right.insert_rule(rule_id, value);
-                    }
+            Property::MaxLeft(value) => {
+                self.max_left.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderBottomLeftRadius(value) => {
-                        self.border_radius_bottom_left.insert_rule(rule_id, value);
-                    }
+            Property::MinRight(value) => {
+                self.min_right.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderBottomRightRadius(value) => {
-                        self.border_radius_bottom_right.insert_rule(rule_id, value);
-                    }
+            Property::MaxRight(value) => {
+                self.max_right.insert_rule(rule_id, value);
+            }
 
-                    Property::OutlineWidth(value) => {
-                        self.outline_width.insert_rule(rule_id, value);
-                    }
+            Property::MinTop(value) => {
+                self.min_top.insert_rule(rule_id, value);
+            }
 
-                    Property::OutlineColor(value) => {
-                        self.outline_color.insert_rule(rule_id, value);
-                    }
+            Property::MaxTop(value) => {
+                self.max_top.insert_rule(rule_id, value);
+            }
 
-                    Property::OutlineOffset(value) => {
-                        self.outline_offset.insert_rule(rule_id, value);
-                    }
+            Property::MinBottom(value) => {
+                self.min_left.insert_rule(rule_id, value);
+            }
 
-                    // Font
-                    Property::FontSize(value) => {
-                        self.font_size.insert_rule(rule_id, value);
-                    }
+            Property::MaxBottom(value) => {
+                self.max_left.insert_rule(rule_id, value);
+            }
 
-                    Property::FontColor(value) => {
-                        self.font_color.insert_rule(rule_id, value);
-                    }
+            // Size
+            Property::Width(value) => {
+                self.width.insert_rule(rule_id, value);
+            }
 
-                    Property::Font(value) => {
-                        self.font.insert_rule(rule_id, value);
-                    }
+            Property::Height(value) => {
+                self.height.insert_rule(rule_id, value);
+            }
 
-                    Property::TextWrap(value) => {
-                        self.text_wrap.insert_rule(rule_id, value);
-                    }
+            // Size Constraints
+            Property::MaxWidth(value) => {
+                self.max_width.insert_rule(rule_id, value);
+            }
 
-                    Property::SelectionColor(value) => {
-                        self.selection_color.insert_rule(rule_id, value);
-                    }
+            Property::MinWidth(value) => {
+                self.min_width.insert_rule(rule_id, value);
+            }
 
-                    Property::CaretColor(value) => {
-                        self.caret_color.insert_rule(rule_id, value);
-                    }
+            Property::MaxHeight(value) => {
+                self.max_height.insert_rule(rule_id, value);
+            }
 
-                    // Background
-                    Property::BackgroundColor(value) => {
-                        self.background_color.insert_rule(rule_id, value);
-                    }
+            Property::MinHeight(value) => {
+                self.min_height.insert_rule(rule_id, value);
+            }
 
-                    Property::BackgroundImage(value) => {
-                        self.background_image.insert_rule(rule_id, value);
-                    }
+            // Border
+            Property::BorderWidth(value) => {
+                self.border_width.insert_rule(rule_id, value);
+            }
 
-                    // Layout
-                    Property::LayoutType(value) => {
-                        self.layout_type.insert_rule(rule_id, value);
-                    }
+            Property::BorderColor(value) => {
+                self.border_color.insert_rule(rule_id, value);
+            }
 
-                    Property::ZIndex(value) => {
-                        self.z_order.insert_rule(rule_id, value);
-                    }
+            Property::BorderCornerShape(shape) => {
+                self.border_shape_top_left.insert_rule(rule_id, shape);
+                self.border_shape_top_right.insert_rule(rule_id, shape);
+                self.border_shape_bottom_left.insert_rule(rule_id, shape);
+                self.border_shape_bottom_right.insert_rule(rule_id, shape);
+            }
 
-                    // Outer Shadow
-                    Property::OuterShadow(box_shadow) => {
-                        self.outer_shadow_h_offset
-                            .insert_rule(rule_id, box_shadow.horizontal_offset);
-                        self.outer_shadow_v_offset.insert_rule(rule_id, box_shadow.vertical_offset);
-                        self.outer_shadow_blur.insert_rule(rule_id, box_shadow.blur_radius);
-                        self.outer_shadow_color.insert_rule(rule_id, box_shadow.color);
-                    }
+            Property::BorderTopLeftShape(shape) => {
+                self.border_shape_top_left.insert_rule(rule_id, shape);
+            }
 
-                    Property::OuterShadowColor(color) => {
-                        self.outer_shadow_color.insert_rule(rule_id, color);
-                    }
+            Property::BorderTopRightShape(shape) => {
+                self.border_shape_top_right.insert_rule(rule_id, shape);
+            }
 
-                    // Inner Shadow
-                    Property::InnerShadow(box_shadow) => {
-                        self.inner_shadow_h_offset
-                            .insert_rule(rule_id, box_shadow.horizontal_offset);
-                        self.inner_shadow_v_offset.insert_rule(rule_id, box_shadow.vertical_offset);
-                        self.inner_shadow_blur.insert_rule(rule_id, box_shadow.blur_radius);
-                        self.inner_shadow_color.insert_rule(rule_id, box_shadow.color);
-                    }
+            Property::BorderBottomLeftShape(shape) => {
+                self.border_shape_bottom_left.insert_rule(rule_id, shape);
+            }
 
-                    // Child Spacing
-                    Property::ChildLeft(value) => {
-                        self.child_left.insert_rule(rule_id, value);
-                    }
+            Property::BorderBottomRightShape(shape) => {
+                self.border_shape_bottom_right.insert_rule(rule_id, shape);
+            }
 
-                    Property::ChildRight(value) => {
-                        self.child_right.insert_rule(rule_id, value);
-                    }
+            // Border Radius
+            Property::BorderRadius(value) => {
+                self.border_radius_top_left.insert_rule(rule_id, value);
+                self.border_radius_top_right.insert_rule(rule_id, value);
+                self.border_radius_bottom_left.insert_rule(rule_id, value);
+                self.border_radius_bottom_right.insert_rule(rule_id, value);
+            }
 
-                    Property::ChildTop(value) => {
-                        self.child_top.insert_rule(rule_id, value);
-                    }
+            Property::BorderTopLeftRadius(value) => {
+                self.border_radius_top_left.insert_rule(rule_id, value);
+            }
 
-                    Property::ChildBottom(value) => {
-                        self.child_bottom.insert_rule(rule_id, value);
-                    }
+            Property::BorderTopRightRadius(value) => {
+                self.border_radius_top_right.insert_rule(rule_id, value);
+            }
 
-                    Property::ChildSpace(value) => {
-                        self.child_left.insert_rule(rule_id, value);
-                        self.child_right.insert_rule(rule_id, value);
-                        self.child_top.insert_rule(rule_id, value);
-                        self.child_bottom.insert_rule(rule_id, value);
-                    }
+            Property::BorderBottomLeftRadius(value) => {
+                self.border_radius_bottom_left.insert_rule(rule_id, value);
+            }
 
-                    Property::RowBetween(value) => {
-                        self.row_between.insert_rule(rule_id, value);
-                    }
+            Property::BorderBottomRightRadius(value) => {
+                self.border_radius_bottom_right.insert_rule(rule_id, value);
+            }
 
-                    Property::ColBetween(value) => {
-                        self.col_between.insert_rule(rule_id, value);
-                    }
+            Property::OutlineWidth(value) => {
+                self.outline_width.insert_rule(rule_id, value);
+            }
 
-                    Property::Cursor(cursor) => {
-                        self.cursor.insert_rule(rule_id, cursor);
-                    }
+            Property::OutlineColor(value) => {
+                self.outline_color.insert_rule(rule_id, value);
+            }
+
+            Property::OutlineOffset(value) => {
+                self.outline_offset.insert_rule(rule_id, value);
+            }
 
-                    // TODO
-                    // Property::Translate(value) => {
-                    //     self.translate.insert_rule(rule_id, value);
-                    // }
-
-                    // Property::Rotate(value) => {
-                    //     self.rotate.insert_rule(rule_id, value);
-                    // }
-
-                    // Property::Scale(value) => {
-                    //     self.scale.insert_rule(rule_id, value.0);
-                    // }
-
-                    // Transitions
-                    Property::Transition(transitions) => {
-                        for transition in transitions {
-                            match transition.property.as_ref() {
-                                "background-color" => {
-                                    let animation = self.animation_manager.create();
-                                    self.background_color.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.background_color.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "left" => {
-                                    let animation = self.animation_manager.create();
-                                    self.left.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.left.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "top" => {
-                                    let animation = self.animation_manager.create();
-                                    self.top.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.top.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "right" => {
-                                    let animation = self.animation_manager.create();
-                                    self.right.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.right.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "bottom" => {
-                                    let animation = self.animation_manager.create();
-                                    self.bottom.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.bottom.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "min-left" => {
-                                    let animation = self.animation_manager.create();
-                                    self.min_left.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.min_left.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "max-left" => {
-                                    let animation = self.animation_manager.create();
-                                    self.max_left.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.max_left.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "min-right" => {
-                                    let animation = self.animation_manager.create();
-                                    self.min_right.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.min_right.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "max-right" => {
-                                    let animation = self.animation_manager.create();
-                                    self.max_right.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.max_right.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "min-top" => {
-                                    let animation = self.animation_manager.create();
-                                    self.min_top.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.min_top.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "max-top" => {
-                                    let animation = self.animation_manager.create();
-                                    self.max_top.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.max_top.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "min-bottom" => {
-                                    let animation = self.animation_manager.create();
-                                    self.min_bottom.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.min_bottom.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "max-bottom" => {
-                                    let animation = self.animation_manager.create();
-                                    self.max_bottom.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.max_bottom.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "width" => {
-                                    let animation = self.animation_manager.create();
-                                    self.width.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.width.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "height" => {
-                                    let animation = self.animation_manager.create();
-                                    self.height.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.height.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "min-width" => {
-                                    let animation = self.animation_manager.create();
-                                    self.min_width.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.min_width.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "max-width" => {
-                                    let animation = self.animation_manager.create();
-                                    self.max_width.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.max_width.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "min-height" => {
-                                    let animation = self.animation_manager.create();
-                                    self.min_height.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.min_height.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "max-height" => {
-                                    let animation = self.animation_manager.create();
-                                    self.max_height.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.max_height.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "child-left" => {
-                                    let animation = self.animation_manager.create();
-                                    self.child_left.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.child_left.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "child-right" => {
-                                    let animation = self.animation_manager.create();
-                                    self.child_right.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.child_right.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "child-top" => {
-                                    let animation = self.animation_manager.create();
-                                    self.child_top.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.child_top.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "child-bottom" => {
-                                    let animation = self.animation_manager.create();
-                                    self.child_bottom.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.child_bottom.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "col-between" => {
-                                    let animation = self.animation_manager.create();
-                                    self.col_between.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.col_between.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "row-between" => {
-                                    let animation = self.animation_manager.create();
-                                    self.row_between.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.row_between.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "opacity" => {
-                                    let animation = self.animation_manager.create();
-                                    self.opacity.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.opacity.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "outer-shadow-color" => {
-                                    let animation = self.animation_manager.create();
-                                    self.outer_shadow_color.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.outer_shadow_color.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "outline-width" => {
-                                    let animation = self.animation_manager.create();
-                                    self.outline_width.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.outline_width.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "outline-color" => {
-                                    let animation = self.animation_manager.create();
-                                    self.outline_color.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.outline_color.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                "outline-offset" => {
-                                    let animation = self.animation_manager.create();
-                                    self.outline_offset.insert_animation(
-                                        animation,
-                                        self.add_transition(transition),
-                                    );
-                                    self.outline_offset.insert_transition(rule_id, animation);
-                                    self.transitions.insert(rule_id, animation);
-                                }
-
-                                _ => {}
-                            }
+            // Font
+            Property::FontSize(value) => {
+                self.font_size.insert_rule(rule_id, value);
+            }
+
+            Property::FontColor(value) => {
+                self.font_color.insert_rule(rule_id, value);
+            }
+
+            Property::Font(value) => {
+                self.font.insert_rule(rule_id, value);
+            }
+
+            Property::TextWrap(value) => {
+                self.text_wrap.insert_rule(rule_id, value);
+            }
+
+            Property::SelectionColor(value) => {
+                self.selection_color.insert_rule(rule_id, value);
+            }
+
+            Property::CaretColor(value) => {
+                self.caret_color.insert_rule(rule_id, value);
+            }
+
+            // Background
+            Property::BackgroundColor(value) => {
+                self.background_color.insert_rule(rule_id, value);
+            }
+
+            Property::BackgroundImage(value) => {
+                self.background_image.insert_rule(rule_id, value);
+            }
+
+            // Layout
+            Property::LayoutType(value) => {
+                self.layout_type.insert_rule(rule_id, value);
+            }
+
+            Property::ZIndex(value) => {
+                self.z_order.insert_rule(rule_id, value);
+            }
+
+            // Outer Shadow
+            Property::OuterShadow(box_shadow) => {
+                self.outer_shadow_h_offset.insert_rule(rule_id, box_shadow.horizontal_offset);
+                self.outer_shadow_v_offset.insert_rule(rule_id, box_shadow.vertical_offset);
+                self.outer_shadow_blur.insert_rule(rule_id, box_shadow.blur_radius);
+                self.outer_shadow_color.insert_rule(rule_id, box_shadow.color);
+            }
+
+            Property::OuterShadowHOffset(value) => {
+                self.outer_shadow_h_offset.insert_rule(rule_id, value);
+            }
+
+            Property::OuterShadowVOffset(value) => {
+                self.outer_shadow_v_offset.insert_rule(rule_id, value);
+            }
+
+            Property::OuterShadowBlur(value) => {
+                self.outer_shadow_blur.insert_rule(rule_id, value);
+            }
+
+            Property::OuterShadowColor(color) => {
+                self.outer_shadow_color.insert_rule(rule_id, color);
+            }
+
+            // Inner Shadow
+            Property::InnerShadow(box_shadow) => {
+                self.inner_shadow_h_offset.insert_rule(rule_id, box_shadow.horizontal_offset);
+                self.inner_shadow_v_offset.insert_rule(rule_id, box_shadow.vertical_offset);
+                self.inner_shadow_blur.insert_rule(rule_id, box_shadow.blur_radius);
+                self.inner_shadow_color.insert_rule(rule_id, box_shadow.color);
+            }
+
+            Property::InnerShadowHOffset(value) => {
+                self.inner_shadow_h_offset.insert_rule(rule_id, value);
+            }
+
+            Property::InnerShadowVOffset(value) => {
+                self.inner_shadow_v_offset.insert_rule(rule_id, value);
+            }
+
+            Property::InnerShadowBlur(value) => {
+                self.inner_shadow_blur.insert_rule(rule_id, value);
+            }
+
+            Property::InnerShadowColor(color) => {
+                self.inner_shadow_color.insert_rule(rule_id, color);
+            }
+
+            // Child Spacing
+            Property::ChildLeft(value) => {
+                self.child_left.insert_rule(rule_id, value);
+            }
+
+            Property::ChildRight(value) => {
+                self.child_right.insert_rule(rule_id, value);
+            }
+
+            Property::ChildTop(value) => {
+                self.child_top.insert_rule(rule_id, value);
+            }
+
+            Property::ChildBottom(value) => {
+                self.child_bottom.insert_rule(rule_id, value);
+            }
+
+            Property::ChildSpace(value) => {
+                self.child_left.insert_rule(rule_id, value);
+                self.child_right.insert_rule(rule_id, value);
+                self.child_top.insert_rule(rule_id, value);
+                self.child_bottom.insert_rule(rule_id, value);
+            }
+
+            Property::RowBetween(value) => {
+                self.row_between.insert_rule(rule_id, value);
+            }
+
+            Property::ColBetween(value) => {
+                self.col_between.insert_rule(rule_id, value);
+            }
+
+            Property::Cursor(cursor) => {
+                self.cursor.insert_rule(rule_id, cursor);
+            }
+
+            // Transform
+            Property::Translate(value) => {
+                self.translate.insert_rule(rule_id, value);
+            }
+
+            Property::Rotate(value) => {
+                self.rotate.insert_rule(rule_id, value);
+            }
+
+            Property::Scale(value) => {
+                self.scale.insert_rule(rule_id, value);
+            }
+
+            // Transitions
+            Property::Transition(transitions) => {
+                for transition in transitions {
+                    match transition.property.as_ref() {
+                        "background-color" => {
+                            let animation = self.animation_manager.create();
+                            self.background_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.background_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
                         }
-                    }
 
-                    Property::Unknown(s, _) => {
-                        println!("Unknown style property: {}", s)
-                    }
+                        "left" => {
+                            let animation = self.animation_manager.create();
+                            self.left.insert_animation(animation, self.add_transition(transition));
+                            self.left.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "top" => {
+                            let animation = self.animation_manager.create();
+                            self.top.insert_animation(animation, self.add_transition(transition));
+                            self.top.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "right" => {
+                            let animation = self.animation_manager.create();
+                            self.right.insert_animation(animation, self.add_transition(transition));
+                            self.right.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "bottom" => {
+                            let animation = self.animation_manager.create();
+                            self.bottom
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.bottom.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "min-left" => {
+                            let animation = self.animation_manager.create();
+                            self.min_left
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.min_left.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "max-left" => {
+                            let animation = self.animation_manager.create();
+                            self.max_left
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.max_left.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "min-right" => {
+                            let animation = self.animation_manager.create();
+                            self.min_right
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.min_right.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "max-right" => {
+                            let animation = self.animation_manager.create();
+                            self.max_right
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.max_right.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "min-top" => {
+                            let animation = self.animation_manager.create();
+                            self.min_top
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.min_top.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "max-top" => {
+                            let animation = self.animation_manager.create();
+                            self.max_top
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.max_top.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "min-bottom" => {
+                            let animation = self.animation_manager.create();
+                            self.min_bottom
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.min_bottom.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "max-bottom" => {
+                            let animation = self.animation_manager.create();
+                            self.max_bottom
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.max_bottom.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "width" => {
+                            let animation = self.animation_manager.create();
+                            self.width.insert_animation(animation, self.add_transition(transition));
+                            self.width.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "height" => {
+                            let animation = self.animation_manager.create();
+                            self.height
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.height.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "min-width" => {
+                            let animation = self.animation_manager.create();
+                            self.min_width
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.min_width.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "max-width" => {
+                            let animation = self.animation_manager.create();
+                            self.max_width
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.max_width.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "min-height" => {
+                            let animation = self.animation_manager.create();
+                            self.min_height
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.min_height.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
 
-                    _ => {}
+                        "max-height" => {
+                            let animation = self.animation_manager.create();
+                            self.max_height
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.max_height.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "child-left" => {
+                            let animation = self.animation_manager.create();
+                            self.child_left
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.child_left.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "child-right" => {
+                            let animation = self.animation_manager.create();
+                            self.child_right
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.child_right.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "child-top" => {
+                            let animation = self.animation_manager.create();
+                            self.child_top
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.child_top.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "child-bottom" => {
+                            let animation = self.animation_manager.create();
+                            self.child_bottom
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.child_bottom.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "col-between" => {
+                            let animation = self.animation_manager.create();
+                            self.col_between
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.col_between.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "row-between" => {
+                            let animation = self.animation_manager.create();
+                            self.row_between
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.row_between.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "opacity" => {
+                            let animation = self.animation_manager.create();
+                            self.opacity
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.opacity.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outer-shadow-color" => {
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outer_shadow_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outline-width" => {
+                            let animation = self.animation_manager.create();
+                            self.outline_width
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outline_width.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outline-color" => {
+                            let animation = self.animation_manager.create();
+                            self.outline_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outline_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outline-offset" => {
+                            let animation = self.animation_manager.create();
+                            self.outline_offset
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outline_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "translate" => {
+                            let animation = self.animation_manager.create();
+                            self.translate
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.translate.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "rotate" => {
+                            let animation = self.animation_manager.create();
+                            self.rotate
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.rotate.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "scale" => {
+                            let animation = self.animation_manager.create();
+                            self.scale.insert_animation(animation, self.add_transition(transition));
+                            self.scale.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "border-width" => {
+                            let animation = self.animation_manager.create();
+                            self.border_width
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.border_width.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "border-color" => {
+                            let animation = self.animation_manager.create();
+                            self.border_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.border_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outer-shadow" => {
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_h_offset.insert_animation(
+                                animation,
+                                self.add_transition(transition.clone()),
+                            );
+                            self.outer_shadow_h_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_v_offset.insert_animation(
+                                animation,
+                                self.add_transition(transition.clone()),
+                            );
+                            self.outer_shadow_v_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_blur.insert_animation(
+                                animation,
+                                self.add_transition(transition.clone()),
+                            );
+                            self.outer_shadow_blur.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outer_shadow_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outer-shadow-h-offset" => {
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_h_offset
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outer_shadow_h_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outer-shadow-v-offset" => {
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_v_offset
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outer_shadow_v_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "outer-shadow-blur" => {
+                            let animation = self.animation_manager.create();
+                            self.outer_shadow_blur
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.outer_shadow_blur.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "inner-shadow" => {
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_h_offset.insert_animation(
+                                animation,
+                                self.add_transition(transition.clone()),
+                            );
+                            self.inner_shadow_h_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_v_offset.insert_animation(
+                                animation,
+                                self.add_transition(transition.clone()),
+                            );
+                            self.inner_shadow_v_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_blur.insert_animation(
+                                animation,
+                                self.add_transition(transition.clone()),
+                            );
+                            self.inner_shadow_blur.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.inner_shadow_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "inner-shadow-h-offset" => {
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_h_offset
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.inner_shadow_h_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "inner-shadow-v-offset" => {
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_v_offset
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.inner_shadow_v_offset.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "inner-shadow-blur" => {
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_blur
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.inner_shadow_blur.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "inner-shadow-color" => {
+                            let animation = self.animation_manager.create();
+                            self.inner_shadow_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.inner_shadow_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "color" => {
+                            let animation = self.animation_manager.create();
+                            self.font_color
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.font_color.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "font-size" => {
+                            let animation = self.animation_manager.create();
+                            self.font_size
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.font_size.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        "background-image" => {
+                            let animation = self.animation_manager.create();
+                            self.background_gradient
+                                .insert_animation(animation, self.add_transition(transition));
+                            self.background_gradient.insert_transition(rule_id, animation);
+                            self.transitions.insert(rule_id, animation);
+                        }
+
+                        _ => {}
+                    }
                 }
             }
+
+            Property::Unknown(s, _) => {
+                println!("Unknown style property: {}", s)
+            }
         }
-        // println!("{:?}", self.rules);
-        // println!("{:?}", self.child_left.shared_data);
     }
 
     fn add_transition<T: Default + Interpolator>(
@@ -923,6 +1234,7 @@ impl Style {
             .with_duration(instant::Duration::from_secs_f32(transition.duration))
             .with_delay(instant::Duration::from_secs_f32(transition.delay))
             .with_keyframe((0.0, Default::default()))
+            .with_timing_function(transition.timing_function)
             .with_keyframe((1.0, Default::default()))
     }
 
@@ -935,9 +1247,9 @@ impl Style {
         self.abilities.insert(entity, Abilities::default()).expect("Failed to add abilities");
         self.visibility.insert(entity, Default::default());
         self.focus_order.insert(entity, Default::default()).unwrap();
-        self.needs_restyle = true;
+        self.restyle(entity);
+        self.redraw(entity);
         self.needs_relayout = true;
-        self.needs_redraw = true;
     }
 
     pub fn remove(&mut self, entity: Entity) {
@@ -1055,11 +1367,60 @@ impl Style {
         self.caret_color.remove(entity);
 
         self.image.remove(entity);
+
+        self.tooltip.remove(entity);
+
+        // Accessibility
+        self.name.remove(entity);
+        self.role.remove(entity);
+        self.accessible_value.remove(entity);
+        self.numeric_value.remove(entity);
+        self.expanded.remove(entity);
+
+        // Custom Properties
+        self.inline_variables.remove(entity);
+        self.variables.remove(entity);
+        self.set_resolved_rules(entity, Vec::new());
+
+        // Restyling
+        self.restyle_entities.remove(&entity);
+        self.restyle_parents.remove(&entity);
+        self.affects_descendants.remove(&entity);
+        self.affects_siblings.remove(&entity);
+
+        // Redrawing
+        self.redraw_entities.remove(&entity);
+    }
+
+    /// Marks an entity as needing its style rules to be matched again, after a change to its id,
+    /// classes or pseudo-classes.
+    pub(crate) fn restyle(&mut self, entity: Entity) {
+        self.restyle_entities.insert(entity);
+    }
+
+    /// Marks the children of an entity as needing to be restyled after a child is added, removed
+    /// or moved, which can change the structural and sibling selectors they match.
+    pub(crate) fn restyle_children(&mut self, entity: Entity) {
+        self.restyle_parents.insert(entity);
+    }
+
+    /// Marks an entity as needing to be redrawn after a change to its visual state.
+    pub(crate) fn redraw(&mut self, entity: Entity) {
+        self.redraw_entities.insert(entity);
     }
 
     pub fn clear_style_rules(&mut self) {
         //self.disabled.clear_rules(entity);
         //self.abilities.clear_rules(entity);
+        // Custom Properties
+        self.rule_variables.clear();
+        self.unresolved_declarations.clear();
+        for (_, rule) in self.resolved_rules.drain() {
+            self.rule_manager.destroy(rule);
+        }
+        self.entity_resolved_rules.clear();
+        self.resolved_rule_users.clear();
+
         // Display
         self.display.clear_rules();
         // Visibility
@@ -5,13 +5,15 @@ use cssparser::{
     ParseError, ParseErrorKind, Parser, ParserInput, SourceLocation, Token,
 };
 
-use crate::animation::Transition;
+use crate::animation::{StepPosition, TimingFunction, Transition};
 use crate::id::GenerationalId;
 use crate::style::color::Color;
+use crate::style::media::{MediaFeature, MediaQuery, MediaQueryList};
 use crate::style::property::Property;
-use crate::style::selector::{Selector, SelectorRelation};
+use crate::style::selector::{NthChild, Selector, SelectorRelation};
 use crate::style::StyleRule;
 use crate::style::*;
+use fnv::FnvHashMap;
 
 #[derive(Clone)]
 pub enum CustomParseError {
@@ -21,6 +23,8 @@ pub enum CustomParseError {
     InvalidColorHex(String),
     InvalidStringName(String),
     UnrecognisedPseudoclass(String),
+    UnrecognisedMediaType(String),
+    UnrecognisedMediaFeature(String),
 }
 
 impl<'t> From<CustomParseError> for ParseError<'t, CustomParseError> {
@@ -62,6 +66,14 @@ impl Debug for CustomParseError {
                 write!(f, "Unrecognised pseudoclass: {}", error_string)
             }
 
+            CustomParseError::UnrecognisedMediaType(error_string) => {
+                write!(f, "Unrecognised media type: {}", error_string)
+            }
+
+            CustomParseError::UnrecognisedMediaFeature(error_string) => {
+                write!(f, "Unrecognised media feature: {}", error_string)
+            }
+
             CustomParseError::InvalidLengthUnits(error_string) => {
                 write!(f, "Invalid length units: {}", error_string)
             }
@@ -89,9 +101,15 @@ impl RuleParser {
     }
 }
 
+/// The prelude of an at-rule with a block.
+pub(crate) enum AtRulePrelude {
+    Keyframes,
+    Media(MediaQueryList),
+}
+
 impl<'i> cssparser::QualifiedRuleParser<'i> for RuleParser {
     type Prelude = Vec<Selector>;
-    type QualifiedRule = StyleRule;
+    type QualifiedRule = Vec<StyleRule>;
     type Error = CustomParseError;
 
     fn parse_prelude<'t>(
@@ -114,14 +132,14 @@ impl<'i> cssparser::QualifiedRuleParser<'i> for RuleParser {
             .filter_map(|property| property.ok())
             .collect::<Vec<_>>();
 
-        Ok(StyleRule { id: Rule::null(), selectors, properties })
+        Ok(vec![StyleRule { id: Rule::null(), selectors, properties, media: Vec::new() }])
     }
 }
 
 impl<'i> cssparser::AtRuleParser<'i> for RuleParser {
-    type PreludeBlock = ();
+    type PreludeBlock = AtRulePrelude;
     type PreludeNoBlock = ();
-    type AtRule = StyleRule;
+    type AtRule = Vec<StyleRule>;
     type Error = CustomParseError;
 
     fn parse_prelude<'t>(
@@ -130,7 +148,7 @@ impl<'i> cssparser::AtRuleParser<'i> for RuleParser {
         input: &mut Parser<'i, 't>,
     ) -> Result<AtRuleType<Self::PreludeNoBlock, Self::PreludeBlock>, ParseError<'i, Self::Error>>
     {
-        match &*name {
+        let prelude = match &*name {
             "keyframes" => {
                 while let Ok(t) = input.next() {
                     match t {
@@ -145,34 +163,167 @@ impl<'i> cssparser::AtRuleParser<'i> for RuleParser {
                         }
                     }
                 }
+
+                AtRulePrelude::Keyframes
             }
 
+            "media" => AtRulePrelude::Media(parse_media_query_list(input)?),
+
             _ => {
                 let token = input.next()?.to_owned();
                 return Err(input.new_basic_unexpected_token_error(token).into());
             }
-        }
+        };
 
-        Ok(AtRuleType::WithBlock(()))
+        Ok(AtRuleType::WithBlock(prelude))
     }
 
-    // TODO
-    /*
     fn parse_block<'t>(
         &mut self,
         prelude: Self::PreludeBlock,
-        location: SourceLocation,
-        input: &mut Parser<'i, 't>
+        _location: SourceLocation,
+        input: &mut Parser<'i, 't>,
     ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
-        let rule_parser = RuleParser::new();
-        let rule_list_parser = RuleListParser::new_for_nested_rule(input, rule_parser).collect::<Vec<_>>();
+        match prelude {
+            // TODO
+            AtRulePrelude::Keyframes => {
+                Err(input.new_error(BasicParseErrorKind::AtRuleBodyInvalid))
+            }
+
+            AtRulePrelude::Media(media) => {
+                let mut rules = Vec::new();
+
+                for result in
+                    cssparser::RuleListParser::new_for_nested_rule(input, RuleParser::new())
+                {
+                    match result {
+                        Ok(nested_rules) => rules.extend(nested_rules),
+                        Err((parse_error, _)) => println!("{}", StyleParseError(parse_error)),
+                    }
+                }
+
+                // Nested rules keep the conditions of every enclosing @media rule
+                for rule in rules.iter_mut() {
+                    rule.media.insert(0, media.clone());
+                }
+
+                Ok(rules)
+            }
+        }
+    }
+}
+
+// Parses a comma separated list of media queries, e.g. `screen and (max-width: 600px), (min-resolution: 2dppx)`
+fn parse_media_query_list<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<MediaQueryList, ParseError<'i, CustomParseError>> {
+    let queries = input.parse_comma_separated(parse_media_query)?;
+
+    Ok(MediaQueryList { queries })
+}
+
+fn parse_media_query<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<MediaQuery, ParseError<'i, CustomParseError>> {
+    let mut features = Vec::new();
+
+    // An optional media type, which can be followed by features
+    if let Ok(media_type) = input.try_parse(|input| input.expect_ident_cloned()) {
+        match &*media_type {
+            "all" | "screen" => {}
+
+            _ => {
+                return Err(input.new_custom_error(CustomParseError::UnrecognisedMediaType(
+                    media_type.to_string(),
+                )));
+            }
+        }
+
+        if input.try_parse(|input| input.expect_ident_matching("and")).is_err() {
+            return Ok(MediaQuery { features });
+        }
+    }
+
+    loop {
+        input.expect_parenthesis_block()?;
+        features.push(input.parse_nested_block(parse_media_feature)?);
+
+        if input.try_parse(|input| input.expect_ident_matching("and")).is_err() {
+            break;
+        }
+    }
 
-        // Keyframe rules
-        for rule in &rule_list_parser {
+    Ok(MediaQuery { features })
+}
+
+fn parse_media_feature<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<MediaFeature, ParseError<'i, CustomParseError>> {
+    let name = input.expect_ident_cloned()?;
+    input.expect_colon()?;
+
+    let feature = match &*name {
+        "width" => MediaFeature::Width(parse_media_length(input)?),
+        "min-width" => MediaFeature::MinWidth(parse_media_length(input)?),
+        "max-width" => MediaFeature::MaxWidth(parse_media_length(input)?),
+        "height" => MediaFeature::Height(parse_media_length(input)?),
+        "min-height" => MediaFeature::MinHeight(parse_media_length(input)?),
+        "max-height" => MediaFeature::MaxHeight(parse_media_length(input)?),
+        "resolution" => MediaFeature::Resolution(parse_media_resolution(input)?),
+        "min-resolution" => MediaFeature::MinResolution(parse_media_resolution(input)?),
+        "max-resolution" => MediaFeature::MaxResolution(parse_media_resolution(input)?),
+
+        _ => {
+            return Err(input
+                .new_custom_error(CustomParseError::UnrecognisedMediaFeature(name.to_string())));
+        }
+    };
+
+    input.expect_exhausted()?;
+
+    Ok(feature)
+}
+
+// Parses a length in logical pixels
+fn parse_media_length<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    match input.next()? {
+        Token::Dimension { value, unit, .. } if &**unit == "px" => Ok(*value),
+        Token::Number { value, .. } if *value == 0.0 => Ok(0.0),
 
+        t => {
+            let t = t.clone();
+            Err(location.new_unexpected_token_error(t))
+        }
+    }
+}
+
+// Parses a resolution in device pixels per logical pixel, where a logical pixel is 1/96th of an inch
+fn parse_media_resolution<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f64, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    match input.next()? {
+        Token::Dimension { value, unit, .. } => {
+            match &**unit {
+                "dppx" | "x" => Ok(*value as f64),
+                "dpi" => Ok(*value as f64 / 96.0),
+                "dpcm" => Ok(*value as f64 * 2.54 / 96.0),
+
+                _ => Err(location
+                    .new_custom_error(CustomParseError::InvalidLengthUnits(unit.to_string()))),
+            }
+        }
+
+        t => {
+            let t = t.clone();
+            Err(location.new_unexpected_token_error(t))
         }
     }
-    */
 }
 
 fn parse_selectors<'i, 't>(
@@ -182,7 +333,8 @@ fn parse_selectors<'i, 't>(
 
     let mut selector = Selector::default();
 
-    let mut _first_token_in_selector = true;
+    // Whitespace at the start of a selector or after a combinator is not a descendant combinator
+    let mut first_token_in_selector = true;
     let mut whitespace = false;
     while let Ok(t) = input.next_including_whitespace() {
         match t {
@@ -212,6 +364,26 @@ fn parse_selectors<'i, 't>(
                 //     selec.relation = Relation::Parent;
                 // }
                 //selector.relation = Some(Box::new(SelectorRelation::Parent(old_selector)));
+                first_token_in_selector = true;
+                continue;
+            }
+
+            Token::Delim('+') => {
+                selector.relation = SelectorRelation::AdjacentSibling;
+                selectors.push(selector);
+                selector = Selector::default();
+                whitespace = false;
+                first_token_in_selector = true;
+                continue;
+            }
+
+            Token::Delim('~') => {
+                selector.relation = SelectorRelation::GeneralSibling;
+                selectors.push(selector);
+                selector = Selector::default();
+                whitespace = false;
+                first_token_in_selector = true;
+                continue;
             }
 
             // Id
@@ -249,22 +421,20 @@ fn parse_selectors<'i, 't>(
             }
 
             Token::WhiteSpace(_ws) => {
-                // Parent relations can (and in almost every style guide, do) have whitespace
-                // surrounding the `>`. In those cases we should treat this as if there were no
+                // Combinators can (and in almost every style guide, do) have whitespace
+                // surrounding them. In those cases we should treat this as if there were no
                 // whitespace since the rest of this parser uses whitespace strictly to indicate
-                // regular nesting. Bit of a hack, but I didn't dare touching the rest of the
-                // parser.
-                whitespace = !matches!(
-                    selectors.last(),
-                    Some(Selector { relation: SelectorRelation::Parent, .. })
-                );
+                // regular nesting.
+                if first_token_in_selector {
+                    continue;
+                }
+
+                whitespace = true;
             }
 
             // Pseudo-class
-            Token::Colon => {
-                let pseudo_class_str = input.expect_ident()?.to_owned();
-
-                match pseudo_class_str.as_ref() {
+            Token::Colon => match input.next()?.clone() {
+                Token::Ident(pseudo_class_str) => match pseudo_class_str.as_ref() {
                     "hover" => selector.pseudo_classes.insert(PseudoClass::HOVER),
                     "over" => selector.pseudo_classes.insert(PseudoClass::OVER),
                     "active" => selector.pseudo_classes.insert(PseudoClass::ACTIVE),
@@ -273,27 +443,53 @@ fn parse_selectors<'i, 't>(
                     "checked" => selector.pseudo_classes.insert(PseudoClass::CHECKED),
                     "selected" => selector.pseudo_classes.insert(PseudoClass::SELECTED),
                     "custom" => selector.pseudo_classes.insert(PseudoClass::CUSTOM),
+                    "first-child" => selector.pseudo_classes.insert(PseudoClass::FIRST_CHILD),
+                    "last-child" => selector.pseudo_classes.insert(PseudoClass::LAST_CHILD),
+                    "root" => selector.pseudo_classes.insert(PseudoClass::ROOT),
+                    "drop-target" => selector.pseudo_classes.insert(PseudoClass::DROP_TARGET),
+
+                    _ => {
+                        return Err(unrecognised_pseudo_class(input, &pseudo_class_str));
+                    }
+                },
+
+                Token::Function(function_name) => match function_name.as_ref() {
+                    "nth-child" => {
+                        let (a, b) = input.parse_nested_block(|input| {
+                            cssparser::parse_nth(input)
+                                .map_err(ParseError::<'_, CustomParseError>::from)
+                        })?;
+
+                        selector.nth_child.push(NthChild::new(a, b));
+                    }
+
+                    "not" => {
+                        let not = input.parse_nested_block(|input| parse_selectors(input))?;
+
+                        // Only compound selectors are allowed in a negation
+                        if not.iter().any(|not| !matches!(not.relation, SelectorRelation::None)) {
+                            return Err(unrecognised_pseudo_class(input, "not"));
+                        }
+
+                        selector.not.extend(not);
+                    }
 
                     _ => {
-                        let parse_error = ParseError {
-                            kind: ParseErrorKind::Custom(
-                                CustomParseError::UnrecognisedPseudoclass(
-                                    pseudo_class_str.to_string(),
-                                ),
-                            ),
-                            location: input.current_source_location(),
-                        };
-
-                        return Err(parse_error);
+                        return Err(unrecognised_pseudo_class(input, &function_name));
                     }
+                },
+
+                t => {
+                    return Err(input.new_basic_unexpected_token_error(t).into());
                 }
-            }
+            },
 
             // This selector is done, on to the next one
             Token::Comma => {
                 selectors.push(selector);
                 selector = Selector::default();
-                _first_token_in_selector = true;
+                whitespace = false;
+                first_token_in_selector = true;
                 continue; // need to continue to avoid `first_token_in_selector` being set to false
             }
 
@@ -307,7 +503,7 @@ fn parse_selectors<'i, 't>(
             }
         }
 
-        _first_token_in_selector = false;
+        first_token_in_selector = false;
     }
 
     selectors.push(selector);
@@ -323,6 +519,18 @@ fn parse_selectors<'i, 't>(
     Ok(selectors)
 }
 
+fn unrecognised_pseudo_class<'i>(
+    input: &Parser<'i, '_>,
+    pseudo_class: &str,
+) -> ParseError<'i, CustomParseError> {
+    ParseError {
+        kind: ParseErrorKind::Custom(CustomParseError::UnrecognisedPseudoclass(
+            pseudo_class.to_string(),
+        )),
+        location: input.current_source_location(),
+    }
+}
+
 // fn parse_selector<'i,'t>(input: &mut Parser<'i,'t>) -> Result<Selector, ParseError<'i, CustomParseError>> {
 //     let mut selector = Selector::default();
 
@@ -356,11 +564,30 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
         name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        // Custom properties keep their value as written so it can be substituted into others
+        if name.starts_with("--") {
+            return Ok(Property::Variable(name.to_string(), parse_raw_value(input)));
+        }
+
+        // Declarations which reference custom properties are resolved for each entity
+        let state = input.state();
+        if references_variables(input) {
+            input.reset(&state);
+            return Ok(Property::Unresolved(name.to_string(), parse_raw_value(input)));
+        }
+        input.reset(&state);
+
         Ok(match &*name {
             // Colors
             "background-color" => Property::BackgroundColor(parse_color(input)?),
             "color" => Property::FontColor(parse_color(input)?),
-            "background-image" => Property::BackgroundImage(parse_string(input)?),
+            "background-image" => {
+                if let Ok(gradients) = input.try_parse(parse_gradients) {
+                    Property::BackgroundGradient(gradients)
+                } else {
+                    Property::BackgroundImage(parse_string(input)?)
+                }
+            }
 
             // Position
             "position" | "position-type" => Property::PositionType(parse_position_type(input)?),
@@ -460,6 +687,11 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
 
             "z-index" => Property::ZIndex(parse_z_index(input)?),
 
+            // Transform
+            "translate" => Property::Translate(parse_translate(input)?),
+            "rotate" => Property::Rotate(parse_angle(input)?),
+            "scale" => Property::Scale(parse_scale(input)?),
+
             "cursor" => Property::Cursor(parse_cursor(input)?),
 
             ident => Property::Unknown(ident.to_owned(), parse_unknown(input)?),
@@ -481,6 +713,118 @@ impl<'i> cssparser::AtRuleParser<'i> for DeclarationParser {
     type Error = CustomParseError;
 }
 
+/// Parses a list of declarations, such as the contents of a rule, skipping any invalid ones.
+pub(crate) fn parse_declarations(declarations: &str) -> Vec<Property> {
+    let mut input = ParserInput::new(declarations);
+    let mut parser = Parser::new(&mut input);
+
+    DeclarationListParser::new(&mut parser, DeclarationParser {})
+        .filter_map(|property| property.ok())
+        .collect()
+}
+
+/// Replaces the `var()` references in a value with the values of the custom properties.
+///
+/// Returns `None` if a referenced custom property is not defined and has no fallback.
+pub(crate) fn substitute_variables(
+    value: &str,
+    variables: &FnvHashMap<String, String>,
+) -> Option<String> {
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+    let mut output = String::new();
+
+    substitute_variables_into(&mut parser, variables, &mut output).ok()?;
+
+    Some(output)
+}
+
+fn substitute_variables_into<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    variables: &FnvHashMap<String, String>,
+    output: &mut String,
+) -> Result<(), ParseError<'i, CustomParseError>> {
+    loop {
+        let start = input.position();
+        let token = match input.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => return Ok(()),
+        };
+
+        match token {
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                input.parse_nested_block(|input| {
+                    let name = input.expect_ident()?.clone();
+
+                    if let Some(value) = variables.get(&*name) {
+                        output.push_str(value);
+
+                        // Skip the unused fallback
+                        while input.next_including_whitespace_and_comments().is_ok() {}
+
+                        return Ok(());
+                    }
+
+                    // Use the fallback, which can reference other custom properties
+                    input.expect_comma()?;
+                    substitute_variables_into(input, variables, output)
+                })?;
+            }
+
+            Token::Function(_)
+            | Token::ParenthesisBlock
+            | Token::SquareBracketBlock
+            | Token::CurlyBracketBlock => {
+                output.push_str(input.slice_from(start));
+                input.parse_nested_block(|input| {
+                    substitute_variables_into(input, variables, output)
+                })?;
+                output.push(match token {
+                    Token::SquareBracketBlock => ']',
+                    Token::CurlyBracketBlock => '}',
+                    _ => ')',
+                });
+            }
+
+            _ => output.push_str(input.slice_from(start)),
+        }
+    }
+}
+
+// Returns true if the rest of the input contains a `var()` function
+fn references_variables(input: &mut Parser) -> bool {
+    while let Ok(token) = input.next() {
+        match token.clone() {
+            Token::Function(name) if name.eq_ignore_ascii_case("var") => return true,
+
+            Token::Function(_)
+            | Token::ParenthesisBlock
+            | Token::SquareBracketBlock
+            | Token::CurlyBracketBlock => {
+                let nested = input.parse_nested_block(|input| {
+                    Ok::<_, ParseError<'_, ()>>(references_variables(input))
+                });
+
+                if nested.unwrap_or_default() {
+                    return true;
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    false
+}
+
+// Consumes the rest of the input and returns it as written
+fn parse_raw_value(input: &mut Parser) -> String {
+    let start = input.position();
+    while input.next().is_ok() {}
+
+    input.slice_from(start).trim().to_owned()
+}
+
 fn css_color(name: &str) -> Option<Color> {
     Some(match name {
         "transparent" => Color::from(name),
@@ -752,56 +1096,484 @@ fn parse_length2<'i>(token: &Token<'i>) -> Result<Units, ParseError<'i, CustomPa
     }
 }
 
+// Parses a single transition, e.g. `background-color 0.5 0.1 ease-in-out`
+//
+// Durations and delays can be given in seconds as plain numbers or with `s` or `ms` units. The
+// optional delay and timing function can be given in either order after the duration.
 fn parse_transition2<'i, 't>(
     input: &mut Parser<'i, 't>,
 ) -> Result<Transition, ParseError<'i, CustomParseError>> {
     let mut transition = Transition::new();
 
-    Ok(match input.next()? {
-        Token::Ident(s) => {
-            transition.property = s.to_string();
+    transition.property = input.expect_ident()?.to_string();
+    transition.duration = parse_time(input)?;
 
-            match input.next()? {
-                Token::Number { value: x, .. } => {
-                    transition.duration = *x;
+    let mut delay = None;
+    let mut timing_function = None;
+    while !input.is_exhausted() {
+        if delay.is_none() {
+            if let Ok(time) = input.try_parse(parse_time) {
+                delay = Some(time);
+                continue;
+            }
+        }
 
-                    match input.next()? {
-                        Token::Number { value: x, .. } => {
-                            transition.delay = *x;
-                        }
+        if timing_function.is_none() {
+            timing_function = Some(parse_timing_function(input)?);
+            continue;
+        }
 
-                        _t => {
-                            // let basic_error = BasicParseError {
-                            //     kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                            //     location: SourceLocation { line: 0, column: 0 },
-                            // };
-                            // return Err(basic_error.into());
-                        }
+        let location = input.current_source_location();
+        let token = input.next()?.clone();
+        return Err(location.new_unexpected_token_error(token));
+    }
+
+    transition.delay = delay.unwrap_or_default();
+    transition.timing_function = timing_function.unwrap_or_default();
+
+    Ok(transition)
+}
+
+// Parses a time in seconds, e.g. `0.5`, `0.5s` or `500ms`
+fn parse_time<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    match input.next()? {
+        Token::Number { value, .. } => Ok(*value),
+
+        Token::Dimension { value, unit, .. } => {
+            match &**unit {
+                "s" => Ok(*value),
+                "ms" => Ok(*value / 1000.0),
+                _ => Err(location
+                    .new_custom_error(CustomParseError::InvalidLengthUnits(unit.to_string()))),
+            }
+        }
+
+        t => {
+            let t = t.clone();
+            Err(location.new_unexpected_token_error(t))
+        }
+    }
+}
+
+// Parses a timing function, e.g. `ease-in`, `cubic-bezier(0.1, 0.7, 1.0, 0.1)`, `steps(4, start)`
+// or `spring(200, 10)`, where the arguments of a spring are its stiffness, damping and optional mass
+fn parse_timing_function<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<TimingFunction, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    match input.next()? {
+        Token::Ident(name) => match &**name {
+            "linear" => Ok(TimingFunction::Linear),
+            "ease" => Ok(TimingFunction::Ease),
+            "ease-in" => Ok(TimingFunction::EaseIn),
+            "ease-out" => Ok(TimingFunction::EaseOut),
+            "ease-in-out" => Ok(TimingFunction::EaseInOut),
+            "step-start" => Ok(TimingFunction::Steps(1, StepPosition::Start)),
+            "step-end" => Ok(TimingFunction::Steps(1, StepPosition::End)),
+            _ => {
+                let name = name.clone();
+                Err(location.new_unexpected_token_error(Token::Ident(name)))
+            }
+        },
+
+        Token::Function(name) if &**name == "cubic-bezier" => input.parse_nested_block(|input| {
+            let x1 = input.expect_number()?;
+            input.expect_comma()?;
+            let y1 = input.expect_number()?;
+            input.expect_comma()?;
+            let x2 = input.expect_number()?;
+            input.expect_comma()?;
+            let y2 = input.expect_number()?;
+
+            if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                return Err(location.new_custom_error(CustomParseError::InvalidValue(format!(
+                    "cubic-bezier({}, {}, {}, {})",
+                    x1, y1, x2, y2
+                ))));
+            }
+
+            Ok(TimingFunction::CubicBezier(x1, y1, x2, y2))
+        }),
+
+        Token::Function(name) if &**name == "steps" => input.parse_nested_block(|input| {
+            let steps = input.expect_integer()?;
+            if steps < 1 {
+                return Err(
+                    location.new_custom_error(CustomParseError::InvalidValue(steps.to_string()))
+                );
+            }
+
+            let position = if input.try_parse(|input| input.expect_comma()).is_ok() {
+                let location = input.current_source_location();
+                let position = input.expect_ident()?;
+                match &**position {
+                    "start" | "jump-start" => StepPosition::Start,
+                    "end" | "jump-end" => StepPosition::End,
+                    _ => {
+                        let position = position.clone();
+                        return Err(location.new_unexpected_token_error(Token::Ident(position)));
                     }
                 }
+            } else {
+                StepPosition::End
+            };
 
-                t => {
-                    let basic_error = BasicParseError {
-                        kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                        location: SourceLocation { line: 0, column: 0 },
-                    };
-                    return Err(basic_error.into());
+            Ok(TimingFunction::Steps(steps as u32, position))
+        }),
+
+        Token::Function(name) if &**name == "spring" => input.parse_nested_block(|input| {
+            let stiffness = input.expect_number()?;
+            input.expect_comma()?;
+            let damping = input.expect_number()?;
+            let mass = if input.try_parse(|input| input.expect_comma()).is_ok() {
+                input.expect_number()?
+            } else {
+                1.0
+            };
+
+            Ok(TimingFunction::Spring { stiffness, damping, mass })
+        }),
+
+        t => {
+            let t = t.clone();
+            Err(location.new_unexpected_token_error(t))
+        }
+    }
+}
+
+// Parses a comma separated list of gradients, e.g. `radial-gradient(white, black), linear-gradient(45deg, red, blue)`
+fn parse_gradients<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<Gradient>, ParseError<'i, CustomParseError>> {
+    input.parse_comma_separated(parse_gradient)
+}
+
+fn parse_gradient<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Gradient, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+    let name = input.expect_function()?.clone();
+
+    input.parse_nested_block(|input| match &*name {
+        "linear-gradient" => parse_linear_gradient(input).map(Gradient::Linear),
+        "radial-gradient" => parse_radial_gradient(input).map(Gradient::Radial),
+        "conic-gradient" => parse_conic_gradient(input).map(Gradient::Conic),
+        _ => Err(location.new_unexpected_token_error(Token::Function(name.clone()))),
+    })
+}
+
+fn parse_linear_gradient<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<LinearGradient, ParseError<'i, CustomParseError>> {
+    let direction = if let Ok(angle) = input.try_parse(parse_angle) {
+        input.expect_comma()?;
+        GradientDirection::Angle(angle)
+    } else if input.try_parse(|input| input.expect_ident_matching("to")).is_ok() {
+        let direction = parse_gradient_side(input)?;
+        input.expect_comma()?;
+        direction
+    } else {
+        GradientDirection::TopToBottom
+    };
+
+    Ok(LinearGradient { direction, stops: parse_gradient_stops(input, false)? })
+}
+
+// Parses the side or corner after `to`, with corners pointing diagonally
+fn parse_gradient_side<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<GradientDirection, ParseError<'i, CustomParseError>> {
+    let first = input.expect_ident_cloned()?;
+    let second = input.try_parse(|input| input.expect_ident_cloned()).ok();
+
+    let mut horizontal = None;
+    let mut vertical = None;
+    for side in std::iter::once(&first).chain(second.as_ref()) {
+        match &**side {
+            "left" if horizontal.is_none() => horizontal = Some(-1.0),
+            "right" if horizontal.is_none() => horizontal = Some(1.0),
+            "top" if vertical.is_none() => vertical = Some(-1.0),
+            "bottom" if vertical.is_none() => vertical = Some(1.0),
+            _ => {
+                return Err(input.new_custom_error(CustomParseError::InvalidValue(side.to_string())))
+            }
+        }
+    }
+
+    Ok(match (horizontal, vertical) {
+        (Some(x), None) if x > 0.0 => GradientDirection::LeftToRight,
+        (Some(_), None) => GradientDirection::RightToLeft,
+        (None, Some(y)) if y > 0.0 => GradientDirection::TopToBottom,
+        (None, Some(_)) => GradientDirection::BottomToTop,
+        (x, y) => {
+            let (x, y): (f32, f32) = (x.unwrap_or_default(), y.unwrap_or_default());
+            GradientDirection::Angle(x.atan2(-y).to_degrees().rem_euclid(360.0))
+        }
+    })
+}
+
+fn parse_radial_gradient<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<RadialGradient, ParseError<'i, CustomParseError>> {
+    let mut gradient = RadialGradient::default();
+    let mut has_prelude = false;
+
+    loop {
+        // Radial gradients are always drawn as circles
+        if input
+            .try_parse(|input| -> Result<(), ParseError<'i, CustomParseError>> {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                match &**ident {
+                    "circle" | "ellipse" => Ok(()),
+                    _ => Err(location.new_unexpected_token_error(Token::Ident(ident.clone()))),
                 }
+            })
+            .is_ok()
+        {
+            has_prelude = true;
+        } else if let Ok(size) = input.try_parse(parse_radial_gradient_size) {
+            gradient.size = size;
+            has_prelude = true;
+        } else if input.try_parse(|input| input.expect_ident_matching("at")).is_ok() {
+            gradient.position = parse_gradient_position(input)?;
+            has_prelude = true;
+        } else {
+            break;
+        }
+    }
+
+    if has_prelude {
+        input.expect_comma()?;
+    }
+
+    gradient.stops = parse_gradient_stops(input, false)?;
+
+    Ok(gradient)
+}
+
+fn parse_radial_gradient_size<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<RadialGradientSize, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match &**name {
+            "closest-side" => RadialGradientSize::ClosestSide,
+            "farthest-side" => RadialGradientSize::FarthestSide,
+            "closest-corner" => RadialGradientSize::ClosestCorner,
+            "farthest-corner" => RadialGradientSize::FarthestCorner,
+            _ => {
+                let t = Token::Ident(name.clone());
+                return Err(location.new_unexpected_token_error(t));
             }
+        },
+
+        Token::Percentage { unit_value, .. } => {
+            RadialGradientSize::Radius(Units::Percentage(*unit_value * 100.0))
+        }
 
-            transition
+        Token::Dimension { value, unit, .. } if &**unit == "px" => {
+            RadialGradientSize::Radius(Units::Pixels(*value))
         }
 
         t => {
-            let basic_error = BasicParseError {
-                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                location: SourceLocation { line: 0, column: 0 },
-            };
-            return Err(basic_error.into());
+            let t = t.clone();
+            return Err(location.new_unexpected_token_error(t));
         }
     })
 }
 
+fn parse_conic_gradient<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<ConicGradient, ParseError<'i, CustomParseError>> {
+    let mut gradient = ConicGradient::default();
+    let mut has_prelude = false;
+
+    if input.try_parse(|input| input.expect_ident_matching("from")).is_ok() {
+        gradient.angle = parse_angle(input)?;
+        has_prelude = true;
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("at")).is_ok() {
+        gradient.position = parse_gradient_position(input)?;
+        has_prelude = true;
+    }
+
+    if has_prelude {
+        input.expect_comma()?;
+    }
+
+    gradient.stops = parse_gradient_stops(input, true)?;
+
+    Ok(gradient)
+}
+
+// Parses the stops of a gradient, where the positions of the stops of conic gradients can be angles
+fn parse_gradient_stops<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    angular: bool,
+) -> Result<Vec<GradientStop>, ParseError<'i, CustomParseError>> {
+    input.parse_comma_separated(|input| {
+        let color = parse_color(input)?;
+        let position = input
+            .try_parse(|input| parse_gradient_stop_position(input, angular))
+            .unwrap_or(Units::Auto);
+
+        Ok(GradientStop::new(position, color))
+    })
+}
+
+fn parse_gradient_stop_position<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    angular: bool,
+) -> Result<Units, ParseError<'i, CustomParseError>> {
+    if angular {
+        if let Ok(angle) = input.try_parse(parse_angle) {
+            return Ok(Units::Percentage(angle / 360.0 * 100.0));
+        }
+    }
+
+    let location = input.current_source_location();
+
+    match input.next()? {
+        Token::Percentage { unit_value, .. } => Ok(Units::Percentage(*unit_value * 100.0)),
+        Token::Dimension { value, unit, .. } if !angular && &**unit == "px" => {
+            Ok(Units::Pixels(*value))
+        }
+        Token::Number { value, .. } if *value == 0.0 => Ok(Units::Percentage(0.0)),
+
+        t => {
+            let t = t.clone();
+            Err(location.new_unexpected_token_error(t))
+        }
+    }
+}
+
+// Parses the position of the center of a gradient, e.g. `top left` or `20px 50%`
+fn parse_gradient_position<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(Units, Units), ParseError<'i, CustomParseError>> {
+    // Keywords which can only be horizontal or vertical are returned with their axis
+    fn component<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<(Units, Option<bool>), ParseError<'i, CustomParseError>> {
+        let location = input.current_source_location();
+
+        Ok(match input.next()? {
+            Token::Ident(name) => match &**name {
+                "left" => (Units::Percentage(0.0), Some(true)),
+                "right" => (Units::Percentage(100.0), Some(true)),
+                "top" => (Units::Percentage(0.0), Some(false)),
+                "bottom" => (Units::Percentage(100.0), Some(false)),
+                "center" => (Units::Percentage(50.0), None),
+                _ => {
+                    let t = Token::Ident(name.clone());
+                    return Err(location.new_unexpected_token_error(t));
+                }
+            },
+
+            Token::Percentage { unit_value, .. } => (Units::Percentage(*unit_value * 100.0), None),
+            Token::Dimension { value, unit, .. } if &**unit == "px" => {
+                (Units::Pixels(*value), None)
+            }
+            Token::Number { value, .. } if *value == 0.0 => (Units::Pixels(0.0), None),
+
+            t => {
+                let t = t.clone();
+                return Err(location.new_unexpected_token_error(t));
+            }
+        })
+    }
+
+    let first = component(input)?;
+    let second = input.try_parse(component).unwrap_or((Units::Percentage(50.0), None));
+
+    if first.1 == Some(false) || second.1 == Some(true) {
+        Ok((second.0, first.0))
+    } else {
+        Ok((first.0, second.0))
+    }
+}
+
+// Parses a horizontal and optional vertical translation in pixels
+fn parse_translate<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(f32, f32), ParseError<'i, CustomParseError>> {
+    fn length<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, ParseError<'i, CustomParseError>> {
+        let location = input.current_source_location();
+
+        match input.next()? {
+            Token::Dimension { value, unit, .. } if &**unit == "px" => Ok(*value),
+            Token::Number { value, .. } => Ok(*value),
+
+            t => {
+                let t = t.clone();
+                Err(location.new_unexpected_token_error(t))
+            }
+        }
+    }
+
+    let x = length(input)?;
+    let y = input.try_parse(length).unwrap_or_default();
+
+    Ok((x, y))
+}
+
+// Parses a horizontal and optional vertical scale factor, with one value scaling both axes
+fn parse_scale<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(f32, f32), ParseError<'i, CustomParseError>> {
+    fn factor<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, ParseError<'i, CustomParseError>> {
+        let location = input.current_source_location();
+
+        match input.next()? {
+            Token::Number { value, .. } => Ok(*value),
+            Token::Percentage { unit_value, .. } => Ok(*unit_value),
+
+            t => {
+                let t = t.clone();
+                Err(location.new_unexpected_token_error(t))
+            }
+        }
+    }
+
+    let x = factor(input)?;
+    let y = input.try_parse(factor).unwrap_or(x);
+
+    Ok((x, y))
+}
+
+// Parses an angle in degrees
+fn parse_angle<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    match input.next()? {
+        Token::Dimension { value, unit, .. } => {
+            match &**unit {
+                "deg" => Ok(*value),
+                "grad" => Ok(*value * 0.9),
+                "rad" => Ok(value.to_degrees()),
+                "turn" => Ok(*value * 360.0),
+                _ => Err(location
+                    .new_custom_error(CustomParseError::InvalidLengthUnits(unit.to_string()))),
+            }
+        }
+
+        Token::Number { value, .. } if *value == 0.0 => Ok(0.0),
+
+        t => {
+            let t = t.clone();
+            Err(location.new_unexpected_token_error(t))
+        }
+    }
+}
+
 fn parse_units<'i, 't>(
     input: &mut Parser<'i, 't>,
 ) -> Result<Units, ParseError<'i, CustomParseError>> {
@@ -1183,5 +1955,136 @@ pub(crate) fn _parse(s: &str) -> Vec<StyleRule> {
         rule_list_parser.collect::<Vec<_>>()
     };
 
-    rules.into_iter().filter_map(|rule| rule.ok()).collect()
+    rules.into_iter().filter_map(|rule| rule.ok()).flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selectors(css: &str) -> Vec<Selector> {
+        let mut rules = _parse(&format!("{} {{}}", css));
+        assert_eq!(rules.len(), 1, "Failed to parse {}", css);
+        rules.remove(0).selectors
+    }
+
+    #[test]
+    fn sibling_combinators() {
+        let parsed = selectors("label + button ~ .item");
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].element.as_deref(), Some("label"));
+        assert!(matches!(parsed[0].relation, SelectorRelation::AdjacentSibling));
+        assert_eq!(parsed[1].element.as_deref(), Some("button"));
+        assert!(matches!(parsed[1].relation, SelectorRelation::GeneralSibling));
+        assert!(parsed[2].classes.contains("item"));
+        assert!(matches!(parsed[2].relation, SelectorRelation::None));
+
+        // Whitespace around combinators isn't a descendant combinator
+        let parsed = selectors("label+button");
+        assert_eq!(parsed.len(), 2);
+        assert!(matches!(parsed[0].relation, SelectorRelation::AdjacentSibling));
+    }
+
+    #[test]
+    fn structural_pseudo_classes() {
+        let parsed = selectors("element:first-child:last-child");
+        assert!(parsed[0].pseudo_classes.contains(PseudoClass::FIRST_CHILD));
+        assert!(parsed[0].pseudo_classes.contains(PseudoClass::LAST_CHILD));
+
+        let parsed = selectors(":root");
+        assert!(parsed[0].pseudo_classes.contains(PseudoClass::ROOT));
+    }
+
+    #[test]
+    fn nth_child() {
+        assert_eq!(selectors("element:nth-child(odd)")[0].nth_child, vec![NthChild::new(2, 1)]);
+        assert_eq!(selectors("element:nth-child(2n)")[0].nth_child, vec![NthChild::new(2, 0)]);
+        assert_eq!(selectors("element:nth-child(-n+3)")[0].nth_child, vec![NthChild::new(-1, 3)]);
+        assert_eq!(selectors("element:nth-child(4)")[0].nth_child, vec![NthChild::new(0, 4)]);
+
+        assert!(_parse("element:nth-child(x) {}").is_empty());
+    }
+
+    #[test]
+    fn not() {
+        let parsed = selectors("button:not(.primary):not(:disabled)");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].not.len(), 2);
+        assert!(parsed[0].not[0].classes.contains("primary"));
+        assert!(parsed[0].not[1].pseudo_classes.contains(PseudoClass::DISABLED));
+
+        // Only compound selectors can be negated
+        assert!(_parse("button:not(.a > .b) {}").is_empty());
+        assert!(_parse("button:not(.a + .b) {}").is_empty());
+    }
+
+    #[test]
+    fn unknown_pseudo_class() {
+        assert!(_parse("button:unknown {}").is_empty());
+        assert!(_parse("button:unknown(1) {}").is_empty());
+    }
+
+    fn variables(variables: &[(&str, &str)]) -> FnvHashMap<String, String> {
+        variables.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn substitute_defined_variables() {
+        let variables = variables(&[("--size", "10px"), ("--color", "red")]);
+
+        assert_eq!(substitute_variables("var(--size)", &variables).as_deref(), Some("10px"));
+        assert_eq!(
+            substitute_variables("1px solid var(--color)", &variables).as_deref(),
+            Some("1px solid red")
+        );
+        // References nested in other functions are substituted too
+        assert_eq!(
+            substitute_variables("calc(var(--size) + 2px)", &variables).as_deref(),
+            Some("calc(10px + 2px)")
+        );
+        assert_eq!(substitute_variables("5px", &variables).as_deref(), Some("5px"));
+    }
+
+    #[test]
+    fn substitute_fallbacks() {
+        let variables = variables(&[("--color", "red")]);
+
+        assert_eq!(substitute_variables("var(--size, 4px)", &variables).as_deref(), Some(" 4px"));
+        assert_eq!(substitute_variables("var(--color, blue)", &variables).as_deref(), Some("red"));
+        // Fallbacks can reference other custom properties
+        assert_eq!(
+            substitute_variables("var(--size, var(--color))", &variables).as_deref(),
+            Some(" red")
+        );
+        assert_eq!(
+            substitute_variables("var(--size, var(--other, 1px))", &variables).as_deref(),
+            Some("  1px")
+        );
+    }
+
+    #[test]
+    fn substitute_undefined_variables() {
+        let variables = variables(&[("--color", "red")]);
+
+        assert_eq!(substitute_variables("var(--size)", &variables), None);
+        assert_eq!(substitute_variables("1px var(--size)", &variables), None);
+        assert_eq!(substitute_variables("var(--size, var(--other))", &variables), None);
+    }
+
+    #[test]
+    fn declarations_with_variables() {
+        let properties = parse_declarations(
+            "--size: 10px; width: var(--size, 4px); height: 5px; color: var(--color);",
+        );
+
+        assert_eq!(
+            properties,
+            vec![
+                Property::Variable("--size".to_string(), "10px".to_string()),
+                Property::Unresolved("width".to_string(), "var(--size, 4px)".to_string()),
+                Property::Height(Units::Pixels(5.0)),
+                Property::Unresolved("color".to_string(), "var(--color)".to_string()),
+            ]
+        );
+    }
 }
@@ -14,6 +14,13 @@ pub enum PropType {
 pub(crate) enum Property {
     Unknown(String, PropType),
 
+    // Custom Properties
+    /// A custom property declaration, e.g. `--accent: #3a86ff`, with the value as written.
+    Variable(String, String),
+    /// A declaration whose value references custom properties with `var()`. The value is kept as
+    /// written and resolved separately for each entity.
+    Unresolved(String, String),
+
     // General
     Display(Display),
     Visibility(Visibility),
@@ -85,8 +92,8 @@ pub(crate) enum Property {
     // Background
     BackgroundColor(Color),
     BackgroundImage(String),
-    // TODO
-    //BackgroundGradient(LinearGradient),
+    /// Gradient background layers, with the first layer drawn on top.
+    BackgroundGradient(Vec<Gradient>),
 
     // Font
     FontSize(f32),
@@ -113,10 +120,11 @@ pub(crate) enum Property {
 
     ZIndex(i32),
 
-    // TODO
-    // Translate((f32, f32)),
-    // Rotate(f32),
-    // Scale((f32, f32)),
+    // Transform
+    Translate((f32, f32)),
+    Rotate(f32),
+    Scale((f32, f32)),
+
     Cursor(CursorIcon),
 }
 
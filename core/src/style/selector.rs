@@ -11,7 +11,7 @@ bitflags! {
     /// A bitflag of possible pseudoclasses.
     ///
     /// This type is part of the prelude.
-    pub struct PseudoClass: u8 {
+    pub struct PseudoClass: u16 {
         const HOVER = 1;
         const OVER = 1 << 1;
         const ACTIVE = 1 << 2;
@@ -20,6 +20,29 @@ bitflags! {
         const CHECKED = 1 << 5;
         const SELECTED = 1 << 6;
         const CUSTOM = 1 << 7;
+        /// Matches views which are the first child of their parent. This is computed from the
+        /// tree when matching and is never set on a view.
+        const FIRST_CHILD = 1 << 8;
+        /// Matches views which are the last child of their parent. This is computed from the
+        /// tree when matching and is never set on a view.
+        const LAST_CHILD = 1 << 9;
+        /// Matches the root view. This is computed from the tree when matching and is never set
+        /// on a view.
+        const ROOT = 1 << 10;
+        /// Matches a drop target while the cursor is over it with a payload it accepts.
+        const DROP_TARGET = 1 << 11;
+    }
+}
+
+impl PseudoClass {
+    /// The pseudo-classes which depend on the siblings of a view.
+    pub(crate) fn structural() -> Self {
+        PseudoClass::FIRST_CHILD | PseudoClass::LAST_CHILD
+    }
+
+    /// The pseudo-classes which are computed from the tree rather than set on a view.
+    pub(crate) fn computed() -> Self {
+        PseudoClass::structural() | PseudoClass::ROOT
     }
 }
 
@@ -52,16 +75,65 @@ impl std::fmt::Display for PseudoClass {
         if self.contains(PseudoClass::SELECTED) {
             write!(f, ":selected")?;
         }
+        if self.contains(PseudoClass::FIRST_CHILD) {
+            write!(f, ":first-child")?;
+        }
+        if self.contains(PseudoClass::LAST_CHILD) {
+            write!(f, ":last-child")?;
+        }
+        if self.contains(PseudoClass::ROOT) {
+            write!(f, ":root")?;
+        }
+        if self.contains(PseudoClass::DROP_TARGET) {
+            write!(f, ":drop-target")?;
+        }
 
         Ok(())
     }
 }
 
+/// The `an+b` argument of the `:nth-child()` pseudo-class.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NthChild {
+    pub a: i32,
+    pub b: i32,
+}
+
+impl NthChild {
+    pub fn new(a: i32, b: i32) -> Self {
+        Self { a, b }
+    }
+
+    /// Returns true if the one-based index of a child is `an+b` for some `n >= 0`.
+    pub fn matches(&self, index: i32) -> bool {
+        let offset = index - self.b;
+
+        if self.a == 0 {
+            offset == 0
+        } else {
+            offset % self.a == 0 && offset / self.a >= 0
+        }
+    }
+}
+
+impl std::fmt::Display for NthChild {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, ":nth-child({}n{:+})", self.a, self.b)
+    }
+}
+
+/// How a selector relates to the selector which follows it.
 #[derive(Clone, Debug)]
 pub enum SelectorRelation {
     None,
+    /// `a b`, the selector matches an ancestor.
     Ancestor,
+    /// `a > b`, the selector matches the parent.
     Parent,
+    /// `a + b`, the selector matches the previous sibling.
+    AdjacentSibling,
+    /// `a ~ b`, the selector matches any previous sibling.
+    GeneralSibling,
 }
 
 /// A style selector.
@@ -71,6 +143,10 @@ pub struct Selector {
     pub element: Option<String>,
     pub classes: HashSet<String>,
     pub pseudo_classes: PseudoClass,
+    /// The arguments of any `:nth-child()` pseudo-classes, all of which must match.
+    pub nth_child: Vec<NthChild>,
+    /// The arguments of any `:not()` pseudo-classes, none of which may match.
+    pub not: Vec<Selector>,
     pub relation: SelectorRelation,
     pub asterisk: bool,
 }
@@ -82,6 +158,8 @@ impl Default for Selector {
             element: None,
             classes: HashSet::new(),
             pseudo_classes: PseudoClass::empty(),
+            nth_child: Vec::new(),
+            not: Vec::new(),
             relation: SelectorRelation::None,
             asterisk: false,
         }
@@ -108,10 +186,20 @@ impl std::fmt::Display for Selector {
 
         write!(f, "{}", self.pseudo_classes)?;
 
+        for nth_child in self.nth_child.iter() {
+            write!(f, "{}", nth_child)?;
+        }
+
+        for not in self.not.iter() {
+            write!(f, ":not({})", not)?;
+        }
+
         match self.relation {
             SelectorRelation::None => {}
             SelectorRelation::Ancestor => write!(f, " ")?,
             SelectorRelation::Parent => write!(f, ">")?,
+            SelectorRelation::AdjacentSibling => write!(f, "+")?,
+            SelectorRelation::GeneralSibling => write!(f, "~")?,
         }
 
         Ok(())
@@ -217,12 +305,32 @@ impl Selector {
         true
     }
 
+    /// Returns true if matching the selector depends on the siblings of a view.
+    pub(crate) fn is_structural(&self) -> bool {
+        self.pseudo_classes.intersects(PseudoClass::structural())
+            || !self.nth_child.is_empty()
+            || matches!(
+                self.relation,
+                SelectorRelation::AdjacentSibling | SelectorRelation::GeneralSibling
+            )
+            || self.not.iter().any(|not| not.is_structural())
+    }
+
     pub(crate) fn specificity(&self) -> Specificity {
-        Specificity([
+        let mut specificity = Specificity([
             if self.id.is_some() { 1 } else { 0 },
-            (self.classes.len() + self.pseudo_classes.bits().count_ones() as usize) as u8,
+            (self.classes.len()
+                + self.pseudo_classes.bits().count_ones() as usize
+                + self.nth_child.len()) as u8,
             if self.element.is_some() { 1 } else { 0 },
-        ])
+        ]);
+
+        // A negation is as specific as the most specific selector in its argument
+        if let Some(not) = self.not.iter().map(|not| not.specificity()).max() {
+            specificity += not;
+        }
+
+        specificity
     }
 
     // pub fn id(mut self, id: &str) -> Self {
@@ -260,3 +368,34 @@ impl PartialEq for Selector {
         self.matches(other)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::NthChild;
+
+    #[test]
+    fn test_nth_child_odd_even() {
+        let odd = NthChild::new(2, 1);
+        let even = NthChild::new(2, 0);
+
+        assert!(odd.matches(1) && odd.matches(3) && !odd.matches(2));
+        assert!(even.matches(2) && even.matches(4) && !even.matches(1));
+    }
+
+    #[test]
+    fn test_nth_child_negative() {
+        // The first three children
+        let first_three = NthChild::new(-1, 3);
+
+        assert!(first_three.matches(1) && first_three.matches(3));
+        assert!(!first_three.matches(4));
+    }
+
+    #[test]
+    fn test_nth_child_index() {
+        let third = NthChild::new(0, 3);
+
+        assert!(third.matches(3));
+        assert!(!third.matches(1) && !third.matches(6));
+    }
+}
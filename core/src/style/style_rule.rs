@@ -7,6 +7,9 @@ pub(crate) struct StyleRule {
     pub(crate) id: Rule,
     pub(crate) selectors: Vec<Selector>,
     pub(crate) properties: Vec<Property>,
+    /// The media query lists of any `@media` rules enclosing the rule, which must all match for
+    /// the rule to apply.
+    pub(crate) media: Vec<MediaQueryList>,
 }
 
 // impl std::fmt::Display for StyleRule {
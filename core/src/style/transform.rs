@@ -106,47 +106,52 @@ impl IndexMut<usize> for Transform2D {
     }
 }
 
-pub fn apply_transform(cx: &mut Context, tree: &Tree) {
+pub fn apply_transform(cx: &mut Context) {
     //println!("Apply Transform");
-    for entity in tree.into_iter() {
+    for entity in cx.tree.into_iter() {
         //println!("Entity: {}", entity);
 
         if entity == Entity::root() {
             continue;
         }
 
-        let parent = tree.get_parent(entity).unwrap();
+        let parent = cx.tree.get_parent(entity).unwrap();
         //let parent_origin = state.data.get_origin(parent);
-        let parent_transform = cx.cache().get_transform(parent);
+        let parent_transform = cx.cache.get_transform(parent);
+        let previous_transform = cx.cache.get_transform(entity);
 
-        cx.cache().set_transform(entity, Transform2D::identity());
+        cx.cache.set_transform(entity, Transform2D::identity());
 
-        cx.cache().set_transform(entity, parent_transform);
+        cx.cache.set_transform(entity, parent_transform);
 
-        let bounds = cx.cache().get_bounds(entity);
+        let bounds = cx.cache.get_bounds(entity);
 
         //state.data.set_origin(entity, parent_origin);
 
-        if let Some((tx, ty)) = cx.style().translate.get(entity).copied() {
-            let scale = cx.style().dpi_factor as f32;
-            cx.cache().set_translate(entity, (tx * scale, ty * scale));
+        if let Some((tx, ty)) = cx.style.translate.get(entity).copied() {
+            let scale = cx.style.dpi_factor as f32;
+            cx.cache.set_translate(entity, (tx * scale, ty * scale));
         }
 
-        if let Some(rotate) = cx.style().rotate.get(entity).copied() {
+        if let Some(rotate) = cx.style.rotate.get(entity).copied() {
             let x = bounds.x + (bounds.w / 2.0);
             let y = bounds.y + (bounds.h / 2.0);
-            cx.cache().set_translate(entity, (x, y));
-            cx.cache().set_rotate(entity, (rotate).to_radians());
-            cx.cache().set_translate(entity, (-x, -y));
+            cx.cache.set_translate(entity, (x, y));
+            cx.cache.set_rotate(entity, (rotate).to_radians());
+            cx.cache.set_translate(entity, (-x, -y));
         }
         //println!("End");
 
-        if let Some((scalex, scaley)) = cx.style().scale.get(entity).copied() {
+        if let Some((scalex, scaley)) = cx.style.scale.get(entity).copied() {
             let x = bounds.x + (bounds.w / 2.0);
             let y = bounds.y + (bounds.h / 2.0);
-            cx.cache().set_translate(entity, (x, y));
-            cx.cache().set_scale(entity, (scalex, scaley));
-            cx.cache().set_translate(entity, (-x, -y));
+            cx.cache.set_translate(entity, (x, y));
+            cx.cache.set_scale(entity, (scalex, scaley));
+            cx.cache.set_translate(entity, (-x, -y));
+        }
+
+        if cx.cache.get_transform(entity) != previous_transform {
+            cx.style.redraw(entity);
         }
     }
 }
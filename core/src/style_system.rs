@@ -1,58 +1,67 @@
 use crate::cache::BoundingBox;
-use crate::layout::LayoutTreeIterator;
+use crate::layout::{LayoutChildIterator, LayoutTreeIterator};
 use femtovg::{Align, Baseline};
+use fnv::FnvHashSet;
 use morphorm::Units;
 
 use crate::prelude::*;
-use crate::style::{Rule, Selector, SelectorRelation};
+use crate::style::{substitute_variables, Rule, Selector, SelectorRelation};
 use crate::text::{measure_text_lines, text_layout, text_paint_general};
 use crate::tree::TreeExt;
+use std::rc::Rc;
 
-pub fn apply_z_ordering(cx: &mut Context, tree: &Tree) {
-    for entity in tree.into_iter() {
+pub fn apply_z_ordering(cx: &mut Context) {
+    for entity in cx.tree.into_iter() {
         if entity == Entity::root() {
             continue;
         }
 
-        if tree.is_ignored(entity) {
+        if cx.tree.is_ignored(entity) {
             continue;
         }
 
-        let parent = tree.get_layout_parent(entity).unwrap();
+        let parent = cx.tree.get_layout_parent(entity).unwrap();
 
-        if let Some(z_order) = cx.style().z_order.get(entity).copied() {
-            cx.cache().set_z_index(entity, z_order);
+        if let Some(z_order) = cx.style.z_order.get(entity).copied() {
+            cx.cache.set_z_index(entity, z_order);
         } else {
-            let parent_z_order = cx.cache().get_z_index(parent);
-            cx.cache().set_z_index(entity, parent_z_order);
+            let parent_z_order = cx.cache.get_z_index(parent);
+            cx.cache.set_z_index(entity, parent_z_order);
         }
     }
 }
 
-pub fn apply_clipping(cx: &mut Context, tree: &Tree) {
+pub fn apply_clipping(cx: &mut Context) {
     //println!("Apply Clipping");
-    for entity in tree.into_iter() {
+    for entity in cx.tree.into_iter() {
         if entity == Entity::root() {
             continue;
         }
 
-        if tree.is_ignored(entity) {
+        if cx.tree.is_ignored(entity) {
             continue;
         }
 
-        let parent = tree.get_layout_parent(entity).unwrap();
+        // Windows clip to their own bounds
+        if cx.windows.contains_key(&entity) {
+            let bounds = cx.cache.get_bounds(entity);
+            cx.cache.set_clip_region(entity, bounds);
+            continue;
+        }
+
+        let parent = cx.tree.get_layout_parent(entity).unwrap();
 
-        let parent_clip_region = cx.cache().get_clip_region(parent);
+        let parent_clip_region = cx.cache.get_clip_region(parent);
 
-        let overflow = cx.style().overflow.get(entity).cloned().unwrap_or_default();
+        let overflow = cx.style.overflow.get(entity).cloned().unwrap_or_default();
 
         let clip_region = if overflow == Overflow::Hidden {
-            let clip_widget = cx.style().clip_widget.get(entity).cloned().unwrap_or(entity);
+            let clip_widget = cx.style.clip_widget.get(entity).cloned().unwrap_or(entity);
 
-            let clip_x = cx.cache().get_posx(clip_widget);
-            let clip_y = cx.cache().get_posy(clip_widget);
-            let clip_w = cx.cache().get_width(clip_widget);
-            let clip_h = cx.cache().get_height(clip_widget);
+            let clip_x = cx.cache.get_posx(clip_widget);
+            let clip_y = cx.cache.get_posy(clip_widget);
+            let clip_w = cx.cache.get_width(clip_widget);
+            let clip_h = cx.cache.get_height(clip_widget);
 
             let mut intersection = BoundingBox::default();
             intersection.x = clip_x.max(parent_clip_region.x);
@@ -78,17 +87,21 @@ pub fn apply_clipping(cx: &mut Context, tree: &Tree) {
             parent_clip_region
         };
 
+        if cx.cache.get_clip_region(entity) != clip_region {
+            cx.style.redraw(entity);
+        }
+
         // Absolute positioned nodes ignore overflow hidden
         //if position_type == PositionType::SelfDirected {
         //    cx.cache().set_clip_region(entity, root_clip_region);
         //} else {
-        cx.cache().set_clip_region(entity, clip_region);
+        cx.cache.set_clip_region(entity, clip_region);
         //}
     }
 }
 
-pub fn apply_visibility(cx: &mut Context, tree: &Tree) {
-    let mut draw_tree: Vec<Entity> = tree.into_iter().collect();
+pub fn apply_visibility(cx: &mut Context) {
+    let mut draw_tree: Vec<Entity> = cx.tree.into_iter().collect();
     draw_tree.sort_by_cached_key(|entity| cx.cache().get_z_index(*entity));
 
     for entity in draw_tree.into_iter() {
@@ -96,11 +109,11 @@ pub fn apply_visibility(cx: &mut Context, tree: &Tree) {
             continue;
         }
 
-        if tree.is_ignored(entity) {
+        if cx.tree.is_ignored(entity) {
             continue;
         }
 
-        let parent = tree.get_layout_parent(entity).unwrap();
+        let parent = cx.tree.get_layout_parent(entity).unwrap();
 
         if cx.cache().get_visibility(parent) == Visibility::Invisible {
             cx.cache().set_visibility(entity, Visibility::Invisible);
@@ -132,9 +145,9 @@ pub fn apply_visibility(cx: &mut Context, tree: &Tree) {
 
 // Apply this before layout
 // THE GOAL OF THIS FUNCTION: set content-width and content-height
-pub fn apply_text_constraints(cx: &mut Context, tree: &Tree) {
+pub fn apply_text_constraints(cx: &mut Context) {
     //println!("Apply text constraints");
-    let mut draw_tree: Vec<Entity> = tree.into_iter().collect();
+    let mut draw_tree: Vec<Entity> = cx.tree.into_iter().collect();
     draw_tree.sort_by_cached_key(|entity| cx.cache().get_z_index(*entity));
 
     for entity in draw_tree.into_iter() {
@@ -146,7 +159,7 @@ pub fn apply_text_constraints(cx: &mut Context, tree: &Tree) {
             continue;
         }
 
-        if tree.is_ignored(entity) {
+        if cx.tree.is_ignored(entity) {
             continue;
         }
 
@@ -296,7 +309,7 @@ pub fn apply_text_constraints(cx: &mut Context, tree: &Tree) {
 
             if let Some(image_name) = cx.style_ref().image.get(entity) {
                 if let Some(img) = cx.resource_manager.images.get(image_name) {
-                    let (image_width, image_height) = img.image.dimensions();
+                    let (image_width, image_height) = img.dimensions();
                     let image_width = image_width as f32;
                     let image_height = image_height as f32;
 
@@ -315,28 +328,58 @@ pub fn apply_text_constraints(cx: &mut Context, tree: &Tree) {
     }
 }
 
-pub fn apply_inline_inheritance(cx: &mut Context, tree: &Tree) {
-    for entity in tree.into_iter() {
-        if let Some(parent) = tree.get_layout_parent(entity) {
-            cx.style().disabled.inherit_inline(entity, parent);
+// Inherits properties from the parent of an entity, redrawing the entity if any of them change
+macro_rules! inherit {
+    ($cx:ident, $entity:ident, $parent:ident, $method:ident, $($name:ident),*) => {
+        $(
+            let previous = $cx.style.$name.get($entity).cloned();
+            $cx.style.$name.$method($entity, $parent);
+            if $cx.style.$name.get($entity) != previous.as_ref() {
+                $cx.style.redraw($entity);
+            }
+        )*
+    };
+}
+
+pub fn apply_inline_inheritance(cx: &mut Context) {
+    for entity in cx.tree.into_iter() {
+        if let Some(parent) = cx.tree.get_layout_parent(entity) {
+            // Restyle views whose inherited disabled state changes, as it's matched by `:disabled`
+            let disabled = cx.style.disabled.get(entity).copied();
+            cx.style.disabled.inherit_inline(entity, parent);
+            if cx.style.disabled.get(entity).copied() != disabled {
+                cx.style.restyle(entity);
+            }
 
-            cx.style().font_color.inherit_inline(entity, parent);
-            cx.style().font_size.inherit_inline(entity, parent);
-            cx.style().font.inherit_inline(entity, parent);
-            cx.style().caret_color.inherit_inline(entity, parent);
-            cx.style().selection_color.inherit_inline(entity, parent);
+            inherit!(
+                cx,
+                entity,
+                parent,
+                inherit_inline,
+                font_color,
+                font_size,
+                font,
+                caret_color,
+                selection_color
+            );
         }
     }
 }
 
-pub fn apply_shared_inheritance(cx: &mut Context, tree: &Tree) {
-    for entity in tree.into_iter() {
-        if let Some(parent) = tree.get_layout_parent(entity) {
-            cx.style().font_color.inherit_shared(entity, parent);
-            cx.style().font_size.inherit_shared(entity, parent);
-            cx.style().font.inherit_shared(entity, parent);
-            cx.style().caret_color.inherit_shared(entity, parent);
-            cx.style().selection_color.inherit_shared(entity, parent);
+pub fn apply_shared_inheritance(cx: &mut Context) {
+    for entity in cx.tree.into_iter() {
+        if let Some(parent) = cx.tree.get_layout_parent(entity) {
+            inherit!(
+                cx,
+                entity,
+                parent,
+                inherit_shared,
+                font_color,
+                font_size,
+                font,
+                caret_color,
+                selection_color
+            );
         }
     }
 }
@@ -381,19 +424,63 @@ fn entity_selector(cx: &Context, entity: Entity) -> Selector {
         element: cx.style_ref().elements.get(entity).cloned(),
         classes: cx.style_ref().classes.get(entity).cloned().unwrap_or_default(),
         pseudo_classes: cx.style_ref().pseudo_classes.get(entity).cloned().unwrap_or_default(),
+        nth_child: Vec::new(),
+        not: Vec::new(),
         relation: SelectorRelation::None,
     }
 }
 
+// Returns true if the widget matches the computed pseudo-classes and negations of the selector
+fn check_structural_match(cx: &Context, tree: &Tree, entity: Entity, selector: &Selector) -> bool {
+    if selector.pseudo_classes.contains(PseudoClass::ROOT) && entity != Entity::root() {
+        return false;
+    }
+
+    if selector.pseudo_classes.intersects(PseudoClass::structural())
+        || !selector.nth_child.is_empty()
+    {
+        if selector.pseudo_classes.contains(PseudoClass::FIRST_CHILD)
+            && tree.get_prev_layout_sibling(entity).is_some()
+        {
+            return false;
+        }
+
+        if selector.pseudo_classes.contains(PseudoClass::LAST_CHILD)
+            && tree.get_next_layout_sibling(entity).is_some()
+        {
+            return false;
+        }
+
+        if !selector.nth_child.is_empty() {
+            let index = std::iter::successors(tree.get_prev_layout_sibling(entity), |sibling| {
+                tree.get_prev_layout_sibling(*sibling)
+            })
+            .count();
+
+            if !selector.nth_child.iter().all(|nth_child| nth_child.matches(index as i32 + 1)) {
+                return false;
+            }
+        }
+    }
+
+    !selector.not.iter().any(|not| check_match(cx, tree, entity, not))
+}
+
 // Returns true if the widget matches the selector
-fn check_match(cx: &Context, entity: Entity, selector: &Selector) -> bool {
+fn check_match(cx: &Context, tree: &Tree, entity: Entity, selector: &Selector) -> bool {
+    if !check_structural_match(cx, tree, entity, selector) {
+        return false;
+    }
+
+    // Computed pseudo-classes are never set on an entity and have already been checked
+    let selector_pseudo_classes = selector.pseudo_classes - PseudoClass::computed();
+
     // Universal selector always matches
     if selector.asterisk {
         if let Some(mut pseudo_classes) = cx.style_ref().pseudo_classes.get(entity).cloned() {
             if let Some(disabled) = cx.style_ref().disabled.get(entity) {
                 pseudo_classes.set(PseudoClass::DISABLED, *disabled);
             }
-            let selector_pseudo_classes = selector.pseudo_classes;
             if !pseudo_classes.is_empty() && !pseudo_classes.contains(selector_pseudo_classes) {
                 return false;
             } else {
@@ -436,7 +523,6 @@ fn check_match(cx: &Context, entity: Entity, selector: &Selector) -> bool {
         if let Some(disabled) = cx.style_ref().disabled.get(entity) {
             pseudo_classes.set(PseudoClass::DISABLED, *disabled);
         }
-        let selector_pseudo_classes = selector.pseudo_classes;
 
         if !selector_pseudo_classes.is_empty() && !pseudo_classes.contains(selector_pseudo_classes)
         {
@@ -450,6 +536,10 @@ fn check_match(cx: &Context, entity: Entity, selector: &Selector) -> bool {
 fn compute_matched_rules(cx: &Context, tree: &Tree, entity: Entity, matched_rules: &mut Vec<Rule>) {
     // Loop through all of the style rules
     'rule_loop: for rule in cx.style_ref().rules.iter() {
+        if cx.style_ref().disabled_rules.contains(&rule.id) {
+            continue;
+        }
+
         let mut relation_entity = entity;
         // Loop through selectors (Should be from right to left)
         // All the selectors need to match for the rule to apply
@@ -457,7 +547,7 @@ fn compute_matched_rules(cx: &Context, tree: &Tree, entity: Entity, matched_rule
             // Get the relation of the selector
             match rule_selector.relation {
                 SelectorRelation::None => {
-                    if !check_match(cx, entity, rule_selector) {
+                    if !check_match(cx, tree, entity, rule_selector) {
                         continue 'rule_loop;
                     }
                 }
@@ -467,7 +557,7 @@ fn compute_matched_rules(cx: &Context, tree: &Tree, entity: Entity, matched_rule
                     // Contrust the selector for the parent
                     // Check if the parent selector matches the rule_seletor
                     if let Some(parent) = tree.get_layout_parent(relation_entity) {
-                        if !check_match(cx, parent, rule_selector) {
+                        if !check_match(cx, tree, parent, rule_selector) {
                             continue 'rule_loop;
                         }
 
@@ -490,7 +580,7 @@ fn compute_matched_rules(cx: &Context, tree: &Tree, entity: Entity, matched_rule
                             continue;
                         }
 
-                        if check_match(cx, ancestor, rule_selector) {
+                        if check_match(cx, tree, ancestor, rule_selector) {
                             relation_entity = ancestor;
 
                             continue 'selector_loop;
@@ -499,6 +589,36 @@ fn compute_matched_rules(cx: &Context, tree: &Tree, entity: Entity, matched_rule
 
                     continue 'rule_loop;
                 }
+
+                SelectorRelation::AdjacentSibling => {
+                    if let Some(sibling) = tree.get_prev_layout_sibling(relation_entity) {
+                        if !check_match(cx, tree, sibling, rule_selector) {
+                            continue 'rule_loop;
+                        }
+
+                        relation_entity = sibling;
+                    } else {
+                        continue 'rule_loop;
+                    }
+                }
+
+                SelectorRelation::GeneralSibling => {
+                    // Walk back through the previous siblings, nearest first
+                    let siblings =
+                        std::iter::successors(tree.get_prev_layout_sibling(relation_entity), |s| {
+                            tree.get_prev_layout_sibling(*s)
+                        });
+
+                    for sibling in siblings {
+                        if check_match(cx, tree, sibling, rule_selector) {
+                            relation_entity = sibling;
+
+                            continue 'selector_loop;
+                        }
+                    }
+
+                    continue 'rule_loop;
+                }
             }
         }
 
@@ -539,6 +659,19 @@ fn link_style_data(cx: &mut Context, entity: Entity, matched_rules: &Vec<Rule>)
         should_redraw = true;
     }
 
+    // Transform
+    if cx.style().translate.link(entity, &matched_rules) {
+        should_redraw = true;
+    }
+
+    if cx.style().rotate.link(entity, &matched_rules) {
+        should_redraw = true;
+    }
+
+    if cx.style().scale.link(entity, &matched_rules) {
+        should_redraw = true;
+    }
+
     if cx.style().left.link(entity, &matched_rules) {
         //println!("6");
         should_relayout = true;
@@ -684,6 +817,10 @@ fn link_style_data(cx: &mut Context, entity: Entity, matched_rules: &Vec<Rule>)
         should_redraw = true;
     }
 
+    if cx.style().background_gradient.link(entity, &matched_rules) {
+        should_redraw = true;
+    }
+
     // Font
     if cx.style().font_color.link(entity, &matched_rules) {
         //println!("43");
@@ -795,52 +932,430 @@ fn link_style_data(cx: &mut Context, entity: Entity, matched_rules: &Vec<Rule>)
     }
 
     if should_redraw {
-        cx.style().needs_redraw = true;
+        cx.style().redraw(entity);
+    }
+}
+
+// A selector which is matched against an ancestor or a previous sibling of the entity being
+// styled, given by the index of its rule and its index within the rule
+struct RelationSelector {
+    rule: usize,
+    selector: usize,
+    // True if the selector is matched against an ancestor, false if against a previous sibling
+    ancestor: bool,
+}
+
+// Returns the selectors which make the styles of entities depend on other entities
+fn relation_selectors(cx: &Context) -> Vec<RelationSelector> {
+    let mut relation_selectors = Vec::new();
+    for (rule, style_rule) in cx.style.rules.iter().enumerate() {
+        for (selector, rule_selector) in style_rule.selectors.iter().enumerate() {
+            let ancestor = match rule_selector.relation {
+                SelectorRelation::None => continue,
+                SelectorRelation::Parent | SelectorRelation::Ancestor => true,
+                SelectorRelation::AdjacentSibling | SelectorRelation::GeneralSibling => false,
+            };
+
+            relation_selectors.push(RelationSelector { rule, selector, ancestor });
+        }
+    }
+
+    relation_selectors
+}
+
+// Records whether the entity matches any of the relation selectors, and returns whether the
+// styles of its descendants and of its later siblings may depend on a change to the entity, which
+// is the case if it matched a selector before or after the change
+fn update_dependents(
+    cx: &mut Context,
+    entity: Entity,
+    relation_selectors: &[RelationSelector],
+) -> (bool, bool) {
+    let mut descendants = false;
+    let mut siblings = false;
+    for relation_selector in relation_selectors {
+        if (relation_selector.ancestor && descendants) || (!relation_selector.ancestor && siblings)
+        {
+            continue;
+        }
+
+        let selector =
+            &cx.style.rules[relation_selector.rule].selectors[relation_selector.selector];
+        if check_match(cx, &cx.tree, entity, selector) {
+            if relation_selector.ancestor {
+                descendants = true;
+            } else {
+                siblings = true;
+            }
+        }
+    }
+
+    let affected_descendants = if descendants {
+        !cx.style.affects_descendants.insert(entity)
+    } else {
+        cx.style.affects_descendants.remove(&entity)
+    };
+
+    let affected_siblings = if siblings {
+        !cx.style.affects_siblings.insert(entity)
+    } else {
+        cx.style.affects_siblings.remove(&entity)
+    };
+
+    (descendants || affected_descendants, siblings || affected_siblings)
+}
+
+// Matches the style rules of a single entity and links its style data, returning whether the
+// styles of its descendants and of its later siblings need to be updated as a result
+fn restyle_entity(
+    cx: &mut Context,
+    entity: Entity,
+    relation_selectors: &[RelationSelector],
+    matched_rules: &mut Vec<Rule>,
+) -> (bool, bool) {
+    matched_rules.clear();
+    compute_matched_rules(cx, &cx.tree, entity, matched_rules);
+    let variables_changed = compute_variables(cx, entity, matched_rules);
+
+    // The root only takes custom properties from the stylesheets
+    if entity != Entity::root() {
+        resolve_variables(cx, entity, matched_rules);
+        link_style_data(cx, entity, matched_rules);
+    }
+
+    let (descendants, siblings) = update_dependents(cx, entity, relation_selectors);
+    (descendants || variables_changed, siblings)
+}
+
+/// Updates the styles of the entities which have changed since the last restyle, or of every
+/// entity when `needs_restyle` is set.
+pub fn apply_styles(cx: &mut Context) {
+    cx.style.collect_resolved_rules();
+
+    if cx.style.needs_restyle {
+        apply_all_styles(cx);
+    } else if !cx.style.restyle_entities.is_empty() || !cx.style.restyle_parents.is_empty() {
+        apply_changed_styles(cx);
     }
+
+    cx.style.needs_restyle = false;
+    cx.style.restyle_entities.clear();
+    cx.style.restyle_parents.clear();
 }
 
-pub fn apply_styles(cx: &mut Context, tree: &Tree) {
+fn apply_all_styles(cx: &mut Context) {
     //println!("RESTYLE");
 
+    let relation_selectors = relation_selectors(cx);
+
     let mut prev_entity = None;
 
     let mut prev_matched_rules = Vec::with_capacity(100);
 
     let mut matched_rules = Vec::with_capacity(100);
 
-    let iterator = LayoutTreeIterator::full(tree);
+    // Siblings can only share their matched rules if no rule depends on their position
+    let share_rules = !cx
+        .style_ref()
+        .rules
+        .iter()
+        .any(|rule| rule.selectors.iter().any(|selector| selector.is_structural()));
+
+    let entities = LayoutTreeIterator::full(&cx.tree).collect::<Vec<_>>();
 
     // Loop through all entities
-    'ent: for entity in iterator {
-        // Skip the root
+    for entity in entities {
+        // Create a list of style rules that match this entity
+        //let mut matched_rules: Vec<Rule> = Vec::new();
+        matched_rules.clear();
+
         if entity == Entity::root() {
+            restyle_entity(cx, entity, &relation_selectors, &mut matched_rules);
             continue;
         }
 
-        // Create a list of style rules that match this entity
-        //let mut matched_rules: Vec<Rule> = Vec::new();
-        matched_rules.clear();
+        let mut shared = false;
 
         // If the entity and the previous entity have the same parent and selectors then they share the same rules
-        if let Some(prev) = prev_entity {
-            if let Some(parent) = tree.get_layout_parent(entity) {
-                if let Some(prev_parent) = tree.get_layout_parent(prev) {
+        if let Some(prev) = prev_entity.filter(|_| share_rules) {
+            if let Some(parent) = cx.tree.get_layout_parent(entity) {
+                if let Some(prev_parent) = cx.tree.get_layout_parent(prev) {
                     if parent == prev_parent {
                         if entity_selector(cx, entity).same(&entity_selector(cx, prev)) {
-                            matched_rules = prev_matched_rules.clone();
-                            prev_entity = Some(entity);
-                            link_style_data(cx, entity, &matched_rules);
-                            continue 'ent;
+                            matched_rules.extend_from_slice(&prev_matched_rules);
+                            shared = true;
                         }
                     }
                 }
             }
         }
 
-        compute_matched_rules(cx, tree, entity, &mut matched_rules);
-        link_style_data(cx, entity, &matched_rules);
+        if !shared {
+            compute_matched_rules(cx, &cx.tree, entity, &mut matched_rules);
+        }
 
         prev_entity = Some(entity);
-        prev_matched_rules = matched_rules.clone();
+        prev_matched_rules.clone_from(&matched_rules);
+
+        compute_variables(cx, entity, &matched_rules);
+        resolve_variables(cx, entity, &mut matched_rules);
+        link_style_data(cx, entity, &matched_rules);
+        update_dependents(cx, entity, &relation_selectors);
+    }
+}
+
+// Restyles the entities which were marked for restyling, along with the descendants and later
+// siblings whose selectors could be affected by them
+fn apply_changed_styles(cx: &mut Context) {
+    let relation_selectors = relation_selectors(cx);
+
+    let mut entities = cx.style.restyle_entities.iter().copied().collect::<Vec<_>>();
+
+    // Adding, removing or moving a view can change which structural and sibling selectors match
+    // the other children of its parent
+    let depends_on_siblings = cx
+        .style
+        .rules
+        .iter()
+        .any(|rule| rule.selectors.iter().any(|selector| selector.is_structural()));
+
+    if depends_on_siblings {
+        for parent in cx.style.restyle_parents.iter() {
+            let parent = if cx.tree.is_ignored(*parent) {
+                match cx.tree.get_layout_parent(*parent) {
+                    Some(layout_parent) => layout_parent,
+                    None => continue,
+                }
+            } else {
+                *parent
+            };
+
+            entities.extend(LayoutChildIterator::new(&cx.tree, parent));
+        }
+    }
+
+    entities.retain(|entity| !cx.tree.is_ignored(*entity));
+
+    // Entities are restyled in tree order so that custom properties are inherited from restyled
+    // parents, and each entity is restyled once
+    cx.tree.sort_in_tree_order(&mut entities);
+    entities.dedup();
+
+    let mut restyled = FnvHashSet::default();
+    let mut matched_rules = Vec::with_capacity(100);
+
+    for entity in entities {
+        if !restyled.insert(entity) {
+            continue;
+        }
+
+        let (descendants, siblings) =
+            restyle_entity(cx, entity, &relation_selectors, &mut matched_rules);
+
+        let mut dependents = Vec::new();
+
+        if descendants {
+            dependents.extend(LayoutTreeIterator::subtree(&cx.tree, entity).skip(1));
+        }
+
+        if siblings {
+            if let Some(parent) = cx.tree.get_layout_parent(entity) {
+                for sibling in LayoutChildIterator::new(&cx.tree, parent)
+                    .skip_while(|sibling| *sibling != entity)
+                    .skip(1)
+                {
+                    dependents.extend(LayoutTreeIterator::subtree(&cx.tree, sibling));
+                }
+            }
+        }
+
+        for dependent in dependents {
+            if restyled.insert(dependent) {
+                restyle_entity(cx, dependent, &relation_selectors, &mut matched_rules);
+            }
+        }
+    }
+}
+
+// Computes the custom properties of an entity from those of its parent, the matched rules and any
+// inline custom properties, returning true if they changed
+fn compute_variables(cx: &mut Context, entity: Entity, matched_rules: &[Rule]) -> bool {
+    let parent_variables = cx
+        .tree
+        .get_layout_parent(entity)
+        .and_then(|parent| cx.style.variables.get(parent).cloned());
+
+    // Rules are sorted from most to least specific, so later declarations take precedence
+    let declarations = matched_rules
+        .iter()
+        .rev()
+        .filter_map(|rule| cx.style.rule_variables.get(rule))
+        .flatten()
+        .chain(cx.style.inline_variables.get(entity).into_iter().flatten())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let variables = if declarations.is_empty() {
+        parent_variables
+    } else {
+        let mut variables =
+            parent_variables.map(|variables| (*variables).clone()).unwrap_or_default();
+
+        for (name, value) in declarations {
+            if let Some(value) = substitute_variables(&value, &variables) {
+                variables.insert(name, value);
+            } else {
+                variables.remove(&name);
+            }
+        }
+
+        Some(Rc::new(variables))
+    };
+
+    let changed = cx.style.variables.get(entity) != variables.as_ref();
+
+    if let Some(variables) = variables {
+        cx.style.variables.insert(entity, variables).expect("Failed to set variables");
+    } else {
+        cx.style.variables.remove(entity);
+    }
+
+    changed
+}
+
+// Inserts the rules holding the declarations which reference custom properties, resolved for the
+// entity, in front of the rules they come from
+fn resolve_variables(cx: &mut Context, entity: Entity, matched_rules: &mut Vec<Rule>) {
+    if cx.style.unresolved_declarations.is_empty() {
+        return;
+    }
+
+    let variables = cx.style.variables.get(entity).cloned().unwrap_or_default();
+
+    let mut resolved_rules = Vec::new();
+    let mut index = 0;
+    while index < matched_rules.len() {
+        let rule = matched_rules[index];
+
+        if let Some(declarations) = cx.style.unresolved_declarations.get(&rule) {
+            // Declarations which can't be resolved are left out
+            let resolved = declarations
+                .iter()
+                .filter_map(|(name, value)| {
+                    substitute_variables(value, &variables)
+                        .map(|value| format!("{}: {};", name, value))
+                })
+                .collect::<String>();
+
+            let resolved_rule = cx.style.resolved_rule(rule, resolved);
+            matched_rules.insert(index, resolved_rule);
+            resolved_rules.push(resolved_rule);
+            index += 1;
+        }
+
+        index += 1;
+    }
+
+    cx.style.set_resolved_rules(entity, resolved_rules);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::id::GenerationalId;
+    use crate::prelude::*;
+    use crate::storage::animatable_set::AnimatableSet;
+
+    const THEME: &str = "
+        element:first-child { width: 1px; }
+        element:last-child { height: 1px; }
+        element:nth-child(2n) { left: 1px; }
+        .a + element { top: 1px; }
+        .a ~ .c { right: 1px; }
+        element:not(.c) { bottom: 1px; }
+    ";
+
+    #[test]
+    fn structural_selectors_match_layout_siblings() {
+        let mut cx = Context::new();
+        cx.add_theme(THEME);
+
+        let mut entities = Vec::new();
+        VStack::new(&mut cx, |cx| {
+            entities.push(Element::new(cx).class("a").entity);
+
+            // The children of an ignored view are matched as children of its layout parent
+            let wrapper = VStack::new(cx, |cx| {
+                entities.push(Element::new(cx).entity);
+                entities.push(Element::new(cx).class("c").entity);
+            })
+            .entity;
+            cx.tree.set_ignored(wrapper, true);
+
+            entities.push(Element::new(cx).class("c").entity);
+        });
+
+        cx.process_style_updates();
+
+        let matched = |property: &AnimatableSet<Units>| {
+            entities.iter().map(|entity| property.get(*entity).is_some()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(matched(&cx.style.width), [true, false, false, false]);
+        assert_eq!(matched(&cx.style.height), [false, false, false, true]);
+        assert_eq!(matched(&cx.style.left), [false, true, false, true]);
+        assert_eq!(matched(&cx.style.top), [false, true, false, false]);
+        assert_eq!(matched(&cx.style.right), [false, false, true, true]);
+        assert_eq!(matched(&cx.style.bottom), [true, true, false, false]);
+    }
+
+    #[test]
+    fn only_child_is_first_and_last_child() {
+        let mut cx = Context::new();
+        cx.add_theme("element:first-child:last-child:nth-child(1) { width: 1px; }");
+
+        let mut only_child = Entity::null();
+        VStack::new(&mut cx, |cx| only_child = Element::new(cx).entity);
+        let first = Element::new(&mut cx).entity;
+        Element::new(&mut cx);
+
+        cx.process_style_updates();
+
+        assert!(cx.style.width.get(only_child).is_some());
+        assert!(cx.style.width.get(first).is_none());
+    }
+
+    #[test]
+    fn cyclic_variables_use_fallbacks() {
+        let mut cx = Context::new();
+        cx.add_theme("element { --a: var(--b); --b: var(--a); width: var(--a, 3px); }");
+        let element = Element::new(&mut cx).entity;
+
+        cx.process_style_updates();
+
+        assert_eq!(cx.style.width.get(element), Some(&Units::Pixels(3.0)));
+    }
+
+    #[test]
+    fn unused_resolved_rules_are_freed() {
+        let mut cx = Context::new();
+        cx.add_theme("element { width: var(--width); }");
+
+        let element = Element::new(&mut cx).variable("--width", "0px").entity;
+        let other = Element::new(&mut cx).variable("--width", "1px").entity;
+        cx.process_style_updates();
+
+        for width in 2..1000 {
+            cx.style
+                .inline_variables
+                .insert(element, vec![("--width".to_string(), format!("{}px", width))])
+                .unwrap();
+            cx.style.restyle(element);
+            cx.process_style_updates();
+
+            assert_eq!(cx.style.width.get(element), Some(&Units::Pixels(width as f32)));
+            assert_eq!(cx.style.width.get(other), Some(&Units::Pixels(1.0)));
+        }
+
+        assert!(cx.style.resolved_rules.len() <= 2 + crate::style::MAX_UNUSED_RESOLVED_RULES);
     }
 }
@@ -0,0 +1,121 @@
+use fnv::FnvHashMap;
+
+use crate::accessibility::{AccessNode, AccessTreeUpdate, Role};
+use crate::context::Context;
+use crate::prelude::*;
+
+/// Rebuilds the accessibility tree and records any changes since the last run.
+///
+/// Only views with a role are exported, with the root entity acting as the window node. The
+/// parent of a node is its nearest ancestor which is also exported.
+pub fn accessibility_system(cx: &mut Context) {
+    let tree = &cx.tree;
+    let mut nodes: FnvHashMap<Entity, AccessNode> = FnvHashMap::default();
+    let mut order = Vec::new();
+
+    for entity in tree.into_iter() {
+        if tree.is_ignored(entity) {
+            continue;
+        }
+
+        let role = if entity == Entity::root() {
+            Role::Window
+        } else if let Some(role) = cx.style.role.get(entity).copied() {
+            if cx.cache_ref().get_display(entity) == Display::None
+                || cx.cache_ref().get_visibility(entity) == Visibility::Invisible
+            {
+                continue;
+            }
+
+            role
+        } else {
+            continue;
+        };
+
+        let node = build_node(cx, tree, entity, role);
+
+        if let Some(parent) = entity.parent_iter(tree).skip(1).find(|p| nodes.contains_key(p)) {
+            if let Some(parent_node) = nodes.get_mut(&parent) {
+                parent_node.children.push(entity);
+            }
+        }
+
+        nodes.insert(entity, node);
+        order.push(entity);
+    }
+
+    let focused = cx.focused();
+    let focus = focused.parent_iter(tree).find(|e| nodes.contains_key(e)).unwrap_or(Entity::root());
+
+    let state = &mut cx.accessibility;
+
+    let changed = order
+        .iter()
+        .filter(|entity| state.nodes.get(entity) != nodes.get(entity))
+        .filter_map(|entity| nodes.get(entity).cloned())
+        .collect::<Vec<_>>();
+
+    let removed = state
+        .nodes
+        .keys()
+        .filter(|entity| !nodes.contains_key(entity))
+        .copied()
+        .collect::<Vec<_>>();
+
+    if !changed.is_empty() || !removed.is_empty() || focus != state.focus {
+        state.push_update(AccessTreeUpdate { nodes: changed, removed, focus });
+    }
+
+    state.nodes = nodes;
+    state.focus = focus;
+}
+
+fn build_node(cx: &Context, tree: &Tree, entity: Entity, role: Role) -> AccessNode {
+    let pseudo_classes = cx.style.pseudo_classes.get(entity).copied().unwrap_or_default();
+
+    let name = cx.style.name.get(entity).cloned().or_else(|| {
+        if role == Role::Label {
+            cx.style.text.get(entity).cloned()
+        } else if role.name_from_contents() {
+            let text = entity
+                .branch_iter(tree)
+                .skip(1)
+                .filter_map(|descendant| cx.style.text.get(descendant))
+                .filter(|text| !text.is_empty())
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if text.is_empty() {
+                None
+            } else {
+                Some(text.join(" "))
+            }
+        } else {
+            None
+        }
+    });
+
+    AccessNode {
+        entity,
+        role,
+        name,
+        value: cx.style.accessible_value.get(entity).cloned(),
+        numeric_value: cx.style.numeric_value.get(entity).copied(),
+        checked: if role.is_checkable() {
+            Some(pseudo_classes.contains(PseudoClass::CHECKED))
+        } else {
+            None
+        },
+        expanded: cx.style.expanded.get(entity).copied(),
+        selected: pseudo_classes.contains(PseudoClass::SELECTED),
+        disabled: cx.style.disabled.get(entity).copied().unwrap_or_default(),
+        focusable: cx
+            .style
+            .abilities
+            .get(entity)
+            .map(|abilities| abilities.contains(Abilities::FOCUSABLE))
+            .unwrap_or_default(),
+        bounds: cx.cache_ref().get_bounds(entity),
+        children: Vec::new(),
+    }
+}
@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use femtovg::ImageId;
+use fnv::{FnvHashMap, FnvHashSet};
+
+use crate::cache::BoundingBox;
+use crate::prelude::*;
+use crate::style::Transform2D;
+
+/// The most regions kept for a window. Once a window has this many, a new region is merged into
+/// the one which it grows the least, so that many scattered changes don't repaint views one by one.
+const MAX_REGIONS: usize = 8;
+
+/// The most draws of a window which the repainted regions are remembered for.
+const MAX_BUFFER_AGE: usize = 4;
+
+/// The regions of the windows which need to be repainted, and the images which the windows are
+/// painted into.
+#[derive(Default)]
+pub(crate) struct DamageState {
+    /// The regions of each window which changed since the window was last drawn. Regions of the
+    /// same window never overlap.
+    pub regions: FnvHashMap<Entity, Vec<BoundingBox>>,
+    /// The image of each window, which keeps its contents between frames so that only the damaged
+    /// regions have to be repainted.
+    pub images: FnvHashMap<Entity, ImageId>,
+    /// The regions of each window repainted on its last few draws, most recent first.
+    pub history: FnvHashMap<Entity, VecDeque<Vec<BoundingBox>>>,
+    /// The age of the screen buffer of each window which the backend knows it for.
+    pub buffer_ages: FnvHashMap<Entity, usize>,
+    /// Whether repainted regions are tinted, to show what is repainted on each frame.
+    pub paint_flashing: bool,
+    /// The number of regions tinted so far, used to pick the color of the next tint.
+    pub flashes: usize,
+}
+
+impl DamageState {
+    /// Adds a region to the damage of a window.
+    ///
+    /// The region is merged with the regions it overlaps, and with the region it grows the least
+    /// if the window already has too many.
+    pub fn add(&mut self, window: Entity, region: BoundingBox) {
+        if region.w <= 0.0 || region.h <= 0.0 {
+            return;
+        }
+
+        let regions = self.regions.entry(window).or_default();
+        let mut region = region;
+
+        loop {
+            let overlapping = regions.iter().position(|other| other.intersects(&region));
+            let merged = overlapping.or_else(|| {
+                if regions.len() < MAX_REGIONS {
+                    return None;
+                }
+
+                regions
+                    .iter()
+                    .map(|other| area(&other.union(&region)) - area(other))
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                    .map(|(index, _)| index)
+            });
+
+            match merged {
+                // The merged region can overlap regions which the new one didn't
+                Some(index) => region = region.union(&regions.swap_remove(index)),
+                None => break,
+            }
+        }
+
+        regions.push(region);
+    }
+
+    /// Records the regions repainted on a draw of a window, and returns the regions which have to
+    /// be copied from its image to its screen buffer, or `None` if the whole image has to be.
+    pub fn present(
+        &mut self,
+        window: Entity,
+        repainted: &[BoundingBox],
+    ) -> Option<Vec<BoundingBox>> {
+        let history = self.history.entry(window).or_default();
+        history.push_front(repainted.to_vec());
+        history.truncate(MAX_BUFFER_AGE);
+
+        // A buffer which was last drawn `age` draws ago is missing what was repainted since
+        match self.buffer_ages.get(&window) {
+            Some(&age) if age > 0 && age <= history.len() => {
+                Some(history.iter().take(age).flatten().copied().collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes the damage and image of a window which has been closed.
+    pub fn remove(&mut self, window: Entity) {
+        self.regions.remove(&window);
+        self.images.remove(&window);
+        self.history.remove(&window);
+        self.buffer_ages.remove(&window);
+    }
+}
+
+fn area(region: &BoundingBox) -> f32 {
+    region.w * region.h
+}
+
+/// Compares the region each entity marked to be redrawn covers with the region it covered when
+/// damage was last computed, and damages both regions of entities which moved, appeared,
+/// disappeared or changed how they're drawn.
+///
+/// Entities whose geometry, clipping or transform changed are marked to be redrawn by the systems
+/// which compute them. The children of an entity whose region changed are checked as well, since
+/// they inherit its visibility, opacity and clip region. Every entity is checked when the whole
+/// tree needs to be redrawn.
+pub(crate) fn damage_system(cx: &mut Context) {
+    let redraw_all = std::mem::take(&mut cx.style.needs_redraw);
+    let redraw = std::mem::take(&mut cx.style.redraw_entities);
+
+    let mut pending: Vec<Entity> =
+        if redraw_all { cx.tree.into_iter().collect() } else { redraw.iter().copied().collect() };
+    let mut checked = FnvHashSet::default();
+
+    while let Some(entity) = pending.pop() {
+        if !checked.insert(entity) {
+            continue;
+        }
+
+        let painted = painted_region(cx, entity);
+        let previous = cx.cache.painted.get(entity).copied();
+
+        if painted == previous && !redraw.contains(&entity) {
+            continue;
+        }
+
+        let window = cx.window_of(entity);
+        for region in previous.into_iter().chain(painted) {
+            cx.damage.add(window, region);
+        }
+
+        match painted {
+            Some(region) => {
+                cx.cache.painted.insert(entity, region).expect("Failed to store painted region");
+            }
+
+            None => {
+                cx.cache.painted.remove(entity);
+            }
+        }
+
+        if painted != previous {
+            pending.extend(entity.child_iter(&cx.tree));
+        }
+    }
+
+    if redraw_all {
+        for window in cx.windows.keys().copied().chain(std::iter::once(Entity::root())) {
+            cx.damage.add(window, cx.cache.get_bounds(window));
+        }
+    }
+}
+
+/// Returns the region of its window which an entity is drawn into, or `None` if it isn't drawn.
+///
+/// This is the bounds of the entity, grown to include its outline and outer shadow and then
+/// transformed, clipped by its clip region. Windows cover their whole bounds, which are filled with
+/// their background color.
+pub(crate) fn painted_region(cx: &Context, entity: Entity) -> Option<BoundingBox> {
+    let bounds = cx.cache.get_bounds(entity);
+
+    if cx.is_window(entity) {
+        return Some(bounds);
+    }
+
+    if cx.tree.is_ignored(entity)
+        || cx.cache.get_visibility(entity) == Visibility::Invisible
+        || cx.cache.get_display(entity) == Display::None
+        || cx.cache.get_opacity(entity) <= 0.0
+    {
+        return None;
+    }
+
+    let mut region = bounds;
+
+    let outline_width = physical(cx, cx.style.outline_width.get(entity), bounds.w.min(bounds.h));
+    if outline_width > 0.0 {
+        let outline_offset =
+            physical(cx, cx.style.outline_offset.get(entity), bounds.w.min(bounds.h));
+        region = region.union(&bounds.expand(outline_width / 2.0 + outline_offset));
+    }
+
+    // The shadow is blurred into an image which is larger than the view, as it's drawn in `View`
+    if cx.style.outer_shadow_color.get(entity).is_some() {
+        let blur = physical(cx, cx.style.outer_shadow_blur.get(entity), bounds.w);
+        let h_offset = physical(cx, cx.style.outer_shadow_h_offset.get(entity), bounds.w);
+        let v_offset = physical(cx, cx.style.outer_shadow_v_offset.get(entity), bounds.w);
+        let d = (blur / 2.0 * 5.0).ceil();
+        let mut shadow = bounds.expand(d / 2.0);
+        shadow.x += h_offset;
+        shadow.y += v_offset;
+        region = region.union(&shadow);
+    }
+
+    // Antialiasing can touch the pixels just outside of a shape
+    region = region.expand(1.0);
+
+    let transform = cx.cache.get_transform(entity);
+    if transform != Transform2D::identity() {
+        let corners = [
+            transform.transform_point(region.left(), region.top()),
+            transform.transform_point(region.right(), region.top()),
+            transform.transform_point(region.left(), region.bottom()),
+            transform.transform_point(region.right(), region.bottom()),
+        ];
+
+        region = BoundingBox::from_min_max(
+            corners.iter().map(|corner| corner.0).fold(f32::INFINITY, f32::min),
+            corners.iter().map(|corner| corner.1).fold(f32::INFINITY, f32::min),
+            corners.iter().map(|corner| corner.0).fold(f32::NEG_INFINITY, f32::max),
+            corners.iter().map(|corner| corner.1).fold(f32::NEG_INFINITY, f32::max),
+        );
+    }
+
+    let region = region.intersection(&cx.cache.get_clip_region(entity));
+    if region.w == 0.0 || region.h == 0.0 {
+        return None;
+    }
+
+    Some(region)
+}
+
+// Converts a length from the style into physical pixels, with percentages of `parent`
+fn physical(cx: &Context, units: Option<&Units>, parent: f32) -> f32 {
+    match units {
+        Some(Units::Pixels(pixels)) => pixels * cx.style.dpi_factor as f32,
+        Some(units) => units.value_or(parent, 0.0),
+        None => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32) -> BoundingBox {
+        BoundingBox { x, y, w: 10.0, h: 10.0 }
+    }
+
+    #[test]
+    fn overlapping_regions_are_merged() {
+        let mut damage = DamageState::default();
+        damage.add(Entity::root(), rect(0.0, 0.0));
+        damage.add(Entity::root(), rect(100.0, 0.0));
+        assert_eq!(damage.regions[&Entity::root()].len(), 2);
+
+        // Merging with the first region makes it overlap the second
+        damage.add(Entity::root(), BoundingBox { x: 5.0, y: 5.0, w: 100.0, h: 10.0 });
+        assert_eq!(
+            damage.regions[&Entity::root()],
+            vec![BoundingBox { x: 0.0, y: 0.0, w: 110.0, h: 15.0 }]
+        );
+    }
+
+    #[test]
+    fn too_many_regions_are_merged_with_the_nearest() {
+        let mut damage = DamageState::default();
+        for index in 0..MAX_REGIONS {
+            damage.add(Entity::root(), rect(index as f32 * 100.0, 0.0));
+        }
+
+        damage.add(Entity::root(), rect(320.0, 0.0));
+
+        let regions = &damage.regions[&Entity::root()];
+        assert_eq!(regions.len(), MAX_REGIONS);
+        assert!(regions.contains(&BoundingBox { x: 300.0, y: 0.0, w: 30.0, h: 10.0 }));
+    }
+
+    #[test]
+    fn regions_repainted_since_buffer_was_drawn_are_presented() {
+        let mut damage = DamageState::default();
+        assert_eq!(damage.present(Entity::root(), &[rect(0.0, 0.0)]), None);
+
+        damage.buffer_ages.insert(Entity::root(), 2);
+        assert_eq!(
+            damage.present(Entity::root(), &[rect(20.0, 0.0)]),
+            Some(vec![rect(20.0, 0.0), rect(0.0, 0.0)])
+        );
+
+        // Too few draws are remembered for an older buffer
+        damage.buffer_ages.insert(Entity::root(), MAX_BUFFER_AGE + 1);
+        assert_eq!(damage.present(Entity::root(), &[]), None);
+    }
+}
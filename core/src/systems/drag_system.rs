@@ -0,0 +1,204 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use fnv::FnvHashMap;
+
+use crate::id::GenerationalId;
+use crate::prelude::*;
+use crate::views::DragPreview;
+
+/// The distance in logical pixels the cursor has to move while pressed on a draggable view before
+/// a drag starts.
+pub(crate) const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Tracks the current drag and the drop targets which could receive it.
+pub(crate) struct DragState {
+    /// The view being dragged, or null if there is no drag.
+    pub source: Entity,
+    /// The data carried by the drag.
+    pub payload: Option<Box<dyn Any>>,
+    /// The position of the cursor relative to the source when the drag started, in physical pixels.
+    pub grab_offset: (f32, f32),
+    /// Builds the contents of the drag preview, if the source has a custom preview.
+    pub preview_content: Option<Rc<dyn Fn(&mut Context)>>,
+    /// The view which follows the cursor during the drag.
+    pub preview: Entity,
+    /// The drop target under the cursor which accepts the payload.
+    pub target: Entity,
+    /// Whether the drag has ended and is waiting for the drop to be handled before being cleared.
+    pub ended: bool,
+    /// Decides whether each drop target accepts a payload.
+    pub targets: FnvHashMap<Entity, Box<dyn Fn(&dyn Any) -> bool>>,
+}
+
+impl Default for DragState {
+    fn default() -> Self {
+        Self {
+            source: Entity::null(),
+            payload: None,
+            grab_offset: (0.0, 0.0),
+            preview_content: None,
+            preview: Entity::null(),
+            target: Entity::null(),
+            ended: false,
+            targets: FnvHashMap::default(),
+        }
+    }
+}
+
+impl DragState {
+    pub fn is_dragging(&self) -> bool {
+        self.source != Entity::null() && !self.ended
+    }
+}
+
+/// Finds the drop target under the cursor, sending enter and leave events when it changes and
+/// over events while the cursor moves over it.
+pub fn update_drop_target(cx: &mut Context) {
+    if !cx.drag.is_dragging() {
+        return;
+    }
+
+    let target = drop_target(cx, cx.hovered);
+    let previous = cx.drag.target;
+
+    if target != previous {
+        if previous != Entity::null() {
+            set_drop_target(cx, previous, false);
+            send_drag_event(cx, previous, DragEvent::Leave);
+        }
+
+        if target != Entity::null() {
+            set_drop_target(cx, target, true);
+            send_drag_event(cx, target, DragEvent::Enter);
+        }
+
+        cx.drag.target = target;
+    }
+
+    if target != Entity::null() {
+        let (x, y) = (cx.mouse().cursorx, cx.mouse().cursory);
+        send_drag_event(cx, target, DragEvent::Over(x, y));
+    }
+}
+
+/// Ends the current drag, dropping the payload onto the drop target under the cursor if `drop` is
+/// true and cancelling the drag otherwise.
+pub fn end_drag(cx: &mut Context, drop: bool) {
+    if !cx.drag.is_dragging() {
+        return;
+    }
+
+    let target = cx.drag.target;
+    let dropped = drop && target != Entity::null();
+
+    if target != Entity::null() {
+        set_drop_target(cx, target, false);
+        send_drag_event(cx, target, if dropped { DragEvent::Drop } else { DragEvent::Leave });
+    }
+
+    let source = cx.drag.source;
+    send_drag_event(cx, source, DragEvent::End(dropped));
+
+    // The payload is kept until the drop has been handled
+    cx.drag.ended = true;
+}
+
+/// Shows the preview of the current drag and moves it with the cursor, and clears the drag once it
+/// has ended.
+pub fn drag_system(cx: &mut Context) {
+    if cx.drag.source == Entity::null() {
+        return;
+    }
+
+    if cx.drag.ended {
+        let preview = std::mem::replace(&mut cx.drag.preview, Entity::null());
+        if preview != Entity::null() {
+            cx.remove(preview);
+        }
+
+        cx.drag.source = Entity::null();
+        cx.drag.payload = None;
+        cx.drag.preview_content = None;
+        cx.drag.target = Entity::null();
+        cx.drag.ended = false;
+
+        return;
+    }
+
+    let scale = cx.style.dpi_factor as f32;
+    let x = (cx.mouse().cursorx - cx.drag.grab_offset.0) / scale;
+    let y = (cx.mouse().cursory - cx.drag.grab_offset.1) / scale;
+
+    let preview = cx.drag.preview;
+    if preview == Entity::null() {
+        show_preview(cx, x, y);
+    } else if cx.style.left.get(preview) != Some(&Pixels(x))
+        || cx.style.top.get(preview) != Some(&Pixels(y))
+    {
+        cx.style.left.insert(preview, Pixels(x));
+        cx.style.top.insert(preview, Pixels(y));
+        cx.need_relayout();
+        cx.style.redraw(preview);
+    }
+}
+
+// Returns the closest view containing the entity which accepts the payload of the drag, or null if
+// there isn't one
+fn drop_target(cx: &Context, entity: Entity) -> Entity {
+    let payload = match &cx.drag.payload {
+        Some(payload) => &**payload,
+        None => return Entity::null(),
+    };
+
+    entity
+        .parent_iter(&cx.tree)
+        .find(|ancestor| cx.drag.targets.get(ancestor).map_or(false, |accepts| (accepts)(payload)))
+        .unwrap_or(Entity::null())
+}
+
+fn set_drop_target(cx: &mut Context, entity: Entity, flag: bool) {
+    if let Some(pseudo_classes) = cx.style.pseudo_classes.get_mut(entity) {
+        pseudo_classes.set(PseudoClass::DROP_TARGET, flag);
+    }
+
+    cx.style.restyle(entity);
+    cx.style.redraw(entity);
+}
+
+fn send_drag_event(cx: &mut Context, target: Entity, drag_event: DragEvent) {
+    let source = cx.drag.source;
+    cx.event_queue.push_back(
+        Event::new(drag_event).target(target).origin(source).propagate(Propagation::Direct),
+    );
+}
+
+fn show_preview(cx: &mut Context, x: f32, y: f32) {
+    let source = cx.drag.source;
+    let window = cx.window_of(source);
+    let scale = cx.style.dpi_factor as f32;
+    let width = cx.cache().get_width(source) / scale;
+    let height = cx.cache().get_height(source) / scale;
+    let content = cx.drag.preview_content.clone();
+
+    let mut preview = Entity::null();
+    cx.with_current(window, |cx| {
+        let handle = if let Some(content) = content {
+            DragPreview::new(cx, move |cx| (content)(cx))
+        } else {
+            // Without custom content the preview is an outline of the source
+            DragPreview::new(cx, |_| {}).width(Pixels(width)).height(Pixels(height))
+        };
+
+        preview = handle.left(Pixels(x)).top(Pixels(y)).entity;
+    });
+
+    // The preview is under the cursor but shouldn't stop the views below it from being hovered
+    for entity in preview.branch_iter(&cx.tree).collect::<Vec<_>>() {
+        if let Some(abilities) = cx.style.abilities.get_mut(entity) {
+            abilities.set(Abilities::HOVERABLE, false);
+        }
+    }
+
+    cx.drag.preview = preview;
+}
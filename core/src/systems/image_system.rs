@@ -1,8 +1,6 @@
-use std::collections::HashSet;
-
 use crate::context::Context;
+use crate::prelude::*;
 use crate::resource::{ImageRetentionPolicy, StoredImage};
-use crate::{prelude::*, resource::ImageOrId};
 
 pub fn image_system(cx: &mut Context) {
     cx.resource_manager.mark_images_unused();
@@ -40,48 +38,64 @@ fn load_image(cx: &mut Context, entity: Entity, image_name: &String) {
 fn try_load_image(cx: &mut Context, entity: Entity, image_name: &str) -> bool {
     // Check if the image is already loaded
     if let Some(image_store) = cx.resource_manager.images.get_mut(image_name) {
-        match &image_store.image {
-            // Image exists and is already loaded so just add this entity as an observer and mark image as used
-            ImageOrId::Id(_, _) => {
-                // TODO: check if the image is actually the same?
-                image_store.observers.insert(entity);
-                image_store.used = true;
-            }
-
-            // Image exists but isn't loaded yet
-            ImageOrId::Image(_, _) => {
-                if let Some(canvas) = cx.canvases.get_mut(&Entity::root()) {
-                    // This loads the image and sets the image id
-                    image_store.image.id(canvas);
-                    image_store.used = true;
-                    cx.need_relayout();
-                    cx.need_redraw();
-                }
-            }
-        }
+        // The image is uploaded to the canvas of the window which shows this entity when the
+        // window is drawn, as that's when its graphics context is current
+        image_store.observers.insert(entity);
+        image_store.used = true;
 
         return true;
     } else {
         // Image doesn't exist yet so load and show placeholder image
         // TODO: Add way to configure the placeholder image
-        cx.resource_manager.images.insert(
-            image_name.to_owned(),
-            StoredImage {
-                image: ImageOrId::Image(
-                    image::load_from_memory_with_format(
-                        include_bytes!("../../resources/images/broken_image.png"),
-                        image::ImageFormat::Png,
-                    )
-                    .unwrap(),
-                    femtovg::ImageFlags::NEAREST,
-                ),
-                retention_policy: ImageRetentionPolicy::Forever,
-                used: true,
-                dirty: false,
-                observers: HashSet::new(),
-            },
+        let mut placeholder = StoredImage::new(
+            image::load_from_memory_with_format(
+                include_bytes!("../../resources/images/broken_image.png"),
+                image::ImageFormat::Png,
+            )
+            .unwrap(),
+            femtovg::ImageFlags::NEAREST,
+            ImageRetentionPolicy::Forever,
         );
+        placeholder.observers.insert(entity);
+
+        cx.resource_manager.images.insert(image_name.to_owned(), placeholder);
     }
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn images_are_uploaded_to_the_windows_which_show_them() {
+        let mut cx = Context::new();
+        for name in ["shared", "tool"] {
+            cx.load_image(
+                name.to_string(),
+                image::DynamicImage::new_rgba8(2, 2),
+                ImageRetentionPolicy::Forever,
+            );
+        }
+
+        Image::new(&mut cx, "shared");
+        let window = Window::new(&mut cx, |cx| {
+            Image::new(cx, "shared");
+            let element = Element::new(cx).entity;
+            cx.style.background_image.insert(element, "tool".to_string());
+        })
+        .entity;
+
+        image_system(&mut cx);
+
+        let uploads = |cx: &Context, window| {
+            let mut names = cx.resource_manager.images_to_upload(window, |e| cx.window_of(e));
+            names.sort();
+            names
+        };
+
+        assert_eq!(uploads(&cx, Entity::root()), vec!["shared"]);
+        assert_eq!(uploads(&cx, window), vec!["shared", "tool"]);
+    }
+}
@@ -1 +1,5 @@
+pub(crate) mod accessibility_system;
+pub(crate) mod damage_system;
+pub(crate) mod drag_system;
 pub(crate) mod image_system;
+pub(crate) mod tooltip_system;
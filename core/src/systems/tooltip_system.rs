@@ -0,0 +1,163 @@
+use std::rc::Rc;
+
+use fnv::FnvHashMap;
+use instant::{Duration, Instant};
+
+use crate::id::GenerationalId;
+use crate::prelude::*;
+use crate::views::Tooltip;
+
+// The distance between the cursor and a tooltip below it, in logical pixels
+const CURSOR_OFFSET: f32 = 20.0;
+
+/// Tracks the tooltip of the hovered view.
+pub(crate) struct TooltipState {
+    /// How long the cursor has to rest on a view before its tooltip is shown.
+    pub delay: Duration,
+    /// When the hovered view last changed, set by the hover system.
+    pub hover_time: Instant,
+    /// The view whose tooltip is shown or waiting to be shown.
+    pub owner: Entity,
+    /// The tooltip view, if one is shown.
+    pub tooltip: Entity,
+    /// Whether the tooltip of the owner was dismissed by a mouse press.
+    pub dismissed: bool,
+    /// Builders for the tooltips of views with rich tooltip content.
+    pub content: FnvHashMap<Entity, Rc<dyn Fn(&mut Context)>>,
+}
+
+impl Default for TooltipState {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            hover_time: Instant::now(),
+            owner: Entity::null(),
+            tooltip: Entity::null(),
+            dismissed: false,
+            content: FnvHashMap::default(),
+        }
+    }
+}
+
+impl TooltipState {
+    /// Returns the time at which the tooltip of the owner should be shown, if it's waiting.
+    pub fn deadline(&self) -> Option<Instant> {
+        if self.owner != Entity::null() && self.tooltip == Entity::null() && !self.dismissed {
+            Some(self.hover_time + self.delay)
+        } else {
+            None
+        }
+    }
+}
+
+/// Shows the tooltip of the hovered view once the cursor has rested on it for the tooltip delay,
+/// and hides it when the cursor leaves the view or a mouse button is pressed.
+pub fn tooltip_system(cx: &mut Context) {
+    let owner = tooltip_owner(cx, cx.hovered);
+
+    if owner != cx.tooltip.owner {
+        hide_tooltip(cx);
+        cx.tooltip.owner = owner;
+        cx.tooltip.dismissed = false;
+    }
+
+    if cx.tooltip.dismissed {
+        hide_tooltip(cx);
+        return;
+    }
+
+    if let Some(deadline) = cx.tooltip.deadline() {
+        if Instant::now() >= deadline {
+            show_tooltip(cx, owner);
+        }
+    }
+}
+
+/// Moves a newly laid out tooltip so that it stays inside of its window.
+pub fn position_tooltip(cx: &mut Context) {
+    let tooltip = cx.tooltip.tooltip;
+    if tooltip == Entity::null() {
+        return;
+    }
+
+    let window = cx.window_of(tooltip);
+    let window_width = cx.cache().get_width(window);
+    let window_height = cx.cache().get_height(window);
+
+    let posx = cx.cache().get_posx(tooltip);
+    let posy = cx.cache().get_posy(tooltip);
+    let width = cx.cache().get_width(tooltip);
+    let height = cx.cache().get_height(tooltip);
+
+    let dx = (window_width - (posx + width)).min(0.0).max(-posx);
+    let dy = (window_height - (posy + height)).min(0.0).max(-posy);
+
+    if dx == 0.0 && dy == 0.0 {
+        return;
+    }
+
+    for entity in tooltip.branch_iter(&cx.tree).collect::<Vec<_>>() {
+        let x = cx.cache().get_posx(entity);
+        let y = cx.cache().get_posy(entity);
+        cx.cache().set_posx(entity, x + dx);
+        cx.cache().set_posy(entity, y + dy);
+    }
+
+    // Moving the tooltip doesn't mark its geometry as changed, so it's repainted here
+    cx.style.redraw(tooltip);
+
+    // Keep the tooltip in place for later layouts
+    let scale = cx.style.dpi_factor as f32;
+    cx.style.left.insert(tooltip, Pixels((posx + dx) / scale));
+    cx.style.top.insert(tooltip, Pixels((posy + dy) / scale));
+}
+
+// Returns the view with a tooltip which contains the entity, or null if there isn't one
+fn tooltip_owner(cx: &Context, entity: Entity) -> Entity {
+    entity
+        .parent_iter(&cx.tree)
+        .find(|ancestor| {
+            cx.style.tooltip.get(*ancestor).is_some() || cx.tooltip.content.contains_key(ancestor)
+        })
+        .unwrap_or(Entity::null())
+}
+
+fn show_tooltip(cx: &mut Context, owner: Entity) {
+    let window = cx.window_of(owner);
+    let scale = cx.style.dpi_factor as f32;
+    let x = cx.mouse().cursorx / scale;
+    let y = cx.mouse().cursory / scale + CURSOR_OFFSET;
+
+    let text = cx.style.tooltip.get(owner).cloned();
+    let content = cx.tooltip.content.get(&owner).cloned();
+
+    let mut tooltip = Entity::null();
+    cx.with_current(window, |cx| {
+        tooltip = Tooltip::new(cx, move |cx| {
+            if let Some(content) = content {
+                (content)(cx);
+            } else if let Some(text) = text {
+                Label::new(cx, &text);
+            }
+        })
+        .left(Pixels(x))
+        .top(Pixels(y))
+        .entity;
+    });
+
+    // The cursor can pass over the tooltip without changing the hovered view
+    for entity in tooltip.branch_iter(&cx.tree).collect::<Vec<_>>() {
+        if let Some(abilities) = cx.style.abilities.get_mut(entity) {
+            abilities.set(Abilities::HOVERABLE, false);
+        }
+    }
+
+    cx.tooltip.tooltip = tooltip;
+}
+
+fn hide_tooltip(cx: &mut Context) {
+    let tooltip = std::mem::replace(&mut cx.tooltip.tooltip, Entity::null());
+    if tooltip != Entity::null() {
+        cx.remove(tooltip);
+    }
+}
@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+use crate::text::{EditableText, Selection};
+
+/// A single change to some text, recorded so that it can be undone and redone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    /// The byte offset of the change.
+    pub offset: usize,
+    /// The text which was replaced.
+    pub deleted: String,
+    /// The text which replaced it.
+    pub inserted: String,
+    /// The selection before the change.
+    pub selection_before: Selection,
+    /// The selection after the change.
+    pub selection_after: Selection,
+}
+
+impl TextEdit {
+    // Whether this edit is a single typed character which can be merged into the previous edit
+    fn is_character(&self) -> bool {
+        self.deleted.is_empty()
+            && self.inserted.chars().count() == 1
+            && !self.inserted.chars().any(|c| c.is_whitespace())
+    }
+}
+
+/// A list of undoable and redoable text edits.
+#[derive(Debug, Clone, Default)]
+pub struct EditHistory {
+    undo_stack: Vec<TextEdit>,
+    redo_stack: Vec<TextEdit>,
+    merge: bool,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces `range` of `text` with `inserted`, setting `selection` and recording the change.
+    ///
+    /// Consecutive single character inserts are merged into one edit until [`break_merge`](Self::break_merge)
+    /// is called or a different kind of edit is made.
+    pub fn edit<T: EditableText>(
+        &mut self,
+        text: &mut T,
+        range: Range<usize>,
+        inserted: &str,
+        selection: &mut Selection,
+        selection_after: Selection,
+    ) {
+        let edit = TextEdit {
+            offset: range.start,
+            deleted: text.as_str()[range.clone()].to_owned(),
+            inserted: inserted.to_owned(),
+            selection_before: *selection,
+            selection_after,
+        };
+
+        text.edit(range, inserted);
+        *selection = selection_after;
+
+        if edit.deleted == edit.inserted {
+            return;
+        }
+
+        self.redo_stack.clear();
+
+        if let Some(previous) = self.undo_stack.last_mut() {
+            if self.merge
+                && edit.is_character()
+                && previous.offset + previous.inserted.len() == edit.offset
+            {
+                previous.inserted.push_str(&edit.inserted);
+                previous.selection_after = edit.selection_after;
+                return;
+            }
+        }
+
+        self.merge = edit.is_character();
+        self.undo_stack.push(edit);
+    }
+
+    /// Stops the next typed character from being merged into the previous edit.
+    pub fn break_merge(&mut self) {
+        self.merge = false;
+    }
+
+    /// Reverts the last edit, returning false if there was nothing to undo.
+    pub fn undo<T: EditableText>(&mut self, text: &mut T, selection: &mut Selection) -> bool {
+        if let Some(edit) = self.undo_stack.pop() {
+            text.edit(edit.offset..edit.offset + edit.inserted.len(), edit.deleted.as_str());
+            *selection = edit.selection_before;
+            self.redo_stack.push(edit);
+            self.merge = false;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reapplies the last undone edit, returning false if there was nothing to redo.
+    pub fn redo<T: EditableText>(&mut self, text: &mut T, selection: &mut Selection) -> bool {
+        if let Some(edit) = self.redo_stack.pop() {
+            text.edit(edit.offset..edit.offset + edit.deleted.len(), edit.inserted.as_str());
+            *selection = edit.selection_after;
+            self.undo_stack.push(edit);
+            self.merge = false;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Removes all recorded edits.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.merge = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(history: &mut EditHistory, text: &mut String, selection: &mut Selection, s: &str) {
+        for c in s.chars() {
+            let offset = selection.min() + c.len_utf8();
+            history.edit(
+                text,
+                selection.range(),
+                &c.to_string(),
+                selection,
+                Selection::caret(offset),
+            );
+        }
+    }
+
+    #[test]
+    fn merges_typed_characters() {
+        let mut history = EditHistory::new();
+        let mut text = String::new();
+        let mut selection = Selection::caret(0);
+
+        type_text(&mut history, &mut text, &mut selection, "hello world");
+        assert_eq!(text, "hello world");
+
+        assert!(history.undo(&mut text, &mut selection));
+        assert_eq!(text, "hello ");
+        assert!(history.undo(&mut text, &mut selection));
+        assert_eq!(text, "hello");
+        assert!(history.undo(&mut text, &mut selection));
+        assert_eq!(text, "");
+        assert_eq!(selection, Selection::caret(0));
+        assert!(!history.undo(&mut text, &mut selection));
+
+        assert!(history.redo(&mut text, &mut selection));
+        assert_eq!(text, "hello");
+        assert_eq!(selection, Selection::caret(5));
+    }
+
+    #[test]
+    fn break_merge() {
+        let mut history = EditHistory::new();
+        let mut text = String::new();
+        let mut selection = Selection::caret(0);
+
+        type_text(&mut history, &mut text, &mut selection, "ab");
+        history.break_merge();
+        type_text(&mut history, &mut text, &mut selection, "cd");
+
+        history.undo(&mut text, &mut selection);
+        assert_eq!(text, "ab");
+    }
+
+    #[test]
+    fn replace_selection() {
+        let mut history = EditHistory::new();
+        let mut text = String::from("hello world");
+        let mut selection = Selection::new(0, 5);
+
+        history.edit(&mut text, selection.range(), "goodbye", &mut selection, Selection::caret(7));
+        assert_eq!(text, "goodbye world");
+
+        history.undo(&mut text, &mut selection);
+        assert_eq!(text, "hello world");
+        assert_eq!(selection, Selection::new(0, 5));
+
+        history.redo(&mut text, &mut selection);
+        assert_eq!(text, "goodbye world");
+        assert_eq!(selection, Selection::caret(7));
+
+        // A new edit clears the redo stack
+        history.undo(&mut text, &mut selection);
+        type_text(&mut history, &mut text, &mut selection, "x");
+        assert!(!history.can_redo());
+    }
+}
@@ -1,6 +1,9 @@
 mod edit;
 pub use edit::*;
 
+mod history;
+pub use history::*;
+
 mod movement;
 pub use movement::*;
 
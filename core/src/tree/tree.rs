@@ -30,6 +30,9 @@ pub struct Tree {
     pub prev_sibling: Vec<Option<Entity>>,
     pub ignored: Vec<bool>,
     pub changed: bool,
+    // Each entity and its position when iterating the tree, by entity index, which is emptied when
+    // the tree changes and recomputed when it's next needed
+    order: Vec<(Entity, usize)>,
 }
 
 impl Tree {
@@ -42,6 +45,7 @@ impl Tree {
             prev_sibling: vec![None],
             ignored: vec![false],
             changed: true,
+            order: Vec::new(),
         }
     }
 
@@ -57,6 +61,25 @@ impl Tree {
         None
     }
 
+    /// Sorts entities into the order in which they're visited when iterating the tree. Entities
+    /// which aren't in the tree are put last.
+    pub fn sort_in_tree_order(&mut self, entities: &mut [Entity]) {
+        if self.order.is_empty() {
+            let mut order = vec![(Entity::null(), usize::MAX); self.parent.len()];
+            for (position, entity) in self.into_iter().enumerate() {
+                order[entity.index()] = (entity, position);
+            }
+
+            self.order = order;
+        }
+
+        let order = &self.order;
+        entities.sort_by_key(|entity| match order.get(entity.index()) {
+            Some((ordered, position)) if ordered == entity => *position,
+            _ => usize::MAX,
+        });
+    }
+
     /// Returns the last child of an entity.
     pub fn get_last_child(&self, entity: Entity) -> Option<Entity> {
         //check if entity exists
@@ -128,6 +151,64 @@ impl Tree {
         None
     }
 
+    /// Returns the previous sibling of an entity in the layout tree, where the children of
+    /// ignored entities take the place of their parent.
+    pub fn get_prev_layout_sibling(&self, entity: Entity) -> Option<Entity> {
+        let mut current = entity;
+        loop {
+            if let Some(mut sibling) = self.get_prev_sibling(current) {
+                // Enter ignored siblings from their last child
+                while self.is_ignored(sibling) {
+                    match self.get_last_child(sibling) {
+                        Some(last_child) => sibling = last_child,
+                        None => break,
+                    }
+                }
+
+                if !self.is_ignored(sibling) {
+                    return Some(sibling);
+                }
+
+                current = sibling;
+            } else {
+                // Leave ignored parents to continue with their previous sibling
+                match self.get_parent(current) {
+                    Some(parent) if self.is_ignored(parent) => current = parent,
+                    _ => return None,
+                }
+            }
+        }
+    }
+
+    /// Returns the next sibling of an entity in the layout tree, where the children of ignored
+    /// entities take the place of their parent.
+    pub fn get_next_layout_sibling(&self, entity: Entity) -> Option<Entity> {
+        let mut current = entity;
+        loop {
+            if let Some(mut sibling) = self.get_next_sibling(current) {
+                // Enter ignored siblings from their first child
+                while self.is_ignored(sibling) {
+                    match self.get_first_child(sibling) {
+                        Some(first_child) => sibling = first_child,
+                        None => break,
+                    }
+                }
+
+                if !self.is_ignored(sibling) {
+                    return Some(sibling);
+                }
+
+                current = sibling;
+            } else {
+                // Leave ignored parents to continue with their next sibling
+                match self.get_parent(current) {
+                    Some(parent) if self.is_ignored(parent) => current = parent,
+                    _ => return None,
+                }
+            }
+        }
+    }
+
     /// Returns the parent of an entity.
     pub fn get_parent(&self, entity: Entity) -> Option<Entity> {
         self.parent.get(entity.index()).map_or(None, |&parent| parent)
@@ -243,6 +324,7 @@ impl Tree {
 
         // Set the changed flag
         self.changed = true;
+        self.order.clear();
 
         Ok(())
     }
@@ -291,6 +373,7 @@ impl Tree {
         self.first_child[parent.index()] = Some(entity);
 
         self.changed = true;
+        self.order.clear();
 
         Ok(())
     }
@@ -346,6 +429,7 @@ impl Tree {
         self.next_sibling[entity.index()] = Some(sibling); // A
 
         self.changed = true;
+        self.order.clear();
 
         Ok(())
     }
@@ -405,6 +489,7 @@ impl Tree {
         self.prev_sibling[entity.index()] = Some(sibling); // B
 
         self.changed = true;
+        self.order.clear();
 
         Ok(())
     }
@@ -444,6 +529,7 @@ impl Tree {
         self.parent[entity.index()] = Some(parent);
 
         self.changed = true;
+        self.order.clear();
     }
 
     pub fn set_ignored(&mut self, entity: Entity, flag: bool) {
@@ -497,6 +583,7 @@ impl Tree {
         }
 
         self.changed = true;
+        self.order.clear();
 
         Ok(())
     }
@@ -83,4 +83,29 @@ mod test {
         assert!(backward.eq(correct.iter().cloned().rev()));
         Ok(())
     }
+
+    #[test]
+    fn sorting_follows_iteration_order() -> Result<(), TreeError> {
+        let mut t = Tree::new();
+        let r = Entity::root();
+        let [a, b, c, d, e] = [1, 2, 3, 4, 5].map(|i| Entity::new(i, 0));
+        t.add(a, r)?;
+        t.add(b, r)?;
+        t.add(c, a)?;
+        t.add(d, a)?;
+        t.add(e, b)?;
+
+        let mut entities = vec![e, d, b, r, c, a];
+        t.sort_in_tree_order(&mut entities);
+        assert_eq!(entities, [r, a, c, d, b, e]);
+
+        // The order is updated when the tree changes, and removed entities go last
+        t.remove(c)?;
+        t.add(c, b)?;
+        t.remove(e)?;
+        let mut entities = vec![e, d, b, r, c, a];
+        t.sort_in_tree_order(&mut entities);
+        assert_eq!(entities, [r, a, d, b, c, e]);
+        Ok(())
+    }
 }
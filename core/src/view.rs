@@ -2,12 +2,13 @@ use std::{any::Any, collections::HashMap};
 
 use crate::prelude::*;
 
+use crate::context::ConicImage;
 use crate::events::ViewHandler;
-use crate::resource::ImageOrId;
 use crate::state::ModelDataStore;
+use crate::style::color_at;
 use crate::text::{idx_to_pos, measure_text_lines, text_layout, text_paint_draw};
 use femtovg::{
-    renderer::OpenGl, Align, Baseline, ImageFlags, Paint, Path, PixelFormat, RenderTarget,
+    renderer::OpenGl, Align, Baseline, ImageFlags, ImageId, Paint, Path, PixelFormat, RenderTarget,
     TextMetrics,
 };
 use morphorm::Units;
@@ -35,7 +36,10 @@ pub trait View: 'static + Sized {
         cx.tree().add(id, current).expect("Failed to add to tree");
         cx.cache().add(id).expect("Failed to add to cache");
         cx.style().add(id);
+        cx.style().restyle_children(current);
         cx.views.insert(id, Box::new(self));
+        // Modifiers applied to the view after it's built are picked up by the next data update
+        cx.dirty_data.insert(id);
 
         cx.data
             .insert(
@@ -79,14 +83,6 @@ pub trait View: 'static + Sized {
         let border_color = cx.border_color().cloned().unwrap_or_default();
         let outline_color = cx.outline_color().cloned().unwrap_or_default();
 
-        let parent = cx
-            .tree
-            .get_layout_parent(cx.current)
-            .expect(&format!("Failed to find parent somehow: {}", cx.current));
-
-        let parent_width = cx.cache.get_width(parent);
-        let parent_height = cx.cache.get_height(parent);
-
         let border_shape_top_left = cx.border_shape_top_left().cloned().unwrap_or_default();
 
         let border_shape_top_right = cx.border_shape_top_right().cloned().unwrap_or_default();
@@ -376,7 +372,7 @@ pub trait View: 'static + Sized {
                 source
             };
 
-            canvas.set_render_target(RenderTarget::Screen);
+            canvas.set_render_target(cx.render_target());
 
             canvas.save();
             canvas.translate(outer_shadow_h_offset, outer_shadow_v_offset);
@@ -402,50 +398,11 @@ pub trait View: 'static + Sized {
         // Fill with background color
         let mut paint = Paint::color(background_color);
 
-        // Gradient overrides background color
-        if let Some(background_gradient) = cx.background_gradient() {
-            let (_, _, end_x, end_y, parent_length) = match background_gradient.direction {
-                GradientDirection::LeftToRight => (0.0, 0.0, bounds.w, 0.0, parent_width),
-                GradientDirection::TopToBottom => (0.0, 0.0, 0.0, bounds.h, parent_height),
-                _ => (0.0, 0.0, bounds.w, 0.0, parent_width),
-            };
-
-            paint = Paint::linear_gradient_stops(
-                bounds.x,
-                bounds.y,
-                bounds.x + end_x,
-                bounds.y + end_y,
-                background_gradient
-                    .get_stops(parent_length)
-                    .iter()
-                    .map(|stop| {
-                        let col: femtovg::Color = stop.1.into();
-                        (stop.0, col)
-                    })
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            );
-        }
-
-        // background-image overrides gradient
+        // background-image overrides background color
         // TODO should we draw image on top of colors?
         if let Some(background_image) = cx.background_image() {
-            if let Some(img) = cx.resource_manager.images.get(background_image) {
-                match img.image {
-                    ImageOrId::Id(id, dim) => {
-                        paint = Paint::image(
-                            id,
-                            bounds.x,
-                            bounds.y,
-                            dim.0 as f32,
-                            dim.1 as f32,
-                            0.0,
-                            1.0,
-                        );
-                    }
-
-                    _ => {}
-                }
+            if let Some((id, dim)) = cx.image_id(background_image) {
+                paint = Paint::image(id, bounds.x, bounds.y, dim.0 as f32, dim.1 as f32, 0.0, 1.0);
             }
         }
 
@@ -454,6 +411,25 @@ pub trait View: 'static + Sized {
         // Fill the quad
         canvas.fill_path(&mut path, paint);
 
+        // Draw gradient layers on top of the background, starting with the bottom layer
+        if let Some(background_gradient) = cx.background_gradient().cloned() {
+            let mut conic_layer = 0;
+            for gradient in background_gradient.iter().rev() {
+                let paint = match gradient {
+                    Gradient::Linear(gradient) => linear_gradient_paint(cx, gradient, opacity),
+                    Gradient::Radial(gradient) => radial_gradient_paint(cx, gradient, opacity),
+                    Gradient::Conic(gradient) => {
+                        conic_layer += 1;
+                        conic_gradient_paint(cx, canvas, conic_layer - 1, gradient, opacity)
+                    }
+                };
+
+                if let Some(paint) = paint {
+                    canvas.fill_path(&mut path, paint);
+                }
+            }
+        }
+
         //println!("{:.2?} seconds for whatever you did.", start.elapsed());
 
         // Draw border
@@ -579,29 +555,23 @@ pub trait View: 'static + Sized {
 
             // Draw image
             if let Some(image_name) = cx.image() {
-                if let Some(img) = cx.resource_manager.images.get(image_name) {
-                    match img.image {
-                        ImageOrId::Id(id, _) => {
-                            let x = match align {
-                                Align::Left => x,
-                                Align::Center => x - w * 0.5,
-                                Align::Right => x - w,
-                            };
-                            let y = match baseline {
-                                Baseline::Top => y,
-                                Baseline::Middle => y - h * 0.5,
-                                Baseline::Alphabetic | Baseline::Bottom => y - h,
-                            };
-
-                            let mut path = Path::new();
-                            path.rect(x, y, w, h);
-
-                            let paint = Paint::image(id, x, y, w, h, 0.0, 1.0);
-                            canvas.fill_path(&mut path, paint);
-                        }
+                if let Some((id, _)) = cx.image_id(image_name) {
+                    let x = match align {
+                        Align::Left => x,
+                        Align::Center => x - w * 0.5,
+                        Align::Right => x - w,
+                    };
+                    let y = match baseline {
+                        Baseline::Top => y,
+                        Baseline::Middle => y - h * 0.5,
+                        Baseline::Alphabetic | Baseline::Bottom => y - h,
+                    };
 
-                        _ => {}
-                    }
+                    let mut path = Path::new();
+                    path.rect(x, y, w, h);
+
+                    let paint = Paint::image(id, x, y, w, h, 0.0, 1.0);
+                    canvas.fill_path(&mut path, paint);
                 }
             }
 
@@ -712,6 +682,177 @@ pub trait View: 'static + Sized {
     }
 }
 
+// Converts the resolved stops of a gradient to femtovg colors
+fn paint_stops(stops: &[(f32, Color)], opacity: f32) -> Vec<(f32, femtovg::Color)> {
+    stops
+        .iter()
+        .map(|(offset, color)| {
+            let mut color: femtovg::Color = (*color).into();
+            color.set_alphaf(color.a * opacity);
+            (*offset, color)
+        })
+        .collect()
+}
+
+fn linear_gradient_paint(
+    cx: &DrawContext,
+    gradient: &LinearGradient,
+    opacity: f32,
+) -> Option<Paint> {
+    let bounds = cx.bounds();
+    let (x1, y1, x2, y2) = gradient.line(bounds.w, bounds.h);
+
+    // Pixel positions of stops are in logical coordinates
+    let length = cx.physical_to_logical((x2 - x1).hypot(y2 - y1));
+    let stops = gradient.get_stops(length);
+    if stops.is_empty() {
+        return None;
+    }
+
+    Some(Paint::linear_gradient_stops(
+        bounds.x + x1,
+        bounds.y + y1,
+        bounds.x + x2,
+        bounds.y + y2,
+        &paint_stops(&stops, opacity),
+    ))
+}
+
+fn radial_gradient_paint(
+    cx: &DrawContext,
+    gradient: &RadialGradient,
+    opacity: f32,
+) -> Option<Paint> {
+    let bounds = cx.bounds();
+    let (x, y, radius) = gradient.circle(bounds.w, bounds.h, cx.logical_to_physical(1.0));
+
+    let stops = gradient.get_stops(cx.physical_to_logical(radius));
+    if stops.is_empty() {
+        return None;
+    }
+
+    Some(Paint::radial_gradient_stops(
+        bounds.x + x,
+        bounds.y + y,
+        0.0,
+        radius,
+        &paint_stops(&stops, opacity),
+    ))
+}
+
+// femtovg has no conic gradients, so the gradient is drawn as a fan of thin wedges into an image
+// which is cached per view and layer, and used to fill the shape of the view. The wedges are only
+// drawn again when the gradient or the size of the view changes.
+fn conic_gradient_paint(
+    cx: &mut DrawContext,
+    canvas: &mut Canvas,
+    layer: usize,
+    gradient: &ConicGradient,
+    opacity: f32,
+) -> Option<Paint> {
+    let bounds = cx.bounds();
+    let stops = gradient.get_stops();
+    if stops.is_empty() {
+        return None;
+    }
+
+    let (width, height) = (bounds.w.ceil() as usize, bounds.h.ceil() as usize);
+    let scale = cx.logical_to_physical(1.0);
+
+    let mut images = cx.draw_cache.conic_images.remove(cx.current).unwrap_or_default();
+    let image = match images.get_mut(layer) {
+        Some(cached) if canvas.image_size(cached.image).ok() == Some((width, height)) => {
+            if cached.gradient != *gradient
+                || cached.size != (bounds.w, bounds.h)
+                || cached.scale != scale
+            {
+                draw_conic_gradient(cx, canvas, cached.image, gradient, &stops);
+                cached.gradient = gradient.clone();
+                cached.size = (bounds.w, bounds.h);
+                cached.scale = scale;
+            }
+
+            cached.image
+        }
+
+        existing => {
+            if let Some(cached) = existing {
+                canvas.delete_image(cached.image);
+            }
+
+            let image = canvas
+                .create_image_empty(
+                    width,
+                    height,
+                    PixelFormat::Rgba8,
+                    ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED,
+                )
+                .expect("Failed to create image");
+
+            draw_conic_gradient(cx, canvas, image, gradient, &stops);
+
+            let cached =
+                ConicImage { image, gradient: gradient.clone(), size: (bounds.w, bounds.h), scale };
+            if layer < images.len() {
+                images[layer] = cached;
+            } else {
+                images.push(cached);
+            }
+
+            image
+        }
+    };
+
+    cx.draw_cache.conic_images.insert(cx.current, images).unwrap();
+
+    Some(Paint::image(image, bounds.x, bounds.y, width as f32, height as f32, 0.0, opacity))
+}
+
+// Draws the wedges of a conic gradient into an image the size of the current view
+fn draw_conic_gradient(
+    cx: &DrawContext,
+    canvas: &mut Canvas,
+    image: ImageId,
+    gradient: &ConicGradient,
+    stops: &[(f32, Color)],
+) {
+    const SEGMENTS: usize = 360;
+
+    let bounds = cx.bounds();
+    let (width, height) = (bounds.w.ceil() as u32, bounds.h.ceil() as u32);
+
+    let (center_x, center_y) = gradient.center(bounds.w, bounds.h, cx.logical_to_physical(1.0));
+    let radius = (center_x.abs() + bounds.w).hypot(center_y.abs() + bounds.h);
+
+    canvas.save();
+    canvas.reset_transform();
+    canvas.reset_scissor();
+    canvas.set_render_target(RenderTarget::Image(image));
+    canvas.clear_rect(0, 0, width, height, femtovg::Color::rgba(0, 0, 0, 0));
+
+    for segment in 0..SEGMENTS {
+        let start = segment as f32 / SEGMENTS as f32;
+        let end = (segment as f32 + 1.5) / SEGMENTS as f32;
+        let color = color_at(stops, (segment as f32 + 0.5) / SEGMENTS as f32);
+
+        let start_angle = (gradient.angle / 360.0 + start) * std::f32::consts::TAU;
+        let end_angle = (gradient.angle / 360.0 + end) * std::f32::consts::TAU;
+
+        let mut wedge = Path::new();
+        wedge.move_to(center_x, center_y);
+        wedge.line_to(center_x + radius * start_angle.sin(), center_y - radius * start_angle.cos());
+        wedge.line_to(center_x + radius * end_angle.sin(), center_y - radius * end_angle.cos());
+        wedge.close();
+
+        let mut paint = Paint::color(color.into());
+        paint.set_anti_alias(false);
+        canvas.fill_path(&mut wedge, paint);
+    }
+
+    canvas.restore();
+    canvas.set_render_target(cx.render_target());
+}
+
 impl<T: View> ViewHandler for T
 where
     T: std::marker::Sized + View + 'static,
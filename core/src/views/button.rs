@@ -78,9 +78,11 @@ impl Button {
         F: FnOnce(&mut Context) -> Handle<V>,
         V: 'static + View,
     {
-        Self { action: Some(Box::new(action)) }.build(cx, move |cx| {
-            (content)(cx).hoverable(false).focusable(false);
-        })
+        Self { action: Some(Box::new(action)) }
+            .build(cx, move |cx| {
+                (content)(cx).hoverable(false).focusable(false);
+            })
+            .role(Role::Button)
     }
 }
 
@@ -109,5 +111,15 @@ impl View for Button {
 
             _ => {}
         });
+
+        event.map(|access_action, _| match access_action {
+            AccessAction::Press => {
+                if let Some(callback) = &self.action {
+                    (callback)(cx);
+                }
+            }
+
+            _ => {}
+        });
     }
 }
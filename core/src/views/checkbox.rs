@@ -143,6 +143,7 @@ impl Checkbox {
                 }
             })
             .cursor(CursorIcon::Hand)
+            .role(Role::CheckBox)
     }
 }
 
@@ -199,5 +200,17 @@ impl View for Checkbox {
 
             _ => {}
         });
+
+        event.map(|access_action, _| match access_action {
+            AccessAction::Press => {
+                if !cx.is_disabled() {
+                    if let Some(callback) = &self.on_toggle {
+                        (callback)(cx);
+                    }
+                }
+            }
+
+            _ => {}
+        });
     }
 }
@@ -0,0 +1,490 @@
+use keyboard_types::Code;
+
+use crate::fonts::icons_names::DOWN;
+use crate::id::GenerationalId;
+use crate::prelude::*;
+use crate::tree::TreeExt;
+
+// The height of an option in the popup list, and the most options shown without scrolling
+const OPTION_HEIGHT: f32 = 26.0;
+const VISIBLE_OPTIONS: usize = 8;
+
+// Events sent to a combobox by its own views
+enum ComboboxUpdate {
+    // The bound value changed
+    SetValue(String),
+    // The text in the textbox was edited
+    Filter(String),
+    // The textbox finished editing, either because enter was pressed or because it lost focus
+    Submit(bool),
+    // An option in the popup was pressed, given by its position in the list of matches
+    Choose(usize),
+    // The arrow next to the textbox was pressed
+    Toggle,
+    // The view which holds the rows of the popup list was built
+    InitList(Entity),
+    // The rows of the popup list were built
+    InitRows(Vec<Entity>),
+}
+
+/// A view which pairs a textbox with a popup list of options, which are filtered by the text
+/// typed into the textbox.
+///
+/// The textbox shows the value targeted by the lens while it's not being edited. Typing into it
+/// opens the list of options which contain the typed text, with the matched text highlighted. The
+/// up and down arrow keys move the highlight, and enter chooses the highlighted option, which
+/// calls the `on_select` callback with the index of the option. Pressing escape, or clicking away
+/// from the combobox, closes the list without changing the value.
+///
+/// When an `on_free_text` callback is set, pressing enter while no option is highlighted calls it
+/// with the typed text, which allows values that aren't in the list of options.
+///
+/// # Example
+/// ```ignore
+/// Combobox::new(cx, AppData::countries, AppData::country)
+///     .on_select(|cx, index| cx.emit(AppEvent::SelectCountry(index)))
+///     .width(Pixels(200.0));
+/// ```
+#[derive(Lens)]
+pub struct Combobox<L: Lens<Target = Vec<T>>, T: 'static + Clone> {
+    options: L,
+    // The text shown in the textbox
+    text: String,
+    // The bound value as text, which the textbox goes back to when editing is cancelled
+    value: String,
+    editing: bool,
+    is_open: bool,
+    // The options which contain the typed text, as the index of the option and its text split
+    // into the parts before, inside and after the match
+    matches: Vec<(usize, String, String, String)>,
+    // The position of the highlighted option in the list of matches
+    highlighted: Option<usize>,
+    textbox: Entity,
+    arrow: Entity,
+    list: Entity,
+    // The rows of the popup list, in the same order as the matches
+    rows: Vec<Entity>,
+    select_callback: Option<Box<dyn Fn(&mut EventContext, usize)>>,
+    free_text_callback: Option<Box<dyn Fn(&mut EventContext, String)>>,
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Clone + ToString> Combobox<L, T> {
+    /// Creates a new combobox with a binding to the list of options and a binding to the value
+    /// shown in the textbox.
+    pub fn new<V>(cx: &mut Context, options: L, value: V) -> Handle<Self>
+    where
+        V: Lens,
+        <V as Lens>::Target: Data + ToString,
+    {
+        let text = value.get_fallible(cx).map(|value| value.to_string()).unwrap_or_default();
+
+        let mut textbox = Entity::null();
+        let mut arrow = Entity::null();
+        let handle = Self {
+            options,
+            text: text.clone(),
+            value: text,
+            editing: false,
+            is_open: false,
+            matches: Vec::new(),
+            highlighted: None,
+            textbox: Entity::null(),
+            arrow: Entity::null(),
+            list: Entity::null(),
+            rows: Vec::new(),
+            select_callback: None,
+            free_text_callback: None,
+        }
+        .build(cx, |cx| {
+            Binding::new(cx, value, |cx, value| {
+                let value = value.get_fallible(cx).map(|value| value.to_string());
+                cx.emit(ComboboxUpdate::SetValue(value.unwrap_or_default()));
+            });
+
+            textbox = Textbox::new(cx, Self::text)
+                .class("combobox_text")
+                .on_edit(|cx, text| cx.emit(ComboboxUpdate::Filter(text)))
+                .on_submit(|cx, _, enter| cx.emit(ComboboxUpdate::Submit(enter)))
+                .entity();
+
+            arrow = Label::new(cx, DOWN)
+                .class("combobox_arrow")
+                .font("icons")
+                .on_press(|cx| cx.emit(ComboboxUpdate::Toggle))
+                .entity();
+
+            Popup::new(cx, Self::is_open, |cx| {
+                ScrollView::new(cx, 0.0, 0.0, false, true, |cx| {
+                    cx.emit(ComboboxUpdate::InitList(cx.current()));
+
+                    Binding::new(cx, Self::matches, |cx, matches| {
+                        let mut rows = Vec::new();
+                        for (position, (_, before, matched, after)) in
+                            matches.get(cx).into_iter().enumerate()
+                        {
+                            let row = HStack::new(cx, |cx| {
+                                Label::new(cx, &before);
+                                Label::new(cx, &matched).class("match");
+                                Label::new(cx, &after);
+                            })
+                            .class("combobox_option")
+                            .height(Pixels(OPTION_HEIGHT))
+                            .checked(
+                                Self::highlighted
+                                    .map(move |highlighted| *highlighted == Some(position)),
+                            )
+                            .on_press(move |cx| cx.emit(ComboboxUpdate::Choose(position)))
+                            .entity();
+                            rows.push(row);
+                        }
+
+                        cx.emit(ComboboxUpdate::InitRows(rows));
+                    });
+                })
+                .height(Self::matches.map(|matches| {
+                    Pixels(matches.len().min(VISIBLE_OPTIONS) as f32 * OPTION_HEIGHT)
+                }));
+            })
+            .top(Percentage(100.0))
+            .height(Auto);
+        });
+
+        handle
+            .modify(|combobox| {
+                combobox.textbox = textbox;
+                combobox.arrow = arrow;
+            })
+            .layout_type(LayoutType::Row)
+            .role(Role::ComboBox)
+            .expanded(Self::is_open)
+    }
+
+    // Shows the options which contain the given text
+    fn filter(&mut self, cx: &mut EventContext, filter: &str)
+    where
+        <L as Lens>::Source: 'static,
+    {
+        self.matches = match cx.data::<L::Source>() {
+            Some(source) => self.options.view(source, |options| {
+                options
+                    .into_iter()
+                    .flatten()
+                    .enumerate()
+                    .filter_map(|(index, option)| {
+                        let text = option.to_string();
+                        let range = find_match(&text, filter)?;
+                        Some((
+                            index,
+                            text[..range.start].to_owned(),
+                            text[range.clone()].to_owned(),
+                            text[range.end..].to_owned(),
+                        ))
+                    })
+                    .collect()
+            }),
+            None => Vec::new(),
+        };
+
+        // Without a filter the current value is highlighted, if it's one of the options. When
+        // free text is allowed, the first match isn't highlighted so that enter keeps the text.
+        self.highlighted = if filter.is_empty() {
+            self.matches.iter().position(|(_, _, _, text)| *text == self.value)
+        } else if self.matches.is_empty() || self.free_text_callback.is_some() {
+            None
+        } else {
+            Some(0)
+        };
+
+        self.is_open = true;
+        self.scroll_to_highlighted(cx);
+    }
+
+    // Finishes editing, choosing the highlighted option or the typed option if enter was pressed
+    fn submit(&mut self, cx: &mut EventContext, enter: bool)
+    where
+        <L as Lens>::Source: 'static,
+    {
+        // Pressing the arrow closes the list itself
+        if !enter && cx.hovered() == self.arrow {
+            return;
+        }
+
+        if enter {
+            if let (true, Some(highlighted)) = (self.is_open, self.highlighted) {
+                self.choose(cx, highlighted);
+                return;
+            }
+
+            // Typing an option in full chooses it even if it isn't highlighted
+            let text = self.text.clone();
+            self.filter(cx, "");
+            if let Some(position) = self
+                .matches
+                .iter()
+                .position(|(_, _, _, option)| option.to_lowercase() == text.to_lowercase())
+            {
+                self.choose(cx, position);
+                return;
+            }
+
+            if let (Some(callback), false) = (&self.free_text_callback, text.is_empty()) {
+                (callback)(cx, text);
+                self.close();
+                return;
+            }
+        }
+
+        self.text = self.value.clone();
+        self.close();
+    }
+
+    fn choose(&mut self, cx: &mut EventContext, position: usize) {
+        if let Some((index, before, matched, after)) = self.matches.get(position) {
+            self.text = format!("{}{}{}", before, matched, after);
+            if let Some(callback) = &self.select_callback {
+                (callback)(cx, *index);
+            }
+        }
+
+        self.close();
+    }
+
+    fn close(&mut self) {
+        self.editing = false;
+        self.is_open = false;
+    }
+
+    // Scrolls the popup list so that the highlighted option is visible
+    fn scroll_to_highlighted(&self, cx: &mut EventContext) {
+        let highlighted = match self.highlighted {
+            Some(highlighted) => highlighted,
+            None => return,
+        };
+
+        let row = match self.rows.get(highlighted) {
+            Some(row) => *row,
+            None => return,
+        };
+
+        let scroll_view = match self.list.parent(cx.tree) {
+            Some(scroll_view) => scroll_view,
+            None => return,
+        };
+
+        let view_height = cx.cache.get_height(scroll_view);
+        let scrollable = cx.cache.get_height(self.list) - view_height;
+        if scrollable <= 0.0 {
+            return;
+        }
+
+        let offset = cx.cache.get_posy(scroll_view) - cx.cache.get_posy(self.list);
+        let row_top = cx.cache.get_posy(row) - cx.cache.get_posy(self.list);
+        let row_bottom = row_top + cx.cache.get_height(row);
+
+        let offset = if row_top < offset {
+            row_top
+        } else if row_bottom > offset + view_height {
+            row_bottom - view_height
+        } else {
+            return;
+        };
+
+        cx.emit_to(scroll_view, ScrollEvent::SetY((offset / scrollable).clamp(0.0, 1.0)));
+    }
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Clone + ToString> View for Combobox<L, T>
+where
+    <L as Lens>::Source: 'static,
+{
+    fn element(&self) -> Option<&'static str> {
+        Some("combobox")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|combobox_update, meta| {
+            match combobox_update {
+                ComboboxUpdate::SetValue(value) => {
+                    self.value = value.clone();
+                    if !self.editing {
+                        self.text = value.clone();
+                    }
+                }
+
+                ComboboxUpdate::Filter(text) => {
+                    // The textbox puts its text back when it finishes editing, which isn't an edit
+                    if self.editing && *text != self.text {
+                        self.text = text.clone();
+                        self.filter(cx, text);
+                    }
+                }
+
+                ComboboxUpdate::Submit(enter) => {
+                    self.submit(cx, *enter);
+                }
+
+                ComboboxUpdate::Choose(position) => {
+                    self.choose(cx, *position);
+                }
+
+                ComboboxUpdate::Toggle => {
+                    if self.is_open {
+                        self.text = self.value.clone();
+                        self.close();
+                    } else {
+                        self.editing = true;
+                        self.filter(cx, "");
+                        cx.emit_to(self.textbox, TextEvent::StartEdit);
+                    }
+                }
+
+                ComboboxUpdate::InitList(list) => {
+                    self.list = *list;
+                }
+
+                ComboboxUpdate::InitRows(rows) => {
+                    self.rows = rows.clone();
+                }
+            }
+
+            meta.consume();
+        });
+
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                // Clicking on the textbox starts editing it and shows all of the options
+                if cx.hovered() == self.textbox && !self.editing {
+                    self.editing = true;
+                    self.filter(cx, "");
+                }
+            }
+
+            WindowEvent::KeyDown(code, _) => match code {
+                Code::ArrowDown | Code::ArrowUp if self.editing => {
+                    if !self.is_open {
+                        self.filter(cx, "");
+                        return;
+                    }
+
+                    let len = self.matches.len();
+                    if len == 0 {
+                        return;
+                    }
+
+                    self.highlighted = Some(match (self.highlighted, *code == Code::ArrowDown) {
+                        (Some(highlighted), true) => (highlighted + 1).min(len - 1),
+                        (Some(highlighted), false) => highlighted.saturating_sub(1),
+                        (None, true) => 0,
+                        (None, false) => len - 1,
+                    });
+
+                    self.scroll_to_highlighted(cx);
+                }
+
+                Code::Escape if self.editing => {
+                    self.text = self.value.clone();
+                    self.close();
+                }
+
+                _ => {}
+            },
+
+            _ => {}
+        });
+    }
+}
+
+impl<L: Lens<Target = Vec<T>>, T: Clone> Handle<'_, Combobox<L, T>> {
+    /// Sets a callback which is called with the index of an option when it's chosen.
+    pub fn on_select<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext, usize),
+    {
+        if let Some(combobox) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<Combobox<L, T>>())
+        {
+            combobox.select_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    /// Allows values which aren't in the list of options. The callback is called with the typed
+    /// text when enter is pressed and the text doesn't match an option.
+    pub fn on_free_text<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext, String),
+    {
+        if let Some(combobox) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<Combobox<L, T>>())
+        {
+            combobox.free_text_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+}
+
+// Returns the range of the first case insensitive match of the filter in the text
+fn find_match(text: &str, filter: &str) -> Option<std::ops::Range<usize>> {
+    if filter.is_empty() {
+        return Some(0..0);
+    }
+
+    let filter: Vec<char> = filter.chars().flat_map(char::to_lowercase).collect();
+
+    'starts: for (start, _) in text.char_indices() {
+        let mut expected = filter.iter().peekable();
+        for (offset, c) in text[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                match expected.next() {
+                    Some(expected) if *expected == lower => {}
+                    _ => continue 'starts,
+                }
+            }
+
+            if expected.peek().is_none() {
+                return Some(start..start + offset + c.len_utf8());
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_filter_matches_start() {
+        assert_eq!(find_match("apple", ""), Some(0..0));
+        assert_eq!(find_match("", ""), Some(0..0));
+    }
+
+    #[test]
+    fn missing_filter_does_not_match() {
+        assert_eq!(find_match("", "a"), None);
+        assert_eq!(find_match("apple", "x"), None);
+        assert_eq!(find_match("apple", "apples"), None);
+        assert_eq!(find_match("abc", "bcd"), None);
+    }
+
+    #[test]
+    fn match_ignores_case() {
+        assert_eq!(find_match("Banana", "NAN"), Some(2..5));
+        assert_eq!(find_match("banana", "Ana"), Some(1..4));
+    }
+
+    #[test]
+    fn match_folds_unicode_case() {
+        // The range is in bytes, and É takes two
+        assert_eq!(find_match("ÉCOLE", "éco"), Some(0..4));
+        assert_eq!(find_match("ΣΊΣΥΦΟΣ", "σίσ"), Some(0..6));
+        assert_eq!(find_match("日本語テキスト", "テキ"), Some(9..15));
+
+        // İ lowercases to i followed by a combining dot, which must both match
+        assert_eq!(find_match("İstanbul", "i\u{307}st"), Some(0..4));
+        assert_eq!(find_match("İstanbul", "ist"), None);
+    }
+}
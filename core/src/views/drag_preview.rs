@@ -0,0 +1,30 @@
+use morphorm::PositionType;
+
+use crate::prelude::*;
+
+/// A view which follows the cursor while a view is dragged.
+///
+/// Previews are created and removed by the drag system. They show the contents set with the
+/// [`drag_preview`](crate::prelude::Handle::drag_preview) modifier of the dragged view, or an
+/// empty box the size of the dragged view otherwise, and can be styled with the `dragpreview`
+/// element selector.
+pub struct DragPreview {}
+
+impl DragPreview {
+    pub(crate) fn new<F>(cx: &mut Context, content: F) -> Handle<Self>
+    where
+        F: FnOnce(&mut Context),
+    {
+        Self {}
+            .build(cx, content)
+            .position_type(PositionType::SelfDirected)
+            .z_order(300)
+            .hoverable(false)
+    }
+}
+
+impl View for DragPreview {
+    fn element(&self) -> Option<&'static str> {
+        Some("dragpreview")
+    }
+}
@@ -161,6 +161,8 @@ impl Dropdown {
                 .height(Auto);
             })
             .size(Auto)
+            .role(Role::ComboBox)
+            .expanded(PopupData::is_open)
     }
 }
 
@@ -168,4 +170,13 @@ impl View for Dropdown {
     fn element(&self) -> Option<&'static str> {
         Some("dropdown")
     }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|access_action, _| match access_action {
+            AccessAction::Press => cx.emit(PopupEvent::Switch),
+            AccessAction::Expand => cx.emit(PopupEvent::Open),
+            AccessAction::Collapse => cx.emit(PopupEvent::Close),
+            _ => {}
+        });
+    }
 }
@@ -0,0 +1,249 @@
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use fnv::FnvHashMap;
+use keyboard_types::Code;
+
+use crate::prelude::*;
+use crate::state::{ByKey, Then};
+
+/// A view for creating a list of items from a binding to a `Vec<T>`, where each item is identified
+/// by a key.
+///
+/// Unlike [`List`], which rebuilds all of its items when the number of items changes, the views of
+/// an item are kept for as long as an item with the same key is in the list. Only the views of
+/// inserted items are built, the views of removed items are removed, and the views of moved items
+/// are moved, so the state of the views, such as focus or the text being edited in a textbox, is
+/// kept when other items are added or removed.
+///
+/// The keys of the items should be unique. Only the first of several items with the same key is
+/// shown.
+///
+/// The lens to an item has no value once the item is removed, so bindings in the views of an item
+/// should read it with `get_fallible` rather than `get`.
+///
+/// # Example
+/// ```ignore
+/// KeyedList::new(cx, AppData::todos, |todo| todo.id, |cx, _, todo| {
+///     Textbox::new(cx, todo.then(Todo::text));
+/// });
+/// ```
+pub struct KeyedList<L, T: 'static, K: 'static>
+where
+    L: Lens<Target = Vec<T>>,
+{
+    p: PhantomData<L>,
+    // The views built for each item
+    rows: FnvHashMap<K, Vec<Entity>>,
+    increment_callback: Option<Box<dyn Fn(&mut EventContext)>>,
+    decrement_callback: Option<Box<dyn Fn(&mut EventContext)>>,
+    clear_callback: Option<Box<dyn Fn(&mut EventContext)>>,
+}
+
+impl<L, T, K> KeyedList<L, T, K>
+where
+    L: 'static + Lens<Target = Vec<T>>,
+    T: Clone,
+    K: Data + Hash + Eq,
+{
+    /// Creates a new KeyedList view with a binding to the given lens, a function which returns the
+    /// key of an item, and a template for constructing the list items.
+    pub fn new<KF, F>(cx: &mut Context, lens: L, key: KF, item: F) -> Handle<Self>
+    where
+        KF: 'static + Fn(&T) -> K,
+        F: 'static + Fn(&mut Context, K, Then<L, ByKey<T, K>>),
+        <L as Lens>::Source: Model,
+    {
+        let key: Rc<dyn Fn(&T) -> K> = Rc::new(key);
+
+        Self {
+            p: PhantomData::default(),
+            rows: FnvHashMap::default(),
+            increment_callback: None,
+            decrement_callback: None,
+            clear_callback: None,
+        }
+        .build(cx, move |cx| {
+            let list = cx.current();
+            let key_fn = key.clone();
+
+            // The items are matched to their views when the keys change. The binding itself is
+            // always the first child of the list and the views of the items follow it.
+            Binding::new(
+                cx,
+                lens.clone()
+                    .map(move |items| items.iter().map(|item| (key_fn)(item)).collect::<Vec<_>>()),
+                move |cx, keys| {
+                    let binding = cx.current();
+                    let keys = keys.get_fallible(cx).unwrap_or_default();
+
+                    cx.with_current(list, |cx| {
+                        Self::reconcile(cx, binding, keys, &lens, &key, &item);
+                    });
+                },
+            );
+        })
+    }
+
+    fn reconcile<F>(
+        cx: &mut Context,
+        binding: Entity,
+        keys: Vec<K>,
+        lens: &L,
+        key_fn: &Rc<dyn Fn(&T) -> K>,
+        item: &F,
+    ) where
+        F: 'static + Fn(&mut Context, K, Then<L, ByKey<T, K>>),
+    {
+        let list = cx.current();
+
+        let mut old_rows =
+            match cx.views.get_mut(&list).and_then(|view| view.downcast_mut::<Self>()) {
+                Some(keyed_list) => std::mem::take(&mut keyed_list.rows),
+                None => return,
+            };
+
+        let mut rows = FnvHashMap::default();
+        let mut order = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            if rows.contains_key(&key) {
+                continue;
+            }
+
+            let entities = match old_rows.remove(&key) {
+                Some(entities) => entities,
+                None => {
+                    // New views are added after the last child of the list
+                    let last = cx.tree.get_last_child(list).unwrap_or(binding);
+                    (item)(
+                        cx,
+                        key.clone(),
+                        lens.clone().then(ByKey::new(key.clone(), key_fn.clone())),
+                    );
+                    let mut entities = Vec::new();
+                    let mut next = cx.tree.get_next_sibling(last);
+                    while let Some(entity) = next {
+                        entities.push(entity);
+                        next = cx.tree.get_next_sibling(entity);
+                    }
+                    entities
+                }
+            };
+
+            order.push(key.clone());
+            rows.insert(key, entities);
+        }
+
+        for entities in old_rows.into_values() {
+            for entity in entities {
+                cx.remove(entity);
+            }
+        }
+
+        // Move the views into the order of the items
+        let mut prev = binding;
+        let mut moved = false;
+        for key in order.iter() {
+            for entity in rows[key].iter() {
+                if cx.tree.get_next_sibling(prev) != Some(*entity) {
+                    cx.tree.set_next_sibling(prev, *entity).expect("Failed to move list item");
+                    moved = true;
+                }
+
+                prev = *entity;
+            }
+        }
+
+        if moved {
+            cx.style().restyle_children(list);
+            cx.need_relayout();
+        }
+
+        if let Some(keyed_list) =
+            cx.views.get_mut(&list).and_then(|view| view.downcast_mut::<Self>())
+        {
+            keyed_list.rows = rows;
+        }
+    }
+}
+
+impl<L, T, K> View for KeyedList<L, T, K>
+where
+    L: 'static + Lens<Target = Vec<T>>,
+    K: 'static,
+{
+    fn element(&self) -> Option<&'static str> {
+        Some("list")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::KeyDown(code, _) => match code {
+                Code::ArrowDown => {
+                    if let Some(callback) = &self.increment_callback {
+                        (callback)(cx);
+                    }
+                }
+
+                Code::ArrowUp => {
+                    if let Some(callback) = &self.decrement_callback {
+                        (callback)(cx);
+                    }
+                }
+
+                Code::Escape => {
+                    if let Some(callback) = &self.clear_callback {
+                        (callback)(cx);
+                    }
+                }
+
+                _ => {}
+            },
+
+            _ => {}
+        });
+    }
+}
+
+impl<L: Lens<Target = Vec<T>>, T: Data, K> Handle<'_, KeyedList<L, T, K>> {
+    pub fn on_increment<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<KeyedList<L, T, K>>())
+        {
+            list.increment_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    pub fn on_decrement<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<KeyedList<L, T, K>>())
+        {
+            list.decrement_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    pub fn on_clear<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<KeyedList<L, T, K>>())
+        {
+            list.clear_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+}
@@ -6,6 +6,7 @@ use crate::prelude::*;
 static DEFAULT_DRAG_SCALAR: f32 = 0.0042;
 static DEFAULT_WHEEL_SCALAR: f32 = 0.005;
 static DEFAULT_MODIFIER_SCALAR: f32 = 0.04;
+static DEFAULT_ACCESS_STEP: f32 = 0.1;
 
 use std::f32::consts::PI;
 
@@ -31,6 +32,7 @@ impl<L: Lens<Target = f32>> Knob<L> {
         lens: L,
         centered: bool,
     ) -> Handle<Self> {
+        let value = lens.clone();
         Self {
             lens: lens.clone(),
             default_normal: normalized_default.get_val(cx),
@@ -71,6 +73,8 @@ impl<L: Lens<Target = f32>> Knob<L> {
                 //     .rotate(30.0);
             });
         })
+        .role(Role::Slider)
+        .numeric_value(value)
     }
 
     pub fn custom<F, T>(
@@ -82,6 +86,7 @@ impl<L: Lens<Target = f32>> Knob<L> {
     where
         F: 'static + Fn(&mut Context, L) -> Handle<T>,
     {
+        let value = lens.clone();
         Self {
             lens: lens.clone(),
             default_normal,
@@ -101,6 +106,8 @@ impl<L: Lens<Target = f32>> Knob<L> {
                 (content)(cx, lens).width(Percentage(100.0)).height(Percentage(100.0));
             });
         })
+        .role(Role::Slider)
+        .numeric_value(value)
     }
 }
 
@@ -210,6 +217,24 @@ impl<L: Lens<Target = f32>> View for Knob<L> {
 
             _ => {}
         });
+
+        event.map(|access_action, _| match access_action {
+            AccessAction::SetNumericValue(val) => {
+                move_virtual_slider(self, cx, *val as f32);
+            }
+
+            AccessAction::Increment => {
+                let new_normal = self.lens.get(cx) + DEFAULT_ACCESS_STEP;
+                move_virtual_slider(self, cx, new_normal);
+            }
+
+            AccessAction::Decrement => {
+                let new_normal = self.lens.get(cx) - DEFAULT_ACCESS_STEP;
+                move_virtual_slider(self, cx, new_normal);
+            }
+
+            _ => {}
+        });
     }
 }
 
@@ -422,7 +447,7 @@ impl Handle<'_, TickKnob> {
             if let Some(view) = cx.views.get_mut(&entity) {
                 if let Some(knob) = view.downcast_mut::<TickKnob>() {
                     knob.normalized_value = value;
-                    cx.style().needs_redraw = true;
+                    cx.style().redraw(entity);
                 }
             }
         });
@@ -551,7 +576,7 @@ impl Handle<'_, ArcTrack> {
             if let Some(view) = cx.views.get_mut(&entity) {
                 if let Some(knob) = view.downcast_mut::<ArcTrack>() {
                     knob.normalized_value = value;
-                    cx.style().needs_redraw = true;
+                    cx.style().redraw(entity);
                 }
             }
         });
@@ -1,5 +1,9 @@
 use std::cell::RefCell;
 
+use fnv::FnvHashMap;
+use keyboard_types::Code;
+
+use crate::events::ViewHandler;
 use crate::fonts::{material_names::RIGHT, unicode_names::CHECK};
 use crate::modifiers::Over;
 use crate::prelude::*;
@@ -21,11 +25,13 @@ where
     F2: 'static + Fn(&mut Context),
 {
     if let Some(data) = handle.cx.data::<MenuData>() {
-        let i = *data.counter.borrow();
-        *data.counter.borrow_mut() += 1;
+        let entity = handle.entity;
+        data.prune(&handle.cx.views);
+        data.entries.borrow_mut().push(MenuEntry { entity, mnemonic: None });
         handle
             .bind(MenuData::selected, move |handle, selected| {
-                let selected = selected.get(handle.cx) == Some(i);
+                let index = handle.cx.data::<MenuData>().and_then(|data| data.index_of(entity));
+                let selected = index.is_some() && selected.get(handle.cx) == index;
                 handle.cx.set_selected(selected);
                 if selected {
                     on_select(handle.cx);
@@ -35,7 +41,11 @@ where
             })
             .on_over(move |cx| {
                 if cx.data::<MenuControllerData>().unwrap().active {
-                    cx.emit(MenuEvent::SetSelected(Some(i)));
+                    if let Some(index) =
+                        cx.data::<MenuData>().and_then(|data| data.index_of(entity))
+                    {
+                        cx.emit(MenuEvent::SetSelected(Some(index)));
+                    }
                 }
             })
     } else {
@@ -48,11 +58,36 @@ where
 #[derive(Lens, Default)]
 struct MenuData {
     selected: Option<usize>,
-    counter: RefCell<usize>,
+    entries: RefCell<Vec<MenuEntry>>,
+}
+
+impl MenuData {
+    // Drops the entries whose views have been removed, such as those rebuilt by a binding
+    fn prune(&self, views: &FnvHashMap<Entity, Box<dyn ViewHandler>>) {
+        self.entries.borrow_mut().retain(|entry| views.contains_key(&entry.entity));
+    }
+
+    fn index_of(&self, entity: Entity) -> Option<usize> {
+        self.entries.borrow().iter().position(|entry| entry.entity == entity)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct MenuEntry {
+    entity: Entity,
+    mnemonic: Option<Code>,
 }
 
 struct MenuControllerData {
     active: bool,
+    accelerators: RefCell<Vec<(KeyChord, Entity)>>,
+}
+
+impl MenuControllerData {
+    // Drops the accelerators of menu buttons which have been removed
+    fn prune(&self, views: &FnvHashMap<Entity, Box<dyn ViewHandler>>) {
+        self.accelerators.borrow_mut().retain(|(_, button)| views.contains_key(button));
+    }
 }
 
 /// Menu control events.
@@ -60,6 +95,8 @@ pub enum MenuEvent {
     SetSelected(Option<usize>),
     Close,
     Activate,
+    /// Performs the action of a menu button, as if it was clicked.
+    Press,
 }
 
 impl Model for MenuData {
@@ -70,7 +107,7 @@ impl Model for MenuData {
                 meta.consume();
             }
             MenuEvent::Close => self.selected = None,
-            MenuEvent::Activate => {}
+            MenuEvent::Activate | MenuEvent::Press => {}
         });
     }
 }
@@ -79,8 +116,11 @@ impl Model for MenuControllerData {
     fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
         event.map(|menu_event, _| match menu_event {
             MenuEvent::Close => {
-                self.active = false;
-                cx.release();
+                // An accelerator closes the menu without it having been opened
+                if self.active {
+                    self.active = false;
+                    cx.release();
+                }
             }
             MenuEvent::Activate => self.active = true,
             _ => {}
@@ -90,7 +130,17 @@ impl Model for MenuControllerData {
 
 /// A MenuController is a container object which holds a menu. It is responsible for managing
 /// the focus of the menu, i.e. grabbing click events until the menu is closed.
-pub struct MenuController {}
+///
+/// It also drives the menu from the keyboard. Pressing and releasing Alt, or pressing Alt with
+/// the mnemonic of a top-level menu, opens the menu. While it's open, the arrow keys move between
+/// the entries, Enter and Space activate the highlighted entry, typing a mnemonic activates the
+/// entry with that mnemonic, and Escape closes one level. The accelerators of menu buttons
+/// perform their actions whether the menu is open or not, except while a text field is focused so
+/// that it keeps shortcuts such as Ctrl+C.
+pub struct MenuController {
+    // Whether Alt has been pressed without any other key since
+    alt_pressed: bool,
+}
 
 impl MenuController {
     pub fn new<F: FnOnce(&mut Context)>(
@@ -102,16 +152,304 @@ impl MenuController {
             panic!("Building a MenuController inside a MenuController. This is illegal.")
         }
 
-        Self {}.build(cx, move |cx| {
-            MenuControllerData { active }.build(cx);
+        Self { alt_pressed: false }.build(cx, move |cx| {
+            MenuControllerData { active, accelerators: RefCell::new(Vec::new()) }.build(cx);
             if active {
                 cx.capture();
             }
+
+            // Key events go to the focused view, so they're caught before they get there
+            cx.add_listener(|controller: &mut MenuController, cx, event| {
+                let active = cx.data::<MenuControllerData>().map_or(false, |data| data.active);
+
+                event.map(|window_event, meta| match window_event {
+                    WindowEvent::KeyDown(code, _) => {
+                        if matches!(code, Code::AltLeft | Code::AltRight) {
+                            controller.alt_pressed = true;
+                            return;
+                        }
+
+                        controller.alt_pressed = false;
+
+                        let handled = press_accelerator(cx, *code)
+                            || if active { navigate(cx, *code) } else { open_mnemonic(cx, *code) };
+
+                        if handled {
+                            meta.consume();
+                        }
+                    }
+
+                    WindowEvent::KeyUp(code, _) => {
+                        if matches!(code, Code::AltLeft | Code::AltRight) && controller.alt_pressed
+                        {
+                            controller.alt_pressed = false;
+                            if active {
+                                close_menus(cx);
+                            } else if let Some(top) = top_stack(cx) {
+                                open_menu(cx, top, 0);
+                            }
+                        }
+                    }
+
+                    _ => {}
+                });
+            });
+
             builder(cx);
         })
     }
 }
 
+// Returns the menu bar, or whichever menu stack is outermost in the menu controller
+fn top_stack(cx: &EventContext) -> Option<Entity> {
+    cx.current().branch_iter(cx.tree).find(|entity| is_menu_stack(cx, *entity))
+}
+
+fn is_menu_stack(cx: &EventContext, entity: Entity) -> bool {
+    cx.views.get(&entity).and_then(|view| view.downcast_ref::<MenuStack>()).is_some()
+}
+
+// Returns the menu stack holding the entries of a submenu
+fn submenu(cx: &EventContext, entry: Entity) -> Option<Entity> {
+    entry.child_iter(cx.tree).find(|child| is_menu_stack(cx, *child))
+}
+
+// The state of a menu stack which is open
+struct MenuLevel {
+    stack: Entity,
+    horizontal: bool,
+    entries: Vec<MenuEntry>,
+    selected: Option<usize>,
+}
+
+impl MenuLevel {
+    fn new(cx: &EventContext, stack: Entity) -> Option<Self> {
+        let data =
+            cx.data.get(stack)?.data.values().find_map(|model| model.downcast_ref::<MenuData>())?;
+        data.prune(cx.views);
+        let horizontal = cx
+            .views
+            .get(&stack)
+            .and_then(|view| view.downcast_ref::<MenuStack>())
+            .map_or(false, |menu_stack| menu_stack.horizontal);
+
+        Some(Self {
+            stack,
+            horizontal,
+            entries: data.entries.borrow().clone(),
+            selected: data.selected.filter(|selected| *selected < data.entries.borrow().len()),
+        })
+    }
+
+    // Returns the index of the entry after or before the selected one, wrapping around
+    fn step(&self, forward: bool) -> usize {
+        let len = self.entries.len();
+        match (self.selected, forward) {
+            (Some(selected), true) => (selected + 1) % len,
+            (Some(selected), false) => (selected + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        }
+    }
+
+    fn select(&self, cx: &mut EventContext, index: usize) {
+        cx.emit_to(self.stack, MenuEvent::SetSelected(Some(index)));
+    }
+}
+
+// Returns the open menu stacks from the outermost one, where each stack after the first is the
+// submenu of the selected entry of the one before it
+fn open_levels(cx: &EventContext, top: Entity) -> Vec<MenuLevel> {
+    let mut levels: Vec<MenuLevel> = Vec::new();
+    let mut stack = Some(top);
+    while let Some(level) = stack.and_then(|stack| MenuLevel::new(cx, stack)) {
+        stack = level.selected.and_then(|selected| submenu(cx, level.entries[selected].entity));
+        levels.push(level);
+    }
+
+    // Menus without entries can't be navigated
+    levels.retain(|level| !level.entries.is_empty());
+    levels
+}
+
+fn open_menu(cx: &mut EventContext, top: Entity, index: usize) {
+    if let Some(level) = MenuLevel::new(cx, top) {
+        if index < level.entries.len() {
+            cx.capture();
+            cx.emit(MenuEvent::Activate);
+            level.select(cx, index);
+        }
+    }
+}
+
+fn close_menus(cx: &mut EventContext) {
+    cx.emit_custom(
+        Event::new(MenuEvent::Close)
+            .propagate(Propagation::Subtree)
+            .target(cx.current())
+            .origin(cx.current()),
+    );
+}
+
+// Selects an entry, and performs its action if it's a menu button
+fn choose(cx: &mut EventContext, level: &MenuLevel, index: usize) {
+    let entity = level.entries[index].entity;
+    level.select(cx, index);
+    if submenu(cx, entity).is_none() {
+        cx.emit_to(entity, MenuEvent::Press);
+    }
+}
+
+fn press_accelerator(cx: &mut EventContext, code: Code) -> bool {
+    // Shortcuts such as Ctrl+C are left to a focused text field
+    if cx.style.role.get(*cx.focused) == Some(&Role::TextField) {
+        return false;
+    }
+
+    let chord = KeyChord::new(*cx.modifiers, code);
+    let buttons = match cx.data::<MenuControllerData>() {
+        Some(data) => {
+            data.prune(cx.views);
+            data.accelerators
+                .borrow()
+                .iter()
+                .filter(|(accelerator, _)| *accelerator == chord)
+                .map(|(_, button)| *button)
+                .collect::<Vec<_>>()
+        }
+        None => return false,
+    };
+
+    for button in buttons.iter() {
+        cx.emit_to(*button, MenuEvent::Press);
+    }
+
+    !buttons.is_empty()
+}
+
+fn open_mnemonic(cx: &mut EventContext, code: Code) -> bool {
+    if *cx.modifiers != Modifiers::ALT {
+        return false;
+    }
+
+    let top = match top_stack(cx) {
+        Some(top) => top,
+        None => return false,
+    };
+
+    let index = MenuLevel::new(cx, top)
+        .and_then(|level| level.entries.iter().position(|entry| entry.mnemonic == Some(code)));
+
+    match index {
+        Some(index) => {
+            open_menu(cx, top, index);
+            true
+        }
+        None => false,
+    }
+}
+
+// Handles a key press while the menu is open and returns whether it was used
+fn navigate(cx: &mut EventContext, code: Code) -> bool {
+    let levels = match top_stack(cx) {
+        Some(top) => open_levels(cx, top),
+        None => return false,
+    };
+
+    if levels.is_empty() {
+        return false;
+    }
+
+    // The level with the highlighted entry, and the submenu opened by that entry if none of its
+    // own entries are highlighted yet
+    let active = levels.iter().rposition(|level| level.selected.is_some());
+    let opened = Some(levels.len() - 1).filter(|last| levels[*last].selected.is_none());
+
+    match code {
+        Code::ArrowDown | Code::ArrowUp => {
+            let forward = code == Code::ArrowDown;
+            let level = match (opened, active) {
+                (Some(opened), _) if !levels[opened].horizontal => &levels[opened],
+                (_, Some(active)) if !levels[active].horizontal => &levels[active],
+                _ => return false,
+            };
+
+            level.select(cx, level.step(forward));
+        }
+
+        Code::ArrowRight | Code::ArrowLeft => {
+            let forward = code == Code::ArrowRight;
+
+            if let Some(active) = active {
+                if !levels[active].horizontal {
+                    match opened {
+                        // Enter the submenu of the highlighted entry
+                        Some(opened) if forward => {
+                            levels[opened].select(cx, 0);
+                            return true;
+                        }
+
+                        // Leave a submenu of a vertical menu
+                        _ if !forward && active > 0 && !levels[active - 1].horizontal => {
+                            cx.emit_to(levels[active].stack, MenuEvent::SetSelected(None));
+                            return true;
+                        }
+
+                        _ => {}
+                    }
+                }
+            }
+
+            // Otherwise move to the next or previous menu of the menu bar
+            match (0..=active.unwrap_or(0)).rev().find(|level| levels[*level].horizontal) {
+                Some(bar) => levels[bar].select(cx, levels[bar].step(forward)),
+                None => return false,
+            }
+        }
+
+        Code::Enter | Code::Space => {
+            let active = match active {
+                Some(active) => active,
+                None => return false,
+            };
+
+            match opened {
+                Some(opened) => levels[opened].select(cx, 0),
+                None => {
+                    let selected = levels[active].selected.unwrap_or_default();
+                    cx.emit_to(levels[active].entries[selected].entity, MenuEvent::Press);
+                }
+            }
+        }
+
+        Code::Escape => match active {
+            Some(active) if active > 0 => {
+                cx.emit_to(levels[active].stack, MenuEvent::SetSelected(None));
+            }
+            _ => close_menus(cx),
+        },
+
+        _ => {
+            if !cx.modifiers.is_empty() && *cx.modifiers != Modifiers::ALT {
+                return false;
+            }
+
+            // Mnemonics choose from the submenu which was just opened, or the highlighted menu
+            let level = match opened.or(active) {
+                Some(level) => &levels[level],
+                None => return false,
+            };
+
+            match level.entries.iter().position(|entry| entry.mnemonic == Some(code)) {
+                Some(index) => choose(cx, level, index),
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
+
 impl View for MenuController {
     fn element(&self) -> Option<&'static str> {
         Some("menucontroller")
@@ -176,28 +514,34 @@ impl View for MenuController {
 
 /// A MenuStack is a stack of views which can be menu entries. The only interesting thing about it
 /// is that it builds a MenuData into itself.
-pub struct MenuStack {}
+pub struct MenuStack {
+    horizontal: bool,
+}
 
 impl MenuStack {
-    fn new<F: FnOnce(&mut Context)>(cx: &mut Context, builder: F) -> Handle<'_, Self> {
+    fn new<F: FnOnce(&mut Context)>(
+        cx: &mut Context,
+        horizontal: bool,
+        builder: F,
+    ) -> Handle<'_, Self> {
         if cx.data::<MenuControllerData>().is_none() {
             panic!("MenuStacks must be built inside a MenuController");
         }
-        Self {}.build(cx, move |cx| {
+        Self { horizontal }.build(cx, move |cx| {
             MenuData::default().build(cx);
             builder(cx);
         })
     }
 
     pub fn new_vertical<F: FnOnce(&mut Context)>(cx: &mut Context, builder: F) -> Handle<'_, Self> {
-        Self::new(cx, builder).class("vertical")
+        Self::new(cx, false, builder).class("vertical")
     }
 
     pub fn new_horizontal<F: FnOnce(&mut Context)>(
         cx: &mut Context,
         builder: F,
     ) -> Handle<'_, Self> {
-        Self::new(cx, builder).class("horizontal")
+        Self::new(cx, true, builder).class("horizontal")
     }
 }
 
@@ -248,6 +592,26 @@ impl View for Menu {
     }
 }
 
+// Sets the key which chooses a menu entry from the keyboard
+fn set_mnemonic(cx: &Context, entity: Entity, mnemonic: Code) {
+    if let Some(data) = cx.data::<MenuData>() {
+        for entry in data.entries.borrow_mut().iter_mut() {
+            if entry.entity == entity {
+                entry.mnemonic = Some(mnemonic);
+            }
+        }
+    }
+}
+
+impl Handle<'_, Over<Menu>> {
+    /// Sets the key which opens the menu from the keyboard. For a menu in a menu bar this key is
+    /// pressed together with Alt while the menu is closed.
+    pub fn mnemonic(self, mnemonic: Code) -> Self {
+        set_mnemonic(self.cx, self.entity, mnemonic);
+        self
+    }
+}
+
 /// A MenuButton is an entry in a menu that can be clicked to perform some action. It has various
 /// constructors depending on whether you want to make this button show a check icon conditionally.
 pub struct MenuButton {
@@ -349,5 +713,40 @@ impl View for MenuButton {
             }
             _ => {}
         });
+
+        event.map(|menu_event, meta| match menu_event {
+            MenuEvent::Press => {
+                if let Some(callback) = &self.action {
+                    callback(cx);
+                    cx.emit(MenuEvent::Close);
+                }
+                meta.consume();
+            }
+            _ => {}
+        });
+    }
+}
+
+impl Handle<'_, Over<MenuButton>> {
+    /// Sets the key which performs the action of the button from the keyboard while its menu is
+    /// open.
+    pub fn mnemonic(self, mnemonic: Code) -> Self {
+        set_mnemonic(self.cx, self.entity, mnemonic);
+        self
+    }
+
+    /// Sets a key chord which performs the action of the button, whether its menu is open or not.
+    /// The key chord is shown at the end of the button.
+    pub fn accelerator(self, accelerator: KeyChord) -> Self {
+        if let Some(data) = self.cx.data::<MenuControllerData>() {
+            data.prune(&self.cx.views);
+            data.accelerators.borrow_mut().push((accelerator, self.entity));
+        }
+
+        self.cx.with_current(self.entity, |cx| {
+            Label::new(cx, &accelerator.to_string()).class("accelerator");
+        });
+
+        self
     }
 }
@@ -4,9 +4,12 @@
 
 mod button;
 mod checkbox;
+mod combobox;
+mod drag_preview;
 mod dropdown;
 mod element;
 mod image;
+mod keyed_list;
 mod knob;
 mod label;
 mod list;
@@ -17,15 +20,24 @@ mod radio_buttons;
 mod scrollbar;
 mod scrollview;
 mod slider;
+mod split_view;
 mod stack;
+mod tab_view;
 mod table;
 mod textbox;
+mod tooltip;
+mod tree_view;
+mod virtual_list;
+mod window;
 
 pub use self::image::Image;
 pub use button::Button;
 pub use checkbox::Checkbox;
+pub use combobox::Combobox;
+pub use drag_preview::DragPreview;
 pub use dropdown::Dropdown;
 pub use element::Element;
+pub use keyed_list::KeyedList;
 pub use knob::{ArcTrack, Knob, KnobMode, TickKnob, Ticks};
 pub use label::Label;
 pub use list::List;
@@ -35,9 +47,15 @@ pub use radio_buttons::RadioButton;
 pub use scrollbar::Scrollbar;
 pub use scrollview::{ScrollData, ScrollEvent, ScrollView};
 pub use slider::Slider;
+pub use split_view::SplitView;
 pub use stack::{HStack, VStack, ZStack};
-pub use table::{Table, TableColumn};
+pub use tab_view::TabView;
+pub use table::{SelectionMode, SortDirection, Table, TableColumn, TableEvent};
 pub use textbox::{TextEvent, Textbox};
+pub use tooltip::Tooltip;
+pub use tree_view::{TreeView, TreeViewEvent};
+pub use virtual_list::{RowHeight, VirtualList, VirtualListEvent};
+pub use window::Window;
 
 use crate::prelude::*;
 
@@ -3,6 +3,10 @@ use std::{marker::PhantomData, ops::Range};
 use crate::prelude::*;
 use crate::views::Orientation;
 
+/// The fraction of the range a slider moves by when incremented or decremented by assistive
+/// technology.
+static ACCESS_STEP: f32 = 0.1;
+
 #[derive(Debug)]
 enum SliderEventInternal {
     SetThumbSize(f32, f32),
@@ -96,6 +100,7 @@ where
     ///     });
     /// ```
     pub fn new(cx: &mut Context, lens: L) -> Handle<Self> {
+        let value = lens.clone();
         Self {
             p: PhantomData::default(),
             is_dragging: false,
@@ -174,6 +179,8 @@ where
                 });
             });
         })
+        .role(Role::Slider)
+        .numeric_value(value)
     }
 }
 
@@ -292,6 +299,25 @@ impl<L: Lens> View for Slider<L> {
 
             _ => {}
         });
+
+        event.map(|access_action, _| {
+            let min = self.internal.range.start;
+            let max = self.internal.range.end;
+            let step = (max - min) * ACCESS_STEP;
+            let current =
+                cx.style.numeric_value.get(cx.current()).map(|val| *val as f32).unwrap_or(min);
+
+            let val = match access_action {
+                AccessAction::SetNumericValue(val) => *val as f32,
+                AccessAction::Increment => current + step,
+                AccessAction::Decrement => current - step,
+                _ => return,
+            };
+
+            if let Some(callback) = &self.on_changing {
+                (callback)(cx, val.clamp(min, max));
+            }
+        });
     }
 }
 
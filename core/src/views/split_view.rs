@@ -0,0 +1,220 @@
+use std::marker::PhantomData;
+
+use crate::id::GenerationalId;
+use crate::prelude::*;
+use crate::views::Orientation;
+
+/// A view which divides its area between two panes with a splitter between them, which can be
+/// dragged to resize the panes.
+///
+/// The position of the splitter is bound to a ratio between 0 and 1, which is the share of the
+/// space taken by the first pane. Dragging the splitter calls the `on_changing` callback with the
+/// new ratio, and double clicking on it resets the ratio to the default ratio.
+///
+/// # Example
+/// ```ignore
+/// SplitView::new(
+///     cx,
+///     AppData::ratio,
+///     Orientation::Horizontal,
+///     |cx| {
+///         Label::new(cx, "Left");
+///     },
+///     |cx| {
+///         Label::new(cx, "Right");
+///     },
+/// )
+/// .min_pane_size(100.0)
+/// .on_changing(|cx, ratio| cx.emit(AppEvent::SetRatio(ratio)));
+/// ```
+pub struct SplitView<L> {
+    p: PhantomData<L>,
+    orientation: Orientation,
+    // The smallest size of either pane and the largest size of the first pane, in logical pixels
+    min_size: f32,
+    max_size: f32,
+    default_ratio: f32,
+    splitter: Entity,
+    // The distance between the cursor and the start of the splitter while it's dragged, in
+    // physical pixels
+    grab_offset: Option<f32>,
+
+    on_changing: Option<Box<dyn Fn(&mut EventContext, f32)>>,
+}
+
+impl<L: Lens<Target = f32>> SplitView<L> {
+    /// Creates a new split view with the ratio targeted by the lens, and the contents of the first
+    /// and second panes. A horizontal split view shows the panes side by side.
+    pub fn new<F1, F2>(
+        cx: &mut Context,
+        ratio: L,
+        orientation: Orientation,
+        first: F1,
+        second: F2,
+    ) -> Handle<Self>
+    where
+        F1: FnOnce(&mut Context),
+        F2: FnOnce(&mut Context),
+    {
+        let mut splitter = Entity::null();
+        let handle = Self {
+            p: PhantomData::default(),
+            orientation,
+            min_size: 0.0,
+            max_size: f32::INFINITY,
+            default_ratio: 0.5,
+            splitter: Entity::null(),
+            grab_offset: None,
+            on_changing: None,
+        }
+        .build(cx, |cx| {
+            VStack::new(cx, first).class("pane").bind(ratio.clone(), move |handle, ratio| {
+                let ratio = ratio.get(handle.cx).clamp(0.0, 1.0);
+                match orientation {
+                    Orientation::Horizontal => handle.width(Stretch(ratio)),
+                    Orientation::Vertical => handle.height(Stretch(ratio)),
+                };
+            });
+
+            splitter = Element::new(cx)
+                .class("splitter")
+                .cursor(match orientation {
+                    Orientation::Horizontal => CursorIcon::EwResize,
+                    Orientation::Vertical => CursorIcon::NsResize,
+                })
+                .entity();
+
+            VStack::new(cx, second).class("pane").bind(ratio, move |handle, ratio| {
+                let ratio = ratio.get(handle.cx).clamp(0.0, 1.0);
+                match orientation {
+                    Orientation::Horizontal => handle.width(Stretch(1.0 - ratio)),
+                    Orientation::Vertical => handle.height(Stretch(1.0 - ratio)),
+                };
+            });
+        });
+
+        let handle = handle.modify(|split_view| split_view.splitter = splitter);
+
+        match orientation {
+            Orientation::Horizontal => handle.layout_type(LayoutType::Row).class("horizontal"),
+            Orientation::Vertical => handle.layout_type(LayoutType::Column).class("vertical"),
+        }
+    }
+
+    // Returns the position and size of the view and the size of the splitter along the axis of the
+    // split, in physical pixels
+    fn sizes(&self, cx: &EventContext) -> (f32, f32, f32) {
+        let current = cx.current();
+        match self.orientation {
+            Orientation::Horizontal => (
+                cx.cache.get_posx(current),
+                cx.cache.get_width(current),
+                cx.cache.get_width(self.splitter),
+            ),
+            Orientation::Vertical => (
+                cx.cache.get_posy(current),
+                cx.cache.get_height(current),
+                cx.cache.get_height(self.splitter),
+            ),
+        }
+    }
+
+    fn change(&self, cx: &mut EventContext, ratio: f32) {
+        if let Some(callback) = &self.on_changing {
+            (callback)(cx, ratio);
+        }
+    }
+}
+
+impl<L: Lens<Target = f32>> View for SplitView<L> {
+    fn element(&self) -> Option<&'static str> {
+        Some("splitview")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                if meta.target == self.splitter {
+                    let (splitter_start, cursor) = match self.orientation {
+                        Orientation::Horizontal => {
+                            (cx.cache.get_posx(self.splitter), cx.mouse.cursorx)
+                        }
+                        Orientation::Vertical => {
+                            (cx.cache.get_posy(self.splitter), cx.mouse.cursory)
+                        }
+                    };
+
+                    self.grab_offset = Some(cursor - splitter_start);
+                    cx.capture();
+                    cx.set_active(true);
+                    meta.consume();
+                }
+            }
+
+            WindowEvent::MouseMove(x, y) => {
+                if let Some(grab_offset) = self.grab_offset {
+                    let (start, size, splitter_size) = self.sizes(cx);
+                    let available = size - splitter_size;
+                    if available <= 0.0 {
+                        return;
+                    }
+
+                    let cursor = match self.orientation {
+                        Orientation::Horizontal => *x,
+                        Orientation::Vertical => *y,
+                    };
+
+                    // Keep both panes above the minimum size and the first below the maximum
+                    let scale = cx.style.dpi_factor as f32;
+                    let min = (self.min_size * scale).min(available / 2.0);
+                    let max = (self.max_size * scale).min(available - min).max(min);
+                    let first = (cursor - grab_offset - start).clamp(min, max);
+
+                    self.change(cx, first / available);
+                }
+            }
+
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if self.grab_offset.take().is_some() {
+                    cx.release();
+                    cx.set_active(false);
+                }
+            }
+
+            WindowEvent::MouseDoubleClick(MouseButton::Left) => {
+                if meta.target == self.splitter {
+                    self.change(cx, self.default_ratio);
+                }
+            }
+
+            _ => {}
+        });
+    }
+}
+
+impl<L: Lens> Handle<'_, SplitView<L>> {
+    /// Sets the callback triggered when the splitter is dragged or reset, which is given the new
+    /// ratio.
+    pub fn on_changing<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext, f32),
+    {
+        self.modify(|split_view| split_view.on_changing = Some(Box::new(callback)))
+    }
+
+    /// Sets the smallest size of either pane in logical pixels when dragging the splitter.
+    pub fn min_pane_size(self, min_size: f32) -> Self {
+        self.modify(|split_view| split_view.min_size = min_size)
+    }
+
+    /// Sets the largest size of the first pane in logical pixels when dragging the splitter.
+    pub fn max_pane_size(self, max_size: f32) -> Self {
+        self.modify(|split_view| split_view.max_size = max_size)
+    }
+
+    /// Sets the ratio which the split view is reset to when the splitter is double clicked. The
+    /// default is 0.5.
+    pub fn default_ratio(self, default_ratio: f32) -> Self {
+        self.modify(|split_view| split_view.default_ratio = default_ratio.clamp(0.0, 1.0))
+    }
+}
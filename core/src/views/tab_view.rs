@@ -0,0 +1,268 @@
+use std::rc::Rc;
+
+use keyboard_types::Code;
+
+use crate::fonts::unicode_names::CANCEL;
+use crate::prelude::*;
+use crate::state::{Index, Then};
+
+enum TabUpdate {
+    Select(usize),
+    Close(usize),
+}
+
+/// A view which shows a strip of tabs built from a binding to a `Vec<T>`, and the content of the
+/// selected tab below it.
+///
+/// The index of the selected tab is bound to a lens. Pressing a tab calls the `on_select`
+/// callback with its index, and the content is rebuilt when the selected index changes. When an
+/// `on_close` callback is set, each tab shows a close button which calls it with the index of
+/// the tab when it's released over the button.
+///
+/// Ctrl+PageDown and Ctrl+PageUp select the next and previous tabs from anywhere within the view,
+/// and the arrow keys do the same after a tab has been pressed.
+///
+/// # Example
+/// ```ignore
+/// TabView::new(
+///     cx,
+///     AppData::documents,
+///     AppData::selected,
+///     |cx, _, document| {
+///         Label::new(cx, document.then(Document::name));
+///     },
+///     |cx, _, document| {
+///         Textbox::new(cx, document.then(Document::text));
+///     },
+/// )
+/// .on_select(|cx, index| cx.emit(AppEvent::Select(index)))
+/// .on_close(|cx, index| cx.emit(AppEvent::Close(index)));
+/// ```
+#[derive(Lens)]
+pub struct TabView<L: Lens<Target = Vec<T>>, S: Lens<Target = usize>, T: 'static + Clone> {
+    tabs: L,
+    selected: S,
+    closable: bool,
+    select_callback: Option<Box<dyn Fn(&mut EventContext, usize)>>,
+    close_callback: Option<Box<dyn Fn(&mut EventContext, usize)>>,
+}
+
+impl<L, S, T> TabView<L, S, T>
+where
+    L: 'static + Lens<Target = Vec<T>>,
+    S: 'static + Lens<Target = usize>,
+    T: Clone,
+{
+    /// Creates a new TabView view with a binding to the given list of tabs and the index of the
+    /// selected tab, a template for constructing the contents of the tab headers, and a template
+    /// for constructing the content of the selected tab.
+    pub fn new<H, F>(cx: &mut Context, tabs: L, selected: S, header: H, content: F) -> Handle<Self>
+    where
+        H: 'static + Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>),
+        F: 'static + Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>),
+        <L as Lens>::Source: Model,
+    {
+        let content = Rc::new(content);
+
+        Self {
+            tabs: tabs.clone(),
+            selected: selected.clone(),
+            closable: false,
+            select_callback: None,
+            close_callback: None,
+        }
+        .build(cx, move |cx| {
+            let count = tabs.clone().map(|tabs| tabs.len());
+
+            HStack::new(cx, {
+                let tabs = tabs.clone();
+                let selected = selected.clone();
+                let count = count.clone();
+                move |cx| {
+                    Binding::new(cx, count, move |cx, count| {
+                        for index in 0..count.get(cx) {
+                            HStack::new(cx, |cx| {
+                                (header)(cx, index, tabs.clone().index(index));
+
+                                TabClose { index, pressed: false }
+                                    .build(cx, |_| {})
+                                    .class("close")
+                                    .text(CANCEL)
+                                    .display(TabView::<L, S, T>::closable);
+                            })
+                            .class("tab")
+                            .checked(selected.clone().map(move |selected| *selected == index))
+                            .on_press(move |cx| cx.emit(TabUpdate::Select(index)));
+                        }
+                    });
+                }
+            })
+            .class("tab_header");
+
+            VStack::new(cx, move |cx| {
+                // The content is rebuilt when the selected tab changes, or when it's added or
+                // removed
+                Binding::new(cx, count, move |cx, count| {
+                    let count = count.get(cx);
+                    let tabs = tabs.clone();
+                    let content = content.clone();
+                    Binding::new(cx, selected.clone(), move |cx, selected| {
+                        let selected = selected.get(cx);
+                        if selected < count {
+                            (content)(cx, selected, tabs.clone().index(selected));
+                        }
+                    });
+                });
+            })
+            .class("tab_content");
+        })
+    }
+
+    fn select(&self, cx: &mut EventContext, index: usize) {
+        if let Some(callback) = &self.select_callback {
+            (callback)(cx, index);
+        }
+    }
+
+    // Returns the number of tabs and the index of the selected tab
+    fn state(&self, cx: &EventContext) -> Option<(usize, usize)>
+    where
+        <L as Lens>::Source: 'static,
+        <S as Lens>::Source: 'static,
+    {
+        let count = self.tabs.view(cx.data::<L::Source>()?, |tabs| tabs.map(|tabs| tabs.len()))?;
+        let selected = self.selected.view(cx.data::<S::Source>()?, |selected| selected.copied())?;
+        Some((count, selected))
+    }
+}
+
+impl<L, S, T> View for TabView<L, S, T>
+where
+    L: 'static + Lens<Target = Vec<T>>,
+    S: 'static + Lens<Target = usize>,
+    T: Clone,
+    <L as Lens>::Source: 'static,
+    <S as Lens>::Source: 'static,
+{
+    fn element(&self) -> Option<&'static str> {
+        Some("tabview")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|tab_update, meta| {
+            match tab_update {
+                TabUpdate::Select(index) => {
+                    cx.focus();
+                    self.select(cx, *index);
+                }
+
+                TabUpdate::Close(index) => {
+                    if let Some(callback) = &self.close_callback {
+                        (callback)(cx, *index);
+                    }
+                }
+            }
+
+            meta.consume();
+        });
+
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::KeyDown(code, _) => {
+                let (count, selected) = match self.state(cx) {
+                    Some(state) if state.0 > 0 => state,
+                    _ => return,
+                };
+
+                // The arrow keys are left to the content unless the tab view itself is focused
+                let focused = *cx.focused == cx.current();
+                let ctrl = cx.modifiers.contains(Modifiers::CTRL);
+                let next = (selected + 1) % count;
+                let previous = (selected + count - 1) % count;
+
+                let index = match code {
+                    Code::PageDown if ctrl => next,
+                    Code::PageUp if ctrl => previous,
+                    Code::ArrowRight if focused => next,
+                    Code::ArrowLeft if focused => previous,
+                    Code::Home if focused => 0,
+                    Code::End if focused => count - 1,
+                    _ => return,
+                };
+
+                self.select(cx, index);
+                meta.consume();
+            }
+
+            _ => {}
+        });
+    }
+}
+
+impl<L: Lens<Target = Vec<T>>, S: Lens<Target = usize>, T: Clone> Handle<'_, TabView<L, S, T>> {
+    /// Sets a callback which is called with the index of a tab when it's selected.
+    pub fn on_select<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext, usize),
+    {
+        if let Some(tab_view) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<TabView<L, S, T>>())
+        {
+            tab_view.select_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    /// Sets a callback which is called with the index of a tab when its close button is pressed.
+    /// The close buttons are only shown once this is set.
+    pub fn on_close<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext, usize),
+    {
+        if let Some(tab_view) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<TabView<L, S, T>>())
+        {
+            tab_view.closable = true;
+            tab_view.close_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+}
+
+// The close button of a tab, which closes the tab when it's pressed and released, and stops the
+// press from selecting the tab
+struct TabClose {
+    index: usize,
+    pressed: bool,
+}
+
+impl View for TabClose {
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                self.pressed = true;
+                cx.capture();
+                cx.set_active(true);
+                meta.consume();
+            }
+
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if self.pressed {
+                    self.pressed = false;
+                    cx.release();
+                    cx.set_active(false);
+
+                    // Releasing the mouse away from the button doesn't close the tab
+                    if cx.hovered() == cx.current() {
+                        cx.emit(TabUpdate::Close(self.index));
+                    }
+
+                    meta.consume();
+                }
+            }
+
+            _ => {}
+        });
+    }
+}
@@ -1,96 +1,923 @@
-use std::marker::PhantomData;
+use std::cmp::Ordering;
 use std::rc::Rc;
 
+use keyboard_types::Code;
+
+use crate::fonts::icons_names::{DOWN, UP};
+use crate::id::GenerationalId;
 use crate::prelude::*;
 use crate::state::{Index, Then};
+use crate::systems::drag_system::DRAG_THRESHOLD;
+use crate::views::virtual_list::unchanged_ends;
+
+// The height of the header and of each row in logical pixels
+const ROW_HEIGHT: f32 = 30.0;
+// The smallest width a column can be resized to in logical pixels
+const MIN_COLUMN_WIDTH: f32 = 20.0;
+
+/// How the rows of a [`Table`] can be selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Rows can't be selected.
+    None,
+    /// One row can be selected at a time.
+    Single,
+    /// Several rows can be selected by holding control or shift while clicking or using the arrow
+    /// keys.
+    Multiple,
+}
+
+/// The direction in which the rows of a [`Table`] are sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
 
-// TODO
+/// Events which can be sent to a [`Table`].
+///
+/// Rows are identified by the index of their item in the bound vector, and columns by their index
+/// in the vector of columns passed to [`Table::new`], regardless of how the table is sorted or how
+/// the columns have been moved.
+pub enum TableEvent {
+    /// Sorts the table by a column, or reverses the direction if the table is already sorted by it.
+    Sort(usize),
+    /// Sets the width of a column in logical pixels.
+    SetColumnWidth(usize, f32),
+    /// Moves a column to the given position in the header.
+    MoveColumn(usize, usize),
+    /// Selects every row, if multiple rows can be selected.
+    SelectAll,
+    /// Deselects every row.
+    ClearSelection,
+    /// Starts editing the cell of a row in a column, if the column is editable.
+    Edit(usize, usize),
+}
+
+// Events sent to a table by its own views
+enum TableUpdate {
+    // The items of the table changed
+    ItemsChanged,
+    // The row shown at the given position was pressed
+    PressRow(usize),
+    // A column was dropped onto the header of another column
+    DropColumn(usize, usize),
+    // The text of the cell being edited was submitted
+    CommitEdit(String),
+}
 
-pub struct Table<L, T: 'static>
+/// A column of a [`Table`], which is passed to [`Table::new`].
+///
+/// # Example
+/// ```ignore
+/// TableColumn::new("Age", |cx, _, person| {
+///     Label::new(cx, person.then(Person::age));
+/// })
+/// .width(100.0)
+/// .sortable(|a: &Person, b: &Person| a.age.cmp(&b.age))
+/// .editable(
+///     |person| person.age.to_string(),
+///     |cx, index, text| cx.emit(AppEvent::SetAge(index, text)),
+/// )
+/// ```
+pub struct TableColumn<L, T: 'static>
 where
     L: Lens<Target = Vec<T>>,
-    T: Data,
 {
-    p: PhantomData<L>,
+    title: String,
+    width: f32,
+    content: Rc<dyn Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>)>,
+    compare: Option<Box<dyn Fn(&T, &T) -> Ordering>>,
+    edit_text: Option<Box<dyn Fn(&T) -> String>>,
+    edit_callback: Option<Box<dyn Fn(&mut EventContext, usize, String)>>,
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Data> TableColumn<L, T> {
+    /// Creates a new column with a title and a template for constructing its cells, which is given
+    /// the index of the item shown by the cell and a lens to the item.
+    pub fn new<F>(title: &str, content: F) -> Self
+    where
+        F: 'static + Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>),
+    {
+        Self {
+            title: title.to_owned(),
+            width: 100.0,
+            content: Rc::new(content),
+            compare: None,
+            edit_text: None,
+            edit_callback: None,
+        }
+    }
+
+    /// Sets the initial width of the column in logical pixels.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+
+        self
+    }
+
+    /// Lets the table be sorted by the column by clicking on its header, using the given function
+    /// to compare items.
+    pub fn sortable<F>(mut self, compare: F) -> Self
+    where
+        F: 'static + Fn(&T, &T) -> Ordering,
+    {
+        self.compare = Some(Box::new(compare));
+
+        self
+    }
+
+    /// Lets the cells of the column be edited by double clicking on them, or by pressing enter
+    /// when their row is selected.
+    ///
+    /// While a cell is edited it shows a textbox which starts with the text returned by `text`.
+    /// When the edit is submitted the callback is called with the index of the item and the new
+    /// text, and should update the item.
+    pub fn editable<F, E>(mut self, text: F, callback: E) -> Self
+    where
+        F: 'static + Fn(&T) -> String,
+        E: 'static + Fn(&mut EventContext, usize, String),
+    {
+        self.edit_text = Some(Box::new(text));
+        self.edit_callback = Some(Box::new(callback));
+
+        self
+    }
+}
+
+/// A view for displaying a `Vec<T>` as rows with a column for each of the given [`TableColumn`]s.
+///
+/// The header of the table stays in place while the rows are scrolled, and only the visible rows
+/// are built, so the table can show large amounts of data. Clicking on the header of a sortable
+/// column sorts the rows, dragging the right edge of a header resizes its column, and dragging a
+/// header onto another one moves the column.
+///
+/// Sorting and moving columns only changes how the items are shown and doesn't change the bound
+/// vector. The rows are sorted again whenever the items change, and the selection follows the
+/// selected items when items are inserted, removed or moved.
+///
+/// # Example
+/// ```ignore
+/// Table::new(cx, AppData::people, vec![
+///     TableColumn::new("Name", |cx, _, person| {
+///         Label::new(cx, person.then(Person::name));
+///     })
+///     .sortable(|a: &Person, b: &Person| a.name.cmp(&b.name)),
+/// ])
+/// .selection_mode(SelectionMode::Multiple)
+/// .on_select(|cx, selected| cx.emit(AppEvent::Select(selected.to_vec())));
+/// ```
+#[derive(Lens)]
+pub struct Table<L: Lens<Target = Vec<T>>, T: 'static + Data> {
+    #[lens(ignore)]
+    lens: L,
+    // The items as of the last change, which the selection is remapped from when they change
+    items: Vec<T>,
+    columns: Rc<Vec<TableColumn<L, T>>>,
+    // The width of each column in logical pixels
+    widths: Vec<f32>,
+    // The columns in the order they are shown
+    column_order: Vec<usize>,
+    sort: Option<(usize, SortDirection)>,
+    // The index of the item shown in each row
+    order: Vec<usize>,
+    // The indices of the selected items
+    selected: Vec<usize>,
+    // The item which is moved by the arrow keys
+    cursor: Option<usize>,
+    // The item where a range selection with shift starts
+    anchor: Option<usize>,
+    // The item and column of the cell being edited
+    editing: Option<(usize, usize)>,
+    edit_text: String,
+    selection_mode: SelectionMode,
+    select_callback: Option<Box<dyn Fn(&mut EventContext, &[usize])>>,
+    list: Entity,
 }
 
 impl<L: 'static + Lens<Target = Vec<T>>, T: Data> Table<L, T> {
-    pub fn new<'a, F>(cx: &'a mut Context, lens: L, list_builder: F) -> Handle<'a, Self>
+    /// Creates a new Table view with a binding to the given lens and the columns to show.
+    pub fn new(cx: &mut Context, lens: L, columns: Vec<TableColumn<L, T>>) -> Handle<Self>
     where
-        F: 'static + Fn(&mut Context, L),
         <L as Lens>::Source: Model,
     {
-        Self { p: PhantomData::default() }.build(cx, move |cx| {
-            HStack::new(cx, move |cx| {
-                (list_builder)(cx, lens);
+        let mut table = Self::with_columns(lens.clone(), columns);
+        if let Some(items) =
+            cx.data::<L::Source>().and_then(|source| lens.view(source, |items| items.cloned()))
+        {
+            table.set_items(items);
+        }
+
+        let columns = table.columns.clone();
+        table.build(cx, move |cx| {
+            let table = cx.current();
+
+            // The rows are sorted by the values of the items, so the table observes the items
+            // rather than just their number
+            Binding::new(cx, lens.clone(), move |cx, _| {
+                cx.emit_custom(
+                    Event::new(TableUpdate::ItemsChanged)
+                        .target(table)
+                        .propagate(Propagation::Direct),
+                );
             });
+
+            let header_columns = columns.clone();
+            HStack::new(cx, move |cx| {
+                Binding::new(cx, Table::<L, T>::column_order, move |cx, column_order| {
+                    for column in column_order.get(cx) {
+                        Self::build_header(cx, table, &header_columns[column].title, column);
+                    }
+                });
+            })
+            .class("table_header")
+            .height(Pixels(ROW_HEIGHT));
+
+            let items = lens.clone();
+            let list =
+                VirtualList::new(cx, lens, RowHeight::Fixed(ROW_HEIGHT), move |cx, row, _| {
+                    Self::build_row(cx, items.clone(), columns.clone(), row);
+                })
+                .entity();
+
+            if let Some(table) =
+                cx.views.get_mut(&table).and_then(|view| view.downcast_mut::<Self>())
+            {
+                table.list = list;
+            }
         })
     }
+
+    fn with_columns(lens: L, columns: Vec<TableColumn<L, T>>) -> Self {
+        Self {
+            lens,
+            items: Vec::new(),
+            widths: columns.iter().map(|column| column.width).collect(),
+            column_order: (0..columns.len()).collect(),
+            columns: Rc::new(columns),
+            sort: None,
+            order: Vec::new(),
+            selected: Vec::new(),
+            cursor: None,
+            anchor: None,
+            editing: None,
+            edit_text: String::new(),
+            selection_mode: SelectionMode::Single,
+            select_callback: None,
+            list: Entity::null(),
+        }
+    }
+
+    fn build_header(cx: &mut Context, table: Entity, title: &str, column: usize) {
+        TableHeader { column, press: None }
+            .build(cx, |cx| {
+                Label::new(cx, title).class("title").width(Stretch(1.0)).hoverable(false);
+
+                Label::new(
+                    cx,
+                    Table::<L, T>::sort.map(move |sort| match sort {
+                        Some((sorted, SortDirection::Ascending)) if *sorted == column => {
+                            UP.to_owned()
+                        }
+                        Some((sorted, SortDirection::Descending)) if *sorted == column => {
+                            DOWN.to_owned()
+                        }
+                        _ => String::new(),
+                    }),
+                )
+                .class("sort_indicator")
+                .font("icons")
+                .hoverable(false);
+
+                ResizeHandle { column, press: None }
+                    .build(cx, |_| {})
+                    .class("resize_handle")
+                    .cursor(CursorIcon::EwResize);
+            })
+            .layout_type(LayoutType::Row)
+            .bind(Table::<L, T>::widths.map(move |widths| widths[column]), |handle, width| {
+                let width = width.get(handle.cx);
+                handle.width(Pixels(width));
+            })
+            .accept_drop(move |drag: &ColumnDrag| drag.table == table)
+            .draggable(move |_| ColumnDrag { table, column })
+            .on_drop(move |cx, drag: &ColumnDrag| {
+                cx.emit(TableUpdate::DropColumn(drag.column, column));
+            });
+    }
+
+    // Builds the cells of the item shown in a row, rebuilding them when the row shows a different
+    // item or the columns are moved
+    fn build_row(cx: &mut Context, lens: L, columns: Rc<Vec<TableColumn<L, T>>>, row: usize) {
+        let row_lens = Table::<L, T>::root
+            .map(move |table| (table.order.get(row).copied(), table.column_order.clone()));
+
+        Binding::new(cx, row_lens, move |cx, state| {
+            let (index, column_order) = state.get(cx);
+            let index = match index {
+                Some(index) => index,
+                None => return,
+            };
+
+            let lens = lens.clone();
+            let columns = columns.clone();
+            HStack::new(cx, move |cx| {
+                for column in column_order {
+                    Self::build_cell(
+                        cx,
+                        lens.clone(),
+                        columns[column].content.clone(),
+                        index,
+                        column,
+                    );
+                }
+            })
+            .class("table_row")
+            .checked(Table::<L, T>::selected.map(move |selected| selected.contains(&index)))
+            .on_press(move |cx| cx.emit(TableUpdate::PressRow(row)));
+        });
+    }
+
+    fn build_cell(
+        cx: &mut Context,
+        lens: L,
+        content: Rc<dyn Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>)>,
+        index: usize,
+        column: usize,
+    ) {
+        let editing = Table::<L, T>::editing.map(move |editing| *editing == Some((index, column)));
+
+        TableCell { index, column }
+            .build(cx, move |cx| {
+                Binding::new(cx, editing, move |cx, editing| {
+                    if editing.get(cx) {
+                        let textbox = Textbox::new(cx, Table::<L, T>::edit_text)
+                            .on_submit(|cx, text, _| cx.emit(TableUpdate::CommitEdit(text)))
+                            .width(Stretch(1.0))
+                            .height(Stretch(1.0))
+                            .entity();
+                        cx.emit_to(textbox, TextEvent::StartEdit);
+                    } else {
+                        (content)(cx, index, lens.clone().index(index));
+                    }
+                });
+            })
+            .bind(Table::<L, T>::widths.map(move |widths| widths[column]), |handle, width| {
+                let width = width.get(handle.cx);
+                handle.width(Pixels(width));
+            });
+    }
+
+    // Returns the row which shows an item
+    fn row_of(&self, index: usize) -> Option<usize> {
+        self.order.iter().position(|item| *item == index)
+    }
+
+    // Replaces the items of the table and sorts the rows again. The selection, cursor and edited
+    // cell follow their items, and are dropped if their items were removed.
+    fn set_items(&mut self, items: Vec<T>) {
+        let old = &self.items;
+        let (prefix, suffix) = unchanged_ends(old, &items);
+        let (old_end, new_end) = (old.len() - suffix, items.len() - suffix);
+        let remap = |index: usize| {
+            if index < prefix {
+                Some(index)
+            } else if index >= old_end {
+                Some(index - old_end + new_end)
+            } else {
+                // An item which can't be found was changed in place if no items were added or
+                // removed, and removed otherwise
+                (prefix..new_end)
+                    .find(|new| items[*new].same(&old[index]))
+                    .or(Some(index).filter(|_| old_end == new_end))
+            }
+        };
+
+        let mut selected = Vec::with_capacity(self.selected.len());
+        for index in self.selected.iter().filter_map(|index| remap(*index)) {
+            if !selected.contains(&index) {
+                selected.push(index);
+            }
+        }
+
+        let cursor = self.cursor.and_then(&remap);
+        let anchor = self.anchor.and_then(&remap);
+        let editing = self.editing.and_then(|(index, column)| Some((remap(index)?, column)));
+
+        self.selected = selected;
+        self.cursor = cursor;
+        self.anchor = anchor;
+        self.editing = editing;
+        self.items = items;
+        self.apply_sort();
+    }
+
+    fn apply_sort(&mut self) {
+        self.order = (0..self.items.len()).collect();
+
+        let (column, direction) = match self.sort {
+            Some(sort) => sort,
+            None => return,
+        };
+
+        let columns = self.columns.clone();
+        let compare = match columns.get(column).and_then(|column| column.compare.as_ref()) {
+            Some(compare) => compare,
+            None => return,
+        };
+
+        let items = &self.items;
+        self.order.sort_by(|a, b| {
+            let ordering = (compare)(&items[*a], &items[*b]);
+            match direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+    }
+
+    // Selects the item shown in a row. If `extend` is true the selection is extended from the
+    // anchor to the row, and if `toggle` is true the selection of the item is toggled.
+    fn select_row(&mut self, cx: &mut EventContext, row: usize, extend: bool, toggle: bool) {
+        if self.select(row, extend, toggle) {
+            cx.emit_to(self.list, VirtualListEvent::ScrollTo(row));
+            self.notify_selection(cx);
+        }
+    }
+
+    // Updates the selection for a row being selected, returning false if there is no such row
+    fn select(&mut self, row: usize, extend: bool, toggle: bool) -> bool {
+        let index = match self.order.get(row) {
+            Some(index) => *index,
+            None => return false,
+        };
+
+        match self.selection_mode {
+            SelectionMode::None => {}
+
+            SelectionMode::Single => {
+                self.selected = vec![index];
+            }
+
+            SelectionMode::Multiple => {
+                let anchor = self.anchor.and_then(|anchor| self.row_of(anchor));
+                match anchor {
+                    Some(anchor) if extend => {
+                        self.selected = self.order[anchor.min(row)..=anchor.max(row)].to_vec();
+                    }
+
+                    _ if toggle => {
+                        if let Some(position) = self.selected.iter().position(|i| *i == index) {
+                            self.selected.remove(position);
+                        } else {
+                            self.selected.push(index);
+                        }
+                    }
+
+                    _ => {
+                        self.selected = vec![index];
+                    }
+                }
+            }
+        }
+
+        self.cursor = Some(index);
+        if !extend || self.anchor.is_none() {
+            self.anchor = Some(index);
+        }
+
+        true
+    }
+
+    fn move_cursor(&mut self, cx: &mut EventContext, up: bool) {
+        if self.order.is_empty() {
+            return;
+        }
+
+        let row = match self.cursor.and_then(|cursor| self.row_of(cursor)) {
+            Some(row) if up => row.saturating_sub(1),
+            Some(row) => (row + 1).min(self.order.len() - 1),
+            None => 0,
+        };
+
+        let extend = cx.modifiers.contains(Modifiers::SHIFT);
+        self.select_row(cx, row, extend, false);
+    }
+
+    fn notify_selection(&self, cx: &mut EventContext) {
+        if let Some(callback) = &self.select_callback {
+            (callback)(cx, &self.selected);
+        }
+    }
+
+    fn start_edit(&mut self, index: usize, column: usize) {
+        let columns = self.columns.clone();
+        let text = match columns.get(column).and_then(|column| column.edit_text.as_ref()) {
+            Some(text) => text,
+            None => return,
+        };
+
+        if let Some(text) = self.items.get(index).map(text) {
+            self.edit_text = text;
+            self.editing = Some((index, column));
+        }
+    }
 }
 
 impl<L: 'static + Lens<Target = Vec<T>>, T: Data> View for Table<L, T>
 where
-    L: 'static + Lens<Target = Vec<T>>,
+    <L as Lens>::Source: 'static,
 {
     fn element(&self) -> Option<&'static str> {
         Some("table")
     }
-}
 
-pub struct TableColumn<R, L, T, U>
-where
-    R: Lens<Target = Vec<T>>,
-    L: Lens<Source = T, Target = U>,
-    T: Data,
-    U: Data,
-{
-    p1: PhantomData<R>,
-    p2: PhantomData<L>,
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|update, meta| {
+            match update {
+                TableUpdate::ItemsChanged => {
+                    let items = cx.data::<L::Source>().map_or(Vec::new(), |source| {
+                        self.lens.view(source, |items| items.cloned().unwrap_or_default())
+                    });
+
+                    let selected = self.selected.clone();
+                    self.set_items(items);
+                    if self.selected != selected {
+                        self.notify_selection(cx);
+                    }
+                }
+
+                TableUpdate::PressRow(row) => {
+                    let extend = cx.modifiers.contains(Modifiers::SHIFT);
+                    let toggle = cx.modifiers.contains(Modifiers::CTRL);
+                    self.select_row(cx, *row, extend, toggle);
+                }
+
+                TableUpdate::DropColumn(column, target) => {
+                    if let Some(position) = self.column_order.iter().position(|c| c == target) {
+                        cx.emit(TableEvent::MoveColumn(*column, position));
+                    }
+                }
+
+                TableUpdate::CommitEdit(text) => {
+                    if let Some((index, column)) = self.editing.take() {
+                        let columns = self.columns.clone();
+                        if let Some(callback) =
+                            columns.get(column).and_then(|column| column.edit_callback.as_ref())
+                        {
+                            (callback)(cx, index, text.clone());
+                        }
+
+                        // Keep the keyboard navigation working after the textbox is removed
+                        cx.focus();
+                    }
+                }
+            }
+
+            meta.consume();
+        });
+
+        event.map(|table_event, _| match table_event {
+            TableEvent::Sort(column) => {
+                if self.columns.get(*column).map_or(false, |column| column.compare.is_some()) {
+                    self.sort = match self.sort {
+                        Some((sorted, SortDirection::Ascending)) if sorted == *column => {
+                            Some((sorted, SortDirection::Descending))
+                        }
+                        _ => Some((*column, SortDirection::Ascending)),
+                    };
+
+                    self.apply_sort();
+                }
+            }
+
+            TableEvent::SetColumnWidth(column, width) => {
+                if let Some(column_width) = self.widths.get_mut(*column) {
+                    *column_width = width.max(MIN_COLUMN_WIDTH);
+                }
+            }
+
+            TableEvent::MoveColumn(column, position) => {
+                if let Some(from) = self.column_order.iter().position(|c| c == column) {
+                    self.column_order.remove(from);
+                    let position = (*position).min(self.column_order.len());
+                    self.column_order.insert(position, *column);
+                }
+            }
+
+            TableEvent::SelectAll => {
+                if self.selection_mode == SelectionMode::Multiple {
+                    self.selected = self.order.clone();
+                    self.notify_selection(cx);
+                }
+            }
+
+            TableEvent::ClearSelection => {
+                self.selected.clear();
+                self.anchor = None;
+                self.notify_selection(cx);
+            }
+
+            TableEvent::Edit(index, column) => {
+                self.start_edit(*index, *column);
+            }
+        });
+
+        event.map(|window_event, _| match window_event {
+            WindowEvent::KeyDown(code, _) => {
+                // Keys go to the textbox while a cell is edited
+                if self.editing.is_some() {
+                    if *code == Code::Escape {
+                        self.editing = None;
+                        cx.focus();
+                    }
+
+                    return;
+                }
+
+                match code {
+                    Code::ArrowDown => {
+                        self.move_cursor(cx, false);
+                    }
+
+                    Code::ArrowUp => {
+                        self.move_cursor(cx, true);
+                    }
+
+                    Code::KeyA if cx.modifiers.contains(Modifiers::CTRL) => {
+                        cx.emit(TableEvent::SelectAll);
+                    }
+
+                    Code::Escape => {
+                        cx.emit(TableEvent::ClearSelection);
+                    }
+
+                    Code::Enter | Code::F2 => {
+                        // Edit the first editable column of the row at the cursor
+                        if let Some(cursor) = self.cursor {
+                            let column = self
+                                .column_order
+                                .iter()
+                                .copied()
+                                .find(|column| self.columns[*column].edit_text.is_some());
+
+                            if let Some(column) = column {
+                                self.start_edit(cursor, column);
+                            }
+                        }
+                    }
+
+                    _ => {}
+                }
+            }
+
+            _ => {}
+        });
+    }
 }
 
-impl<R, L, T: Data, U: Data> TableColumn<R, L, T, U>
-where
-    R: Lens<Target = Vec<T>>,
-    L: Lens<Source = T, Target = U>,
-{
-    pub fn new<F, Label>(
-        cx: &mut Context,
-        list: R,
-        item: L,
-        label: Label,
-        content: F,
-    ) -> Handle<Self>
+impl<L: Lens<Target = Vec<T>>, T: Data> Handle<'_, Table<L, T>> {
+    /// Sets how the rows of the table can be selected. By default one row can be selected at a time.
+    pub fn selection_mode(self, selection_mode: SelectionMode) -> Self {
+        if let Some(table) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<Table<L, T>>())
+        {
+            table.selection_mode = selection_mode;
+        }
+
+        self
+    }
+
+    /// Sets a callback which is called with the indices of the selected items when the selection
+    /// changes.
+    pub fn on_select<F>(self, callback: F) -> Self
     where
-        F: 'static + Fn(&mut Context, usize, Then<Then<R, Index<<R as Lens>::Target, T>>, L>),
-        Label: 'static + Fn(&mut Context),
-        <R as Lens>::Source: Model,
+        F: 'static + Fn(&mut EventContext, &[usize]),
     {
-        Self { p1: PhantomData::default(), p2: PhantomData::default() }.build(cx, move |cx| {
-            //VStack::new(cx, move |cx|{
-            (label)(cx);
-            //    Element::new(cx).height(Pixels(1.0)).background_color(Color::black());
-            //}).height(Pixels(30.0));
-
-            let content = Rc::new(content);
-            //let item = item.clone();
-            List::new(cx, list, move |cx, index, it| {
-                let content = content.clone();
-                let item = item.clone();
-                VStack::new(cx, move |cx| {
-                    //let item = item.clone();
-                    Binding::new(cx, it.then(item), move |cx, l| {
-                        (content)(cx, index, l.clone());
-                    });
-                });
-            })
-            .width(Stretch(1.0));
-        })
+        if let Some(table) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<Table<L, T>>())
+        {
+            table.select_callback = Some(Box::new(callback));
+        }
+
+        self
     }
 }
 
-impl<R, L, T: Data, U: Data> View for TableColumn<R, L, T, U>
-where
-    R: Lens<Target = Vec<T>>,
-    L: Lens<Source = T, Target = U>,
-{
+// The payload of a column header being dragged to move its column
+#[derive(Clone, Copy)]
+struct ColumnDrag {
+    table: Entity,
+    column: usize,
+}
+
+// The header of a column, which sorts the table by the column when clicked
+struct TableHeader {
+    column: usize,
+    // Where the left mouse button was pressed on the header, until it's released
+    press: Option<(f32, f32)>,
+}
+
+impl View for TableHeader {
+    fn element(&self) -> Option<&'static str> {
+        Some("tableheader")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                if cx.current() == cx.hovered()
+                    || cx.hovered().is_descendant_of(cx.tree, cx.current())
+                {
+                    self.press = Some((cx.mouse.cursorx, cx.mouse.cursory));
+                }
+            }
+
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                // A press which moved far enough to drag the header isn't a click
+                if let Some((pressx, pressy)) = self.press.take() {
+                    let threshold = DRAG_THRESHOLD * cx.style.dpi_factor as f32;
+                    let (x, y) = (cx.mouse.cursorx, cx.mouse.cursory);
+                    if ((x - pressx).powi(2) + (y - pressy).powi(2)).sqrt() < threshold {
+                        cx.emit(TableEvent::Sort(self.column));
+                    }
+                }
+            }
+
+            _ => {}
+        });
+    }
+}
+
+// The right edge of a column header, which resizes the column when dragged
+struct ResizeHandle {
+    column: usize,
+    // The horizontal position of the cursor and the width of the column when the handle was
+    // pressed, in physical pixels
+    press: Option<(f32, f32)>,
+}
+
+impl View for ResizeHandle {
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                if cx.current() == cx.hovered() {
+                    let header = cx.current().parent(cx.tree).unwrap_or(cx.current());
+                    self.press = Some((cx.mouse.cursorx, cx.cache.get_width(header)));
+                    cx.capture();
+
+                    // Pressing the handle shouldn't start dragging the header
+                    meta.consume();
+                }
+            }
+
+            WindowEvent::MouseMove(x, _) => {
+                if let Some((pressx, width)) = self.press {
+                    let scale = cx.style.dpi_factor as f32;
+                    cx.emit(TableEvent::SetColumnWidth(self.column, (width + x - pressx) / scale));
+                }
+            }
+
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if self.press.take().is_some() {
+                    cx.release();
+                    meta.consume();
+                }
+            }
+
+            _ => {}
+        });
+    }
+}
+
+// A cell of a table, which starts editing when double clicked
+struct TableCell {
+    index: usize,
+    column: usize,
+}
+
+impl View for TableCell {
+    fn element(&self) -> Option<&'static str> {
+        Some("tablecell")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseDoubleClick(MouseButton::Left) => {
+                cx.emit(TableEvent::Edit(self.index, self.column));
+            }
+
+            _ => {}
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Lens)]
+    struct AppData {
+        items: Vec<u32>,
+    }
+
+    fn table(items: Vec<u32>) -> Table<impl 'static + Lens<Target = Vec<u32>>, u32> {
+        let column = TableColumn::new("Number", |_, _, _| {})
+            .sortable(|a: &u32, b: &u32| a.cmp(b))
+            .editable(|n| n.to_string(), |_, _, _| {});
+        let mut table = Table::with_columns(AppData::items, vec![column]);
+        table.set_items(items);
+        table
+    }
+
+    #[test]
+    fn rows_are_sorted_again_when_items_change() {
+        let mut table = table(vec![3, 1, 2]);
+        assert_eq!(table.order, [0, 1, 2]);
+
+        table.sort = Some((0, SortDirection::Ascending));
+        table.apply_sort();
+        assert_eq!(table.order, [1, 2, 0]);
+
+        // An edited value moves its row
+        table.set_items(vec![3, 4, 2]);
+        assert_eq!(table.order, [2, 0, 1]);
+
+        table.sort = Some((0, SortDirection::Descending));
+        table.set_items(vec![3, 4, 2, 5]);
+        assert_eq!(table.order, [3, 1, 0, 2]);
+    }
+
+    #[test]
+    fn selection_extends_and_toggles() {
+        let mut table = table(vec![3, 1, 2, 0]);
+        table.sort = Some((0, SortDirection::Ascending));
+        table.apply_sort();
+        table.selection_mode = SelectionMode::Multiple;
+
+        // Rows select the items shown in them
+        assert!(table.select(0, false, false));
+        assert_eq!(table.selected, [3]);
+
+        // A range of rows is selected from the anchor
+        assert!(table.select(2, true, false));
+        assert_eq!(table.selected, [3, 1, 2]);
+
+        assert!(table.select(1, false, true));
+        assert_eq!(table.selected, [3, 2]);
+
+        assert!(!table.select(4, false, false));
+        assert_eq!(table.selected, [3, 2]);
+
+        table.selection_mode = SelectionMode::Single;
+        assert!(table.select(3, true, true));
+        assert_eq!(table.selected, [0]);
+    }
+
+    #[test]
+    fn selection_follows_items() {
+        let mut table = table(vec![10, 20, 30, 40]);
+        table.selection_mode = SelectionMode::Multiple;
+        table.select(1, false, false);
+        table.select(3, false, true);
+        assert_eq!(table.selected, [1, 3]);
+
+        // Inserting an item before the selected items moves their indices
+        table.set_items(vec![5, 10, 20, 30, 40]);
+        assert_eq!(table.selected, [2, 4]);
+        assert_eq!(table.cursor, Some(4));
+        assert_eq!(table.anchor, Some(4));
+
+        // Removing a selected item drops it from the selection
+        table.set_items(vec![5, 10, 30, 40]);
+        assert_eq!(table.selected, [3]);
+
+        // A moved item stays selected
+        table.set_items(vec![40, 5, 10, 30]);
+        assert_eq!(table.selected, [0]);
+
+        // An item changed in place stays selected
+        table.set_items(vec![41, 5, 10, 30]);
+        assert_eq!(table.selected, [0]);
+    }
+
+    #[test]
+    fn edited_cell_follows_its_item() {
+        let mut table = table(vec![1, 2, 3]);
+
+        table.start_edit(1, 0);
+        assert_eq!(table.editing, Some((1, 0)));
+        assert_eq!(table.edit_text, "2");
+
+        table.set_items(vec![0, 1, 2, 3]);
+        assert_eq!(table.editing, Some((2, 0)));
+
+        table.set_items(vec![0, 1, 3]);
+        assert_eq!(table.editing, None);
+
+        // Columns which aren't editable and missing items can't be edited
+        table.start_edit(1, 1);
+        table.start_edit(5, 0);
+        assert_eq!(table.editing, None);
+    }
 }
@@ -3,10 +3,11 @@ use crate::id::GenerationalId;
 use crate::prelude::*;
 use crate::text::{
     idx_to_pos, measure_text_lines, pos_to_idx, text_layout, text_paint_general, Direction,
-    EditableText, Movement, Selection,
+    EditHistory, EditableText, Movement, Selection,
 };
 use crate::tree::TreeExt;
 use keyboard_types::Code;
+use std::ops::Range;
 use std::sync::Arc;
 
 #[derive(Lens)]
@@ -22,6 +23,10 @@ pub struct TextboxData {
     content_entity: Entity,
     kind: TextboxKind,
     on_submit: Option<Arc<dyn Fn(&mut EventContext, String, bool) + Send + Sync>>,
+    history: EditHistory,
+    preedit: Option<Range<usize>>,
+    preedit_underline: Option<(f32, f32, f32)>,
+    ime_cursor_area: Option<(f32, f32, f32, f32)>,
 }
 
 impl TextboxData {
@@ -39,6 +44,10 @@ impl TextboxData {
             content_entity: Entity::null(),
             kind: TextboxKind::SingleLine,
             on_submit: None,
+            history: EditHistory::new(),
+            preedit: None,
+            preedit_underline: None,
+            ime_cursor_area: None,
         }
     }
 
@@ -71,6 +80,20 @@ impl TextboxData {
             measure_text_lines(&self.text, paint, &ranges, bounds.x, bounds.y, &cx.text_context);
         let ranges_metrics = ranges.into_iter().zip(metrics.into_iter()).collect::<Vec<_>>();
         let (line, (x, _)) = idx_to_pos(self.selection.active, ranges_metrics.iter());
+
+        // the underline of the text being composed, relative to the content
+        self.preedit_underline = self.preedit.as_ref().map(|range| {
+            let (start_line, (start_x, _)) = idx_to_pos(range.start, ranges_metrics.iter());
+            let (end_line, (end_x, _)) = idx_to_pos(range.end, ranges_metrics.iter());
+            // only the first line of a wrapped composition is underlined
+            let end_x = if end_line == start_line { end_x } else { bounds.x + bounds.w };
+            (
+                (start_x - bounds.x) / scale,
+                (start_line + 1) as f32 * line_height / scale - 1.0,
+                (end_x - start_x) / scale,
+            )
+        });
+
         if self.re_sel_x {
             self.re_sel_x = false;
             self.sel_x = x;
@@ -125,48 +148,72 @@ impl TextboxData {
         if caret_box.y + caret_box.h >= parent_bounds.y + parent_bounds.h {
             ty -= caret_box.y + caret_box.h - (parent_bounds.y + parent_bounds.h);
         }
+
+        // let the input method place its candidate window next to the caret
+        if self.edit {
+            let area = (
+                (x.round() + tx) / scale,
+                (bounds.y + line as f32 * line_height + ty) / scale,
+                1.0 / scale,
+                line_height / scale,
+            );
+            if self.ime_cursor_area != Some(area) {
+                self.ime_cursor_area = Some(area);
+                cx.emit(WindowEvent::SetImeCursorArea(area.0, area.1, area.2, area.3));
+            }
+        }
+
         self.transform = (tx.round() / scale, ty.round() / scale);
     }
 
+    // Replaces a range of the text and records the change in the edit history
+    fn replace_range(&mut self, range: Range<usize>, text: &str, selection: Selection) {
+        self.history.edit(&mut self.text, range, text, &mut self.selection, selection);
+    }
+
     pub fn insert_text(&mut self, _cx: &mut EventContext, text: &str) {
+        self.clear_preedit();
         let text_length = text.len();
-        self.text.edit(self.selection.range(), text);
-
-        self.selection = Selection::caret(self.selection.min() + text_length);
+        let selection = Selection::caret(self.selection.min() + text_length);
+        self.replace_range(self.selection.range(), text, selection);
     }
 
     pub fn delete_text(&mut self, _cx: &mut EventContext, movement: Movement) {
         if !self.selection.is_caret() {
-            self.text.edit(self.selection.range(), "");
-
-            self.selection = Selection::caret(self.selection.min());
+            self.replace_range(self.selection.range(), "", Selection::caret(self.selection.min()));
         } else {
             match movement {
                 Movement::Grapheme(Direction::Upstream) => {
                     if let Some(offset) = self.text.prev_grapheme_offset(self.selection.active) {
-                        self.text.edit(offset..self.selection.active, "");
-                        self.selection = Selection::caret(offset);
+                        self.replace_range(
+                            offset..self.selection.active,
+                            "",
+                            Selection::caret(offset),
+                        );
                     }
                 }
 
                 Movement::Grapheme(Direction::Downstream) => {
                     if let Some(offset) = self.text.next_grapheme_offset(self.selection.active) {
-                        self.text.edit(self.selection.active..offset, "");
-                        self.selection = Selection::caret(self.selection.active);
+                        let active = self.selection.active;
+                        self.replace_range(active..offset, "", Selection::caret(active));
                     }
                 }
 
                 Movement::Word(Direction::Upstream) => {
                     if let Some(offset) = self.text.prev_word_offset(self.selection.active) {
-                        self.text.edit(offset..self.selection.active, "");
-                        self.selection = Selection::caret(offset);
+                        self.replace_range(
+                            offset..self.selection.active,
+                            "",
+                            Selection::caret(offset),
+                        );
                     }
                 }
 
                 Movement::Word(Direction::Downstream) => {
                     if let Some(offset) = self.text.next_word_offset(self.selection.active) {
-                        self.text.edit(self.selection.active..offset, "");
-                        self.selection = Selection::caret(self.selection.active);
+                        let active = self.selection.active;
+                        self.replace_range(active..offset, "", Selection::caret(active));
                     }
                 }
 
@@ -269,9 +316,55 @@ impl TextboxData {
         }
     }
 
+    pub fn set_preedit(
+        &mut self,
+        _: &mut EventContext,
+        text: &str,
+        cursor: Option<(usize, usize)>,
+    ) {
+        self.clear_preedit();
+
+        if text.is_empty() {
+            return;
+        }
+
+        // replace the selection up front, so that committing the composition behaves like typing
+        if !self.selection.is_caret() {
+            self.replace_range(self.selection.range(), "", Selection::caret(self.selection.min()));
+        }
+
+        // the composition is shown in the text but isn't part of the edit history
+        let start = self.selection.active;
+        self.text.edit(start..start, text);
+        self.preedit = Some(start..start + text.len());
+        self.selection = match cursor {
+            Some((anchor, active)) => Selection::new(start + anchor, start + active),
+            None => Selection::caret(start + text.len()),
+        };
+    }
+
+    // Removes the text being composed with an input method
+    fn clear_preedit(&mut self) {
+        if let Some(range) = self.preedit.take() {
+            self.text.edit(range.clone(), "");
+            self.selection = Selection::caret(range.start);
+            self.preedit_underline = None;
+        }
+    }
+
     pub fn select_all(&mut self, _: &mut EventContext) {
         self.selection = Selection::new(0, self.text.len());
     }
+
+    pub fn undo(&mut self, _: &mut EventContext) -> bool {
+        self.clear_preedit();
+        self.history.undo(&mut self.text, &mut self.selection)
+    }
+
+    pub fn redo(&mut self, _: &mut EventContext) -> bool {
+        self.clear_preedit();
+        self.history.redo(&mut self.text, &mut self.selection)
+    }
 }
 
 pub enum TextEvent {
@@ -286,6 +379,9 @@ pub enum TextEvent {
     Drag(f32, f32),
     Copy,
     Paste,
+    Undo,
+    Redo,
+    SetPreedit(String, Option<(usize, usize)>),
 
     // Helpers
     SetOnEdit(Option<Arc<dyn Fn(&mut EventContext, String) + Send + Sync>>),
@@ -311,7 +407,8 @@ impl Model for TextboxData {
             }
 
             TextEvent::DeleteText(movement) => {
-                if self.edit {
+                // keys are handled by the input method while composing
+                if self.edit && self.preedit.is_none() {
                     self.delete_text(cx, *movement);
                     self.set_caret(cx);
 
@@ -324,12 +421,40 @@ impl Model for TextboxData {
             }
 
             TextEvent::MoveCursor(movement, selection) => {
-                if self.edit {
+                if self.edit && self.preedit.is_none() {
+                    self.history.break_merge();
                     self.move_cursor(cx, *movement, *selection);
                     self.set_caret(cx);
                 }
             }
 
+            TextEvent::SetPreedit(text, cursor) => {
+                if self.edit {
+                    self.set_preedit(cx, text, *cursor);
+                    self.set_caret(cx);
+                }
+            }
+
+            TextEvent::Undo | TextEvent::Redo => {
+                if self.edit {
+                    let changed = if matches!(text_event, TextEvent::Undo) {
+                        self.undo(cx)
+                    } else {
+                        self.redo(cx)
+                    };
+
+                    if changed {
+                        self.set_caret(cx);
+
+                        if let Some(callback) = self.on_edit.take() {
+                            (callback)(cx, self.text.as_str().to_owned());
+
+                            self.on_edit = Some(callback);
+                        }
+                    }
+                }
+            }
+
             TextEvent::StartEdit => {
                 if !cx.is_disabled() {
                     if !self.edit {
@@ -337,18 +462,25 @@ impl Model for TextboxData {
                         cx.focus();
                         cx.capture();
                         cx.set_checked(true);
+                        cx.emit(WindowEvent::SetImeAllowed(true));
                         cx.emit(TextEvent::SelectAll);
                     }
                 }
             }
 
             TextEvent::EndEdit => {
+                self.clear_preedit();
+                if self.edit {
+                    cx.emit(WindowEvent::SetImeAllowed(false));
+                }
                 self.edit = false;
+                self.ime_cursor_area = None;
                 cx.set_checked(false);
                 cx.release();
             }
 
             TextEvent::Submit(reason) => {
+                self.clear_preedit();
                 if let Some(callback) = self.on_submit.take() {
                     (callback)(cx, self.text.as_str().to_owned(), *reason);
 
@@ -358,6 +490,7 @@ impl Model for TextboxData {
             }
 
             TextEvent::SelectAll => {
+                self.history.break_merge();
                 self.select_all(cx);
                 self.set_caret(cx);
             }
@@ -370,6 +503,7 @@ impl Model for TextboxData {
                     posy,
                     cx.draw_cache.text_lines.get(self.content_entity).unwrap().iter(),
                 );
+                self.history.break_merge();
                 self.selection = Selection::new(idx, idx);
                 self.sel_x = posx;
                 self.set_caret(cx);
@@ -463,6 +597,7 @@ where
     }
 
     fn new_core(cx: &mut Context, lens: L, kind: TextboxKind) -> Handle<Self> {
+        let value = lens.clone();
         let result = Self { lens: lens.clone(), kind }.build(cx, move |cx| {
             Binding::new(cx, lens.clone(), |cx, text| {
                 let text =
@@ -481,6 +616,15 @@ where
                             content_entity: text_data.content_entity,
                             kind: text_data.kind,
                             on_submit: text_data.on_submit.clone(),
+                            // The history is only valid for the text it was recorded against
+                            history: if text == text_data.text {
+                                text_data.history.clone()
+                            } else {
+                                EditHistory::new()
+                            },
+                            preedit: text_data.preedit.clone(),
+                            preedit_underline: text_data.preedit_underline,
+                            ime_cursor_area: text_data.ime_cursor_area,
                         };
                         let parent = cx.current().parent(cx.tree()).unwrap();
                         cx.with_current(parent, |cx| td.build(cx));
@@ -499,7 +643,21 @@ where
             TextboxContainer {}
                 .build(cx, move |cx| {
                     let lbl = TextboxLabel {}
-                        .build(cx, |_| {})
+                        .build(cx, |cx| {
+                            // underlines the text being composed with an input method
+                            Binding::new(cx, TextboxData::preedit_underline, |cx, underline| {
+                                if let Some((left, top, width)) = underline.get(cx) {
+                                    Element::new(cx)
+                                        .class("textbox_preedit")
+                                        .position_type(PositionType::SelfDirected)
+                                        .left(Pixels(left))
+                                        .top(Pixels(top))
+                                        .width(Pixels(width))
+                                        .height(Pixels(1.0))
+                                        .hoverable(false);
+                                }
+                            });
+                        })
                         .hoverable(false)
                         .class("textbox_content")
                         .text(TextboxData::text)
@@ -521,6 +679,8 @@ where
                 TextboxKind::MultiLineWrapped => "multi_line_wrapped",
             })
             .cursor(CursorIcon::Text)
+            .role(Role::TextField)
+            .accessible_value(value)
     }
 }
 
@@ -612,6 +772,14 @@ where
                 }
             }
 
+            WindowEvent::ImePreedit(text, cursor) => {
+                cx.emit(TextEvent::SetPreedit(text.clone(), *cursor));
+            }
+
+            WindowEvent::ImeCommit(text) => {
+                cx.emit(TextEvent::InsertText(text.clone()));
+            }
+
             WindowEvent::KeyDown(code, _) => match code {
                 Code::Enter => {
                     // Finish editing
@@ -761,11 +929,42 @@ where
                     cx.emit(TextEvent::Paste);
                 }
 
+                Code::KeyZ => {
+                    if cx.modifiers.contains(Modifiers::CTRL) {
+                        if cx.modifiers.contains(Modifiers::SHIFT) {
+                            cx.emit(TextEvent::Redo);
+                        } else {
+                            cx.emit(TextEvent::Undo);
+                        }
+                    }
+                }
+
+                Code::KeyY => {
+                    if cx.modifiers.contains(Modifiers::CTRL) {
+                        cx.emit(TextEvent::Redo);
+                    }
+                }
+
                 _ => {}
             },
 
             _ => {}
         });
+
+        event.map(|access_action, _| match access_action {
+            AccessAction::Press => {
+                cx.emit(TextEvent::StartEdit);
+            }
+
+            AccessAction::SetValue(text) => {
+                cx.emit(TextEvent::StartEdit);
+                cx.emit(TextEvent::SelectAll);
+                cx.emit(TextEvent::InsertText(text.clone()));
+                cx.emit(TextEvent::Submit(false));
+            }
+
+            _ => {}
+        });
     }
 }
 
@@ -0,0 +1,31 @@
+use morphorm::PositionType;
+
+use crate::prelude::*;
+
+/// A view which shows the tooltip of a hovered view.
+///
+/// Tooltips are created and removed by the tooltip system for views with a tooltip set through
+/// the [`tooltip`](crate::prelude::Handle::tooltip) or
+/// [`tooltip_content`](crate::prelude::Handle::tooltip_content) modifiers. They can be styled with
+/// the `tooltip` element selector.
+pub struct Tooltip {}
+
+impl Tooltip {
+    pub(crate) fn new<F>(cx: &mut Context, content: F) -> Handle<Self>
+    where
+        F: FnOnce(&mut Context),
+    {
+        Self {}
+            .build(cx, content)
+            .position_type(PositionType::SelfDirected)
+            .z_order(200)
+            .hoverable(false)
+            .role(Role::Tooltip)
+    }
+}
+
+impl View for Tooltip {
+    fn element(&self) -> Option<&'static str> {
+        Some("tooltip")
+    }
+}
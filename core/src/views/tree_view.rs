@@ -0,0 +1,341 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use keyboard_types::Code;
+
+use crate::fonts::icons_names::{DOWN, RIGHT};
+use crate::prelude::*;
+use crate::state::{ByPath, Then};
+
+/// Events which can be sent to a [`TreeView`].
+///
+/// Nodes are identified by their path, which is the index of the node among the roots followed by
+/// its index among the children of each of its ancestors.
+pub enum TreeViewEvent {
+    /// Shows the children of a node.
+    Expand(Vec<usize>),
+    /// Hides the children of a node.
+    Collapse(Vec<usize>),
+    /// Shows the children of a node if they are hidden, and hides them otherwise.
+    Toggle(Vec<usize>),
+    /// Selects a node.
+    Select(Vec<usize>),
+}
+
+/// A view for displaying hierarchical data from a binding to a `Vec<T>` of root nodes, where each
+/// node has a `Vec<T>` of children.
+///
+/// Each node is shown as a row with a disclosure triangle, which shows or hides the children of
+/// the node when pressed, followed by the contents built by the item template. The children of a
+/// node are only built while it's expanded.
+///
+/// The selected node can be moved with the up and down arrow keys, and the right and left arrow
+/// keys expand and collapse it.
+///
+/// The expanded and selected nodes are remembered by their paths, so they aren't updated when
+/// nodes are inserted or removed.
+///
+/// # Example
+/// ```ignore
+/// TreeView::new(cx, AppData::folders, |folder: &Folder| &folder.children, |cx, _, folder| {
+///     Label::new(cx, folder.then(Folder::name));
+/// })
+/// .on_select(|cx, path| cx.emit(AppEvent::Select(path.to_vec())));
+/// ```
+#[derive(Lens)]
+pub struct TreeView<L: Lens<Target = Vec<T>>, T: 'static + Clone> {
+    items: L,
+    children: Rc<dyn Fn(&T) -> &Vec<T>>,
+    expanded: HashSet<Vec<usize>>,
+    selected: Option<Vec<usize>>,
+    select_callback: Option<Box<dyn Fn(&mut EventContext, &[usize])>>,
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Clone> TreeView<L, T> {
+    /// Creates a new TreeView view with a binding to the given lens, a function which returns the
+    /// children of a node, and a template for constructing the contents of the rows, which is
+    /// given the path of the node and a lens to the node.
+    pub fn new<C, F>(cx: &mut Context, lens: L, children: C, item: F) -> Handle<Self>
+    where
+        C: 'static + Fn(&T) -> &Vec<T>,
+        F: 'static + Fn(&mut Context, &[usize], Then<L, ByPath<T>>),
+        <L as Lens>::Source: Model,
+    {
+        let children: Rc<dyn Fn(&T) -> &Vec<T>> = Rc::new(children);
+        let item = Rc::new(item);
+
+        Self {
+            items: lens.clone(),
+            children: children.clone(),
+            expanded: HashSet::new(),
+            selected: None,
+            select_callback: None,
+        }
+        .build(cx, move |cx| {
+            Binding::new(cx, lens.clone().map(|roots| roots.len()), move |cx, len| {
+                for index in 0..len.get(cx) {
+                    Self::build_node(cx, lens.clone(), children.clone(), item.clone(), vec![index]);
+                }
+            });
+        })
+    }
+
+    fn build_node<F>(
+        cx: &mut Context,
+        lens: L,
+        children: Rc<dyn Fn(&T) -> &Vec<T>>,
+        item: Rc<F>,
+        path: Vec<usize>,
+    ) where
+        F: 'static + Fn(&mut Context, &[usize], Then<L, ByPath<T>>),
+    {
+        let node = lens.clone().then(ByPath::new(path.clone(), children.clone()));
+
+        VStack::new(cx, move |cx| {
+            let expanded = {
+                let path = path.clone();
+                TreeView::<L, T>::expanded.map(move |expanded| expanded.contains(&path))
+            };
+
+            let has_children = {
+                let children = children.clone();
+                node.clone().map(move |node| !(children)(node).is_empty())
+            };
+
+            let row_path = path.clone();
+            HStack::new(cx, |cx| {
+                let toggle_path = path.clone();
+                Label::new(
+                    cx,
+                    expanded.clone().map(|expanded| {
+                        if *expanded {
+                            DOWN.to_owned()
+                        } else {
+                            RIGHT.to_owned()
+                        }
+                    }),
+                )
+                .class("disclosure")
+                .font("icons")
+                .visibility(has_children)
+                .on_press(move |cx| cx.emit(TreeViewEvent::Toggle(toggle_path.clone())));
+
+                (item)(cx, &path, node.clone());
+            })
+            .class("tree_row")
+            .checked(TreeView::<L, T>::selected.map({
+                let path = path.clone();
+                move |selected| selected.as_ref() == Some(&path)
+            }))
+            .on_press(move |cx| cx.emit(TreeViewEvent::Select(row_path.clone())));
+
+            // The children are only built while the node is expanded
+            Binding::new(cx, expanded, move |cx, expanded| {
+                if !expanded.get(cx) {
+                    return;
+                }
+
+                let lens = lens.clone();
+                let children = children.clone();
+                let item = item.clone();
+                let path = path.clone();
+                let count = {
+                    let children = children.clone();
+                    node.clone().map(move |node| (children)(node).len())
+                };
+
+                VStack::new(cx, move |cx| {
+                    Binding::new(cx, count, move |cx, count| {
+                        for index in 0..count.get(cx) {
+                            let mut child_path = path.clone();
+                            child_path.push(index);
+                            Self::build_node(
+                                cx,
+                                lens.clone(),
+                                children.clone(),
+                                item.clone(),
+                                child_path,
+                            );
+                        }
+                    });
+                })
+                .class("tree_children");
+            });
+        })
+        .class("tree_node");
+    }
+
+    // Returns the paths of the nodes which are shown, in order, and whether each of them has
+    // children
+    fn visible_nodes(&self, roots: &[T]) -> Vec<(Vec<usize>, bool)> {
+        let mut nodes = Vec::new();
+        let mut path = Vec::new();
+        self.push_visible(roots, &mut path, &mut nodes);
+        nodes
+    }
+
+    fn push_visible(
+        &self,
+        siblings: &[T],
+        path: &mut Vec<usize>,
+        nodes: &mut Vec<(Vec<usize>, bool)>,
+    ) {
+        for (index, node) in siblings.iter().enumerate() {
+            path.push(index);
+
+            let children = (self.children)(node);
+            nodes.push((path.clone(), !children.is_empty()));
+            if self.expanded.contains(path) {
+                self.push_visible(children, path, nodes);
+            }
+
+            path.pop();
+        }
+    }
+
+    fn select(&mut self, cx: &mut EventContext, path: Vec<usize>) {
+        if let Some(callback) = &self.select_callback {
+            (callback)(cx, &path);
+        }
+
+        self.selected = Some(path);
+    }
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Clone> View for TreeView<L, T>
+where
+    <L as Lens>::Source: 'static,
+{
+    fn element(&self) -> Option<&'static str> {
+        Some("treeview")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|tree_event, _| match tree_event {
+            TreeViewEvent::Expand(path) => {
+                self.expanded.insert(path.clone());
+            }
+
+            TreeViewEvent::Collapse(path) => {
+                self.expanded.remove(path);
+            }
+
+            TreeViewEvent::Toggle(path) => {
+                if !self.expanded.remove(path) {
+                    self.expanded.insert(path.clone());
+                }
+            }
+
+            TreeViewEvent::Select(path) => {
+                self.select(cx, path.clone());
+            }
+        });
+
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseDown(_) => {
+                if *cx.focused != cx.current()
+                    && !cx.focused.is_descendant_of(cx.tree, cx.current())
+                {
+                    cx.focus();
+                }
+            }
+
+            WindowEvent::KeyDown(code, _) => {
+                let nodes = match cx.data::<L::Source>() {
+                    Some(source) => self.items.view(source, |roots| {
+                        roots.map(|roots| self.visible_nodes(roots)).unwrap_or_default()
+                    }),
+                    None => return,
+                };
+
+                if nodes.is_empty() {
+                    return;
+                }
+
+                let position = self
+                    .selected
+                    .as_ref()
+                    .and_then(|selected| nodes.iter().position(|(path, _)| path == selected));
+
+                match (code, position) {
+                    (Code::ArrowDown, Some(position)) => {
+                        if let Some((path, _)) = nodes.get(position + 1) {
+                            self.select(cx, path.clone());
+                        }
+                    }
+
+                    (Code::ArrowUp, Some(position)) => {
+                        if position > 0 {
+                            self.select(cx, nodes[position - 1].0.clone());
+                        }
+                    }
+
+                    (Code::ArrowDown, None) | (Code::ArrowUp, None) => {
+                        self.select(cx, nodes[0].0.clone());
+                    }
+
+                    (Code::ArrowRight, Some(position)) => {
+                        let (path, has_children) = &nodes[position];
+                        if *has_children {
+                            if self.expanded.contains(path) {
+                                let mut child = path.clone();
+                                child.push(0);
+                                self.select(cx, child);
+                            } else {
+                                self.expanded.insert(path.clone());
+                            }
+                        }
+                    }
+
+                    (Code::ArrowLeft, Some(position)) => {
+                        let path = &nodes[position].0;
+                        if self.expanded.contains(path) {
+                            self.expanded.remove(path);
+                        } else if path.len() > 1 {
+                            self.select(cx, path[..path.len() - 1].to_vec());
+                        }
+                    }
+
+                    (Code::Space, Some(position)) | (Code::Enter, Some(position)) => {
+                        let path = &nodes[position].0;
+                        if !self.expanded.remove(path) && nodes[position].1 {
+                            self.expanded.insert(path.clone());
+                        }
+                    }
+
+                    _ => {}
+                }
+            }
+
+            _ => {}
+        });
+    }
+}
+
+impl<L: Lens<Target = Vec<T>>, T: Clone> Handle<'_, TreeView<L, T>> {
+    /// Sets a callback which is called with the path of the selected node when the selection
+    /// changes.
+    pub fn on_select<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext, &[usize]),
+    {
+        if let Some(tree_view) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<TreeView<L, T>>())
+        {
+            tree_view.select_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    /// Expands a node, showing its children.
+    pub fn expand(self, path: &[usize]) -> Self {
+        if let Some(tree_view) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<TreeView<L, T>>())
+        {
+            tree_view.expanded.insert(path.to_vec());
+        }
+
+        self
+    }
+}
@@ -0,0 +1,676 @@
+use std::rc::Rc;
+
+use keyboard_types::Code;
+use morphorm::{GeometryChanged, PositionType};
+
+use crate::id::GenerationalId;
+use crate::prelude::*;
+use crate::state::{Index, Then};
+use crate::views::ScrollEvent;
+
+/// How the heights of the rows of a [`VirtualList`] are found.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// Every row has the same height, in logical pixels.
+    Fixed(f32),
+    /// Rows are measured once they have been built. Rows which haven't been built yet are assumed
+    /// to have the given estimated height, in logical pixels.
+    Measured(f32),
+}
+
+/// Events which can be sent to a [`VirtualList`].
+pub enum VirtualListEvent {
+    /// Scrolls the list so that the row with the given index is visible.
+    ScrollTo(usize),
+}
+
+// Events sent to a virtual list by its own views
+enum VirtualListUpdate {
+    // The number of items in a list with fixed row heights changed
+    Len(usize),
+    // The items of a list with measured row heights changed
+    ItemsChanged,
+    // A row with the given index was measured to have the given height in logical pixels
+    Measured(usize, f32),
+    // The rows measured by the last layout have all been received
+    MeasuredAll,
+}
+
+/// A view for displaying a long list of items from a binding to a `Vec<T>`.
+///
+/// Unlike [`List`], only the rows inside of the visible area of the list, plus a few rows above
+/// and below it, are built. The views which hold the rows are reused as the list is scrolled, so
+/// the cost of the list doesn't grow with the number of items.
+///
+/// # Example
+/// ```ignore
+/// VirtualList::new(cx, AppData::items, RowHeight::Fixed(30.0), |cx, index, item| {
+///     Label::new(cx, item);
+/// });
+/// ```
+#[derive(Lens)]
+pub struct VirtualList<L: Lens<Target = Vec<T>>, T: 'static + Data> {
+    #[lens(ignore)]
+    lens: L,
+    row_height: RowHeight,
+    overscan: usize,
+    len: usize,
+    // The items which the measured heights belong to, so that the heights can be moved along with
+    // their items when the list changes
+    items: Vec<T>,
+    // The measured heights of the rows when using measured row heights
+    heights: Vec<f32>,
+    // Whether rows have been measured since the offsets were last updated
+    measured: bool,
+    // The offsets of the tops of the rows when using measured row heights, followed by the total height
+    offsets: Vec<f32>,
+    // The scroll offset and height of the visible area in logical pixels
+    viewport: (f32, f32),
+    // The index of the row shown by each of the reusable row views
+    slots: Vec<Option<usize>>,
+    // The reusable row views
+    slot_views: Vec<Entity>,
+    scroll_view: Entity,
+    content: Entity,
+    increment_callback: Option<Box<dyn Fn(&mut EventContext)>>,
+    decrement_callback: Option<Box<dyn Fn(&mut EventContext)>>,
+    clear_callback: Option<Box<dyn Fn(&mut EventContext)>>,
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Data> VirtualList<L, T> {
+    /// Creates a new VirtualList view with a binding to the given lens, a way of finding the heights
+    /// of the rows, and a template for constructing the list items.
+    pub fn new<F>(cx: &mut Context, lens: L, row_height: RowHeight, item: F) -> Handle<Self>
+    where
+        F: 'static + Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>),
+        <L as Lens>::Source: Model,
+    {
+        let mut list = Self::with_row_height(lens.clone(), row_height);
+
+        if let RowHeight::Measured(_) = row_height {
+            let items = cx.data().map_or(Vec::new(), |source| {
+                lens.view(source, |items| items.cloned().unwrap_or_default())
+            });
+            list.set_items(items, &[]);
+        } else {
+            let len = cx.data().map_or(0, |source| {
+                lens.view(source, |items| items.map_or(0, |items| items.len()))
+            });
+            list.set_len(len);
+        }
+
+        let item = Rc::new(item);
+        list.build(cx, move |cx| {
+            let list = cx.current();
+
+            // Measured heights are moved along with their items, so a list with measured rows
+            // observes the items rather than just their number
+            if let RowHeight::Measured(_) = row_height {
+                Binding::new(cx, lens.clone(), move |cx, _| {
+                    cx.emit_custom(
+                        Event::new(VirtualListUpdate::ItemsChanged)
+                            .target(list)
+                            .propagate(Propagation::Direct),
+                    );
+                });
+            } else {
+                Binding::new(cx, lens.clone().map(|items| items.len()), move |cx, len| {
+                    let len = len.get(cx);
+                    cx.emit_custom(
+                        Event::new(VirtualListUpdate::Len(len))
+                            .target(list)
+                            .propagate(Propagation::Direct),
+                    );
+                });
+            }
+
+            let scroll_view = ScrollView::new(cx, 0.0, 0.0, false, true, move |cx| {
+                let content = cx.current();
+                if let Some(virtual_list) =
+                    cx.views.get_mut(&list).and_then(|view| view.downcast_mut::<Self>())
+                {
+                    virtual_list.content = content;
+                }
+
+                Binding::new(
+                    cx,
+                    VirtualList::<L, T>::root.map(|list| list.total_height()),
+                    move |cx, height| {
+                        let height = height.get(cx);
+                        cx.style().height.insert(content, Pixels(height));
+                        cx.style().width.insert(content, Stretch(1.0));
+                        cx.need_relayout();
+                    },
+                );
+
+                // Rebuild the rows when more of them are needed to fill the visible area
+                Binding::new(
+                    cx,
+                    VirtualList::<L, T>::slots.map(|slots| slots.len()),
+                    move |cx, count| {
+                        let slot_views = (0..count.get(cx))
+                            .map(|slot| {
+                                Self::build_slot(cx, lens.clone(), row_height, slot, item.clone())
+                            })
+                            .collect();
+
+                        if let Some(virtual_list) =
+                            cx.views.get_mut(&list).and_then(|view| view.downcast_mut::<Self>())
+                        {
+                            virtual_list.slot_views = slot_views;
+                        }
+                    },
+                );
+            })
+            .entity();
+
+            if let Some(virtual_list) =
+                cx.views.get_mut(&list).and_then(|view| view.downcast_mut::<Self>())
+            {
+                virtual_list.scroll_view = scroll_view;
+            }
+        })
+    }
+
+    fn with_row_height(lens: L, row_height: RowHeight) -> Self {
+        Self {
+            lens,
+            row_height,
+            overscan: 4,
+            len: 0,
+            items: Vec::new(),
+            heights: Vec::new(),
+            measured: false,
+            offsets: vec![0.0],
+            viewport: (0.0, 0.0),
+            slots: Vec::new(),
+            slot_views: Vec::new(),
+            scroll_view: Entity::null(),
+            content: Entity::null(),
+            increment_callback: None,
+            decrement_callback: None,
+            clear_callback: None,
+        }
+    }
+
+    // Builds the view which shows the row assigned to a slot, moving it to the position of the row
+    // and rebuilding its contents when the row changes
+    fn build_slot<F>(
+        cx: &mut Context,
+        lens: L,
+        row_height: RowHeight,
+        slot: usize,
+        item: Rc<F>,
+    ) -> Entity
+    where
+        F: 'static + Fn(&mut Context, usize, Then<L, Index<Vec<T>, T>>),
+    {
+        let index_lens =
+            VirtualList::<L, T>::slots.map(move |slots| slots.get(slot).copied().flatten());
+        let top_lens = VirtualList::<L, T>::root.map(move |list| list.slot_top(slot));
+
+        let height = match row_height {
+            RowHeight::Fixed(height) => Pixels(height),
+            RowHeight::Measured(_) => Auto,
+        };
+
+        VStack::new(cx, move |cx| {
+            Binding::new(cx, index_lens, move |cx, index| {
+                if let Some(index) = index.get(cx) {
+                    VStack::new(cx, |cx| (item)(cx, index, lens.clone().index(index)))
+                        .height(height)
+                        .on_geo_changed(move |cx, geo| {
+                            if geo.contains(GeometryChanged::HEIGHT_CHANGED) {
+                                let height =
+                                    cx.cache.get_height(cx.current()) / cx.style.dpi_factor as f32;
+                                cx.emit(VirtualListUpdate::Measured(index, height));
+                            }
+                        });
+                }
+            });
+        })
+        .class("virtual_row")
+        .position_type(PositionType::SelfDirected)
+        .width(Stretch(1.0))
+        .height(height)
+        .bind(top_lens, |handle, top| match top.get(handle.cx) {
+            Some(top) => {
+                handle.display(Display::Flex).top(Pixels(top));
+            }
+
+            None => {
+                handle.display(Display::None);
+            }
+        })
+        .entity()
+    }
+
+    fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.update_slots();
+    }
+
+    // Replaces the items of a list with measured row heights. The heights of the unchanged items at
+    // the start and end of the list are kept, and the rows in between which are built take the
+    // given heights of their views, since each view keeps showing the row with the same index.
+    fn set_items(&mut self, items: Vec<T>, built: &[(usize, f32)]) {
+        if let RowHeight::Measured(estimate) = self.row_height {
+            let (prefix, suffix) = unchanged_ends(&self.items, &items);
+            let changed = prefix..items.len() - suffix;
+
+            let mut heights = Vec::with_capacity(items.len());
+            heights.extend_from_slice(&self.heights[..prefix]);
+            heights.resize(changed.end, estimate);
+            heights.extend_from_slice(&self.heights[self.heights.len() - suffix..]);
+
+            for (index, height) in built {
+                if changed.contains(index) {
+                    heights[*index] = *height;
+                }
+            }
+
+            self.heights = heights;
+            self.update_offsets();
+        }
+
+        self.len = items.len();
+        self.items = items;
+        self.update_slots();
+    }
+
+    // Returns the index and height in logical pixels of the rows which are built
+    fn built_rows(&self, cx: &EventContext) -> Vec<(usize, f32)> {
+        let scale = cx.style.dpi_factor as f32;
+        self.slots
+            .iter()
+            .zip(self.slot_views.iter())
+            .filter_map(|(index, view)| Some(((*index)?, cx.cache.get_height(*view) / scale)))
+            .collect()
+    }
+
+    fn update_offsets(&mut self) {
+        self.offsets.clear();
+        self.offsets.push(0.0);
+
+        let mut offset = 0.0;
+        for height in self.heights.iter() {
+            offset += height;
+            self.offsets.push(offset);
+        }
+    }
+
+    // Returns the offset of the top of a row in logical pixels
+    fn row_top(&self, index: usize) -> f32 {
+        match self.row_height {
+            RowHeight::Fixed(height) => index as f32 * height,
+            RowHeight::Measured(_) => self.offsets[index.min(self.len)],
+        }
+    }
+
+    // Returns the height of a row in logical pixels
+    fn row_size(&self, index: usize) -> f32 {
+        match self.row_height {
+            RowHeight::Fixed(height) => height,
+            RowHeight::Measured(estimate) => self.heights.get(index).copied().unwrap_or(estimate),
+        }
+    }
+
+    fn total_height(&self) -> f32 {
+        self.row_top(self.len)
+    }
+
+    // Returns the index of the row at an offset in logical pixels
+    fn row_at(&self, offset: f32) -> usize {
+        let index = match self.row_height {
+            RowHeight::Fixed(height) if height > 0.0 => (offset.max(0.0) / height) as usize,
+            RowHeight::Fixed(_) => 0,
+            RowHeight::Measured(_) => {
+                self.offsets.partition_point(|top| *top <= offset).saturating_sub(1)
+            }
+        };
+
+        index.min(self.len.saturating_sub(1))
+    }
+
+    fn slot_top(&self, slot: usize) -> Option<f32> {
+        self.slots.get(slot).copied().flatten().map(|index| self.row_top(index))
+    }
+
+    // Assigns the rows in and around the visible area to the slots
+    fn update_slots(&mut self) {
+        if self.len == 0 {
+            self.slots.iter_mut().for_each(|slot| *slot = None);
+            return;
+        }
+
+        let (top, height) = self.viewport;
+        let first = self.row_at(top).saturating_sub(self.overscan);
+        let last = (self.row_at(top + height) + 1 + self.overscan).min(self.len);
+
+        if last - first > self.slots.len() {
+            self.slots = vec![None; last - first];
+        }
+
+        // Each row always uses the same slot so that scrolling only rebuilds the rows which scroll
+        // into view
+        let count = self.slots.len();
+        for (slot, row) in self.slots.iter_mut().enumerate() {
+            let index = first + (slot + count - first % count) % count;
+            *row = if index < last { Some(index) } else { None };
+        }
+    }
+
+    fn scroll_to(&self, cx: &mut EventContext, top: f32) {
+        let (_, height) = self.viewport;
+        let scrollable = self.total_height() - height;
+        if scrollable > 0.0 {
+            cx.emit_to(self.scroll_view, ScrollEvent::SetY((top / scrollable).clamp(0.0, 1.0)));
+        }
+    }
+}
+
+// Returns the number of items at the start and at the end of a list which are the same before and
+// after it changed
+pub(crate) fn unchanged_ends<T: Data>(old: &[T], new: &[T]) -> (usize, usize) {
+    let prefix = old.iter().zip(new.iter()).take_while(|(old, new)| old.same(new)).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old.same(new))
+        .count();
+
+    (prefix, suffix)
+}
+
+impl<L: 'static + Lens<Target = Vec<T>>, T: Data> View for VirtualList<L, T> {
+    fn element(&self) -> Option<&'static str> {
+        Some("virtuallist")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|update, meta| {
+            match update {
+                VirtualListUpdate::Len(len) => {
+                    if *len != self.len {
+                        self.set_len(*len);
+                    }
+                }
+
+                VirtualListUpdate::ItemsChanged => {
+                    let items = cx.data::<L::Source>().map_or(Vec::new(), |source| {
+                        self.lens.view(source, |items| items.cloned().unwrap_or_default())
+                    });
+                    let built = self.built_rows(cx);
+                    self.set_items(items, &built);
+                }
+
+                // The rows measured by a layout are all sent before this event reaches the list, so
+                // the offsets are only updated once for them
+                VirtualListUpdate::Measured(index, height) => {
+                    if let RowHeight::Measured(_) = self.row_height {
+                        if *index < self.len && self.heights[*index] != *height {
+                            self.heights[*index] = *height;
+
+                            if !self.measured {
+                                self.measured = true;
+                                cx.emit_custom(
+                                    Event::new(VirtualListUpdate::MeasuredAll)
+                                        .target(cx.current())
+                                        .propagate(Propagation::Direct),
+                                );
+                            }
+                        }
+                    }
+                }
+
+                VirtualListUpdate::MeasuredAll => {
+                    self.measured = false;
+                    self.update_offsets();
+                    self.update_slots();
+                }
+            }
+
+            meta.consume();
+        });
+
+        event.map(|list_event, _| match list_event {
+            VirtualListEvent::ScrollTo(index) => {
+                let (top, height) = self.viewport;
+                let row_top = self.row_top(*index);
+                let row_bottom = row_top + self.row_size(*index);
+
+                if row_top < top {
+                    self.scroll_to(cx, row_top);
+                } else if row_bottom > top + height {
+                    self.scroll_to(cx, row_bottom - height);
+                }
+            }
+        });
+
+        event.map(|window_event, _| match window_event {
+            // Scrolling moves the content of the scroll view, so the visible rows are found from
+            // the positions of the scroll view and its content
+            WindowEvent::GeometryChanged(_) => {
+                if self.scroll_view == Entity::null() || self.content == Entity::null() {
+                    return;
+                }
+
+                let scale = cx.style.dpi_factor as f32;
+                let top =
+                    (cx.cache.get_posy(self.scroll_view) - cx.cache.get_posy(self.content)) / scale;
+                let height = cx.cache.get_height(self.scroll_view) / scale;
+
+                if (top, height) != self.viewport {
+                    self.viewport = (top, height);
+                    self.update_slots();
+                }
+            }
+
+            WindowEvent::MouseDown(_) => {
+                if *cx.focused != cx.current()
+                    && !cx.focused.is_descendant_of(cx.tree, cx.current())
+                {
+                    cx.focus();
+                }
+            }
+
+            WindowEvent::KeyDown(code, _) => match code {
+                Code::ArrowDown => {
+                    if let Some(callback) = &self.increment_callback {
+                        (callback)(cx);
+                    }
+                }
+
+                Code::ArrowUp => {
+                    if let Some(callback) = &self.decrement_callback {
+                        (callback)(cx);
+                    }
+                }
+
+                Code::Escape => {
+                    if let Some(callback) = &self.clear_callback {
+                        (callback)(cx);
+                    }
+                }
+
+                Code::PageDown => {
+                    let (top, height) = self.viewport;
+                    self.scroll_to(cx, top + height);
+                }
+
+                Code::PageUp => {
+                    let (top, height) = self.viewport;
+                    self.scroll_to(cx, top - height);
+                }
+
+                Code::Home => {
+                    self.scroll_to(cx, 0.0);
+                }
+
+                Code::End => {
+                    self.scroll_to(cx, self.total_height());
+                }
+
+                _ => {}
+            },
+
+            _ => {}
+        });
+    }
+}
+
+impl<L: Lens<Target = Vec<T>>, T: Data> Handle<'_, VirtualList<L, T>> {
+    /// Sets the number of rows which are built above and below the visible area of the list.
+    pub fn overscan(self, rows: usize) -> Self {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<VirtualList<L, T>>())
+        {
+            list.overscan = rows;
+            list.update_slots();
+        }
+
+        self
+    }
+
+    pub fn on_increment<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<VirtualList<L, T>>())
+        {
+            list.increment_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    pub fn on_decrement<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<VirtualList<L, T>>())
+        {
+            list.decrement_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    pub fn on_clear<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        if let Some(list) =
+            self.cx.views.get_mut(&self.entity).and_then(|f| f.downcast_mut::<VirtualList<L, T>>())
+        {
+            list.clear_callback = Some(Box::new(callback));
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Lens)]
+    struct AppData {
+        items: Vec<u32>,
+    }
+
+    fn list<L: 'static + Lens<Target = Vec<u32>>>(
+        lens: L,
+        row_height: RowHeight,
+    ) -> VirtualList<L, u32> {
+        VirtualList::with_row_height(lens, row_height)
+    }
+
+    #[test]
+    fn fixed_row_positions() {
+        let mut list = list(AppData::items, RowHeight::Fixed(10.0));
+        list.set_len(100);
+
+        assert_eq!(list.row_top(0), 0.0);
+        assert_eq!(list.row_top(3), 30.0);
+        assert_eq!(list.total_height(), 1000.0);
+
+        assert_eq!(list.row_at(-5.0), 0);
+        assert_eq!(list.row_at(29.9), 2);
+        assert_eq!(list.row_at(30.0), 3);
+        assert_eq!(list.row_at(5000.0), 99);
+    }
+
+    #[test]
+    fn measured_row_positions() {
+        let mut list = list(AppData::items, RowHeight::Measured(10.0));
+        list.set_items(vec![0, 1, 2, 3], &[]);
+        assert_eq!(list.total_height(), 40.0);
+
+        list.heights[1] = 30.0;
+        list.update_offsets();
+
+        assert_eq!(list.row_top(1), 10.0);
+        assert_eq!(list.row_top(2), 40.0);
+        assert_eq!(list.total_height(), 60.0);
+
+        assert_eq!(list.row_at(9.9), 0);
+        assert_eq!(list.row_at(39.9), 1);
+        assert_eq!(list.row_at(40.0), 2);
+        assert_eq!(list.row_at(100.0), 3);
+    }
+
+    #[test]
+    fn measured_heights_move_with_their_items() {
+        let mut list = list(AppData::items, RowHeight::Measured(10.0));
+        list.set_items(vec![0, 1, 2, 3], &[]);
+        list.heights[2] = 50.0;
+
+        list.set_items(vec![7, 0, 1, 2, 3], &[]);
+        assert_eq!(list.heights, [10.0, 10.0, 10.0, 50.0, 10.0]);
+
+        list.set_items(vec![7, 2, 3], &[]);
+        assert_eq!(list.heights, [10.0, 50.0, 10.0]);
+        assert_eq!(list.total_height(), 70.0);
+
+        // Changed rows which are built keep the heights of their views
+        list.set_items(vec![7, 8, 9], &[(1, 20.0), (2, 25.0)]);
+        assert_eq!(list.heights, [10.0, 20.0, 25.0]);
+    }
+
+    #[test]
+    fn slots_cover_visible_rows_and_overscan() {
+        let mut list = list(AppData::items, RowHeight::Fixed(10.0));
+        list.overscan = 2;
+        list.viewport = (100.0, 50.0);
+        list.set_len(100);
+
+        let mut rows = list.slots.iter().map(|slot| slot.unwrap()).collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(rows, (8..18).collect::<Vec<_>>());
+
+        // Scrolling reassigns only the slot of the row which scrolled out of view
+        let before = list.slots.clone();
+        list.viewport = (110.0, 50.0);
+        list.update_slots();
+
+        let changed = (0..before.len()).filter(|slot| before[*slot] != list.slots[*slot]);
+        assert_eq!(changed.collect::<Vec<_>>().len(), 1);
+        assert!(list.slots.contains(&Some(18)));
+        assert!(!list.slots.contains(&Some(8)));
+    }
+
+    #[test]
+    fn slots_are_cleared_for_empty_list() {
+        let mut list = list(AppData::items, RowHeight::Fixed(10.0));
+        list.viewport = (0.0, 50.0);
+        list.set_len(10);
+        assert!(list.slots.iter().all(|slot| slot.is_some()));
+
+        list.set_len(0);
+        assert!(list.slots.iter().all(|slot| slot.is_none()));
+    }
+}
@@ -0,0 +1,223 @@
+use morphorm::PositionType;
+
+use crate::prelude::*;
+use crate::window::Position;
+
+/// A view which displays its contents in a separate top-level window.
+///
+/// The window is part of the same tree as the main window, so its contents can use the models
+/// and lenses of its ancestors. The windowing backend opens the window after it has been built
+/// and closes it again when the view is removed, for example by a [`Binding`] being rebuilt.
+///
+/// Only the winit backend supports opening additional windows.
+///
+/// # Example
+/// ```ignore
+/// Binding::new(cx, AppData::show_inspector, |cx, show| {
+///     if show.get(cx) {
+///         Window::new(cx, |cx| {
+///             Label::new(cx, "Inspector");
+///         })
+///         .title("Inspector")
+///         .inner_size((300, 400))
+///         .on_close(|cx| cx.emit(AppEvent::HideInspector));
+///     }
+/// });
+/// ```
+pub struct Window {
+    on_close: Option<Box<dyn Fn(&mut EventContext)>>,
+}
+
+impl Window {
+    pub fn new<F>(cx: &mut Context, content: F) -> Handle<Self>
+    where
+        F: FnOnce(&mut Context),
+    {
+        let description = WindowDescription::new();
+        let width = description.inner_size.width as f32;
+        let height = description.inner_size.height as f32;
+
+        let handle = Self { on_close: None }.build(cx, content);
+
+        handle.cx.windows.insert(handle.entity, description);
+
+        handle
+            .position_type(PositionType::SelfDirected)
+            .left(Pixels(0.0))
+            .top(Pixels(0.0))
+            .width(Pixels(width))
+            .height(Pixels(height))
+            .role(Role::Window)
+    }
+}
+
+impl Handle<'_, Window> {
+    /// Sets a callback which is called when the user asks to close the window.
+    ///
+    /// The window stays open until the view is removed. If no callback is set, the window is
+    /// hidden instead.
+    pub fn on_close<F>(self, callback: F) -> Self
+    where
+        F: 'static + Fn(&mut EventContext),
+    {
+        self.modify(|window| window.on_close = Some(Box::new(callback)))
+    }
+
+    fn description(&mut self) -> &mut WindowDescription {
+        self.cx.windows.get_mut(&self.entity).expect("Window has no description")
+    }
+}
+
+impl View for Window {
+    fn element(&self) -> Option<&'static str> {
+        Some("window")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::WindowClose => {
+                if let Some(callback) = &self.on_close {
+                    (callback)(cx);
+                } else {
+                    cx.emit_to(cx.current(), WindowEvent::SetVisible(false));
+                }
+
+                meta.consume();
+            }
+
+            _ => {}
+        });
+    }
+}
+
+impl WindowModifiers for Handle<'_, Window> {
+    fn title<T: ToString>(mut self, title: impl Res<T>) -> Self {
+        self.description().title = title.get_val(self.cx).to_string();
+        title.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetTitle(val.to_string()));
+        });
+
+        self
+    }
+
+    fn inner_size<S: Into<WindowSize>>(mut self, size: impl Res<S>) -> Self {
+        let inner_size = size.get_val(self.cx).into();
+        self.description().inner_size = inner_size;
+        self.cx.style().width.insert(self.entity, Pixels(inner_size.width as f32));
+        self.cx.style().height.insert(self.entity, Pixels(inner_size.height as f32));
+        size.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetSize(val.into()));
+        });
+
+        self
+    }
+
+    fn min_inner_size<S: Into<WindowSize>>(mut self, size: impl Res<Option<S>>) -> Self {
+        self.description().min_inner_size = size.get_val(self.cx).map(|size| size.into());
+        size.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetMinSize(val.map(|size| size.into())));
+        });
+
+        self
+    }
+
+    fn max_inner_size<S: Into<WindowSize>>(mut self, size: impl Res<Option<S>>) -> Self {
+        self.description().max_inner_size = size.get_val(self.cx).map(|size| size.into());
+        size.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetMaxSize(val.map(|size| size.into())));
+        });
+
+        self
+    }
+
+    fn position<P: Into<Position>>(mut self, position: impl Res<P>) -> Self {
+        self.description().position = Some(position.get_val(self.cx).into());
+        position.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetPosition(val.into()));
+        });
+
+        self
+    }
+
+    fn resizable(mut self, flag: impl Res<bool>) -> Self {
+        self.description().resizable = flag.get_val(self.cx);
+        flag.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetResizable(val));
+        });
+
+        self
+    }
+
+    fn minimized(mut self, flag: impl Res<bool>) -> Self {
+        self.description().minimized = flag.get_val(self.cx);
+        flag.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetMinimized(val));
+        });
+
+        self
+    }
+
+    fn maximized(mut self, flag: impl Res<bool>) -> Self {
+        self.description().maximized = flag.get_val(self.cx);
+        flag.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetMaximized(val));
+        });
+
+        self
+    }
+
+    fn visible(mut self, flag: impl Res<bool>) -> Self {
+        self.description().visible = flag.get_val(self.cx);
+        flag.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetVisible(val));
+        });
+
+        self
+    }
+
+    fn transparent(mut self, flag: bool) -> Self {
+        self.description().transparent = flag;
+
+        self
+    }
+
+    fn decorations(mut self, flag: impl Res<bool>) -> Self {
+        self.description().decorations = flag.get_val(self.cx);
+        flag.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetDecorations(val));
+        });
+
+        self
+    }
+
+    fn always_on_top(mut self, flag: impl Res<bool>) -> Self {
+        self.description().always_on_top = flag.get_val(self.cx);
+        flag.set_or_bind(self.cx, self.entity, |cx, entity, val| {
+            cx.emit_to(entity, WindowEvent::SetAlwaysOnTop(val));
+        });
+
+        self
+    }
+
+    fn vsync(mut self, flag: bool) -> Self {
+        self.description().vsync = flag;
+
+        self
+    }
+
+    fn icon(mut self, image: Vec<u8>, width: u32, height: u32) -> Self {
+        let description = self.description();
+        description.icon = Some(image);
+        description.icon_width = width;
+        description.icon_height = height;
+
+        self
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn canvas(mut self, canvas: &str) -> Self {
+        self.description().target_canvas = Some(canvas.to_owned());
+
+        self
+    }
+}
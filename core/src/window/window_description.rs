@@ -41,6 +41,7 @@ impl Position {
 /// Passed to the window to set initial window properties.
 ///
 /// This type is part of the prelude.
+#[derive(Clone)]
 pub struct WindowDescription {
     pub title: String,
     pub inner_size: WindowSize,
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use keyboard_types::{Code, Key};
 use morphorm::GeometryChanged;
 
@@ -32,6 +34,18 @@ pub enum WindowEvent {
     MouseEnter,
     /// Emitted when the mouse cursor leaves an entity or one of its descendants
     MouseLeave,
+    /// Emitted when a file is dragged over the window, with the path of the file. When several
+    /// files are dragged at once, one event is emitted for each of them.
+    ///
+    /// The file events of a drag are all sent to the view which was hovered when it entered the
+    /// window. They are only emitted by the winit backend, as baseview doesn't report files
+    /// dragged onto the window.
+    FileHovered(PathBuf),
+    /// Emitted when a file is dropped onto the window, with the path of the file. When several
+    /// files are dropped at once, one event is emitted for each of them.
+    FileDropped(PathBuf),
+    /// Emitted when files dragged over the window leave it or the drag is cancelled
+    FileHoverCancelled,
 
     FocusIn,
 
@@ -39,6 +53,13 @@ pub enum WindowEvent {
 
     /// Emitted when a character is typed
     CharInput(char),
+    /// Emitted while text is being composed with an input method (IME).
+    ///
+    /// Contains the text being composed and the byte range of the cursor within it, if any. An
+    /// empty string means the composition was cleared.
+    ImePreedit(String, Option<(usize, usize)>),
+    /// Emitted when an input method (IME) commits the composed text.
+    ImeCommit(String),
     /// Emitted when a keyboard key is pressed
     KeyDown(Code, Option<Key>),
     /// Emitted when a keyboard key is released
@@ -49,6 +70,12 @@ pub enum WindowEvent {
     GrabCursor(bool),
     /// Sets the (x,y) position of the mouse cursor in window coordinates
     SetCursorPosition(u32, u32),
+    /// Allows or disallows input methods (IME) in the window. While allowed, text composed with an
+    /// input method is sent as `ImePreedit` and `ImeCommit` events instead of `CharInput`.
+    SetImeAllowed(bool),
+    /// Sets the area of the text caret as (x, y, width, height) in logical window coordinates, so
+    /// that an input method can show its candidate window next to it
+    SetImeCursorArea(f32, f32, f32, f32),
 
     SetTitle(String),
     SetSize(WindowSize),
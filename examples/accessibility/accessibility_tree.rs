@@ -0,0 +1,73 @@
+use vizia::accessibility::{AccessTreeUpdate, AccessibilityAdapter};
+use vizia::prelude::*;
+
+// An adapter which prints the changes to the accessibility tree instead of forwarding them to
+// the platform.
+struct PrintAdapter;
+
+impl AccessibilityAdapter for PrintAdapter {
+    fn update(&mut self, update: AccessTreeUpdate) {
+        for node in update.nodes.iter() {
+            println!(
+                "{} {:?} name: {:?} value: {:?} {:?} checked: {:?}",
+                node.entity, node.role, node.name, node.value, node.numeric_value, node.checked
+            );
+        }
+
+        for entity in update.removed.iter() {
+            println!("{} removed", entity);
+        }
+
+        println!("Focus: {}", update.focus);
+    }
+}
+
+#[derive(Lens)]
+pub struct AppData {
+    flag: bool,
+    value: f32,
+    text: String,
+}
+
+pub enum AppEvent {
+    Toggle,
+    SetValue(f32),
+    SetText(String),
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Toggle => self.flag ^= true,
+            AppEvent::SetValue(value) => self.value = *value,
+            AppEvent::SetText(text) => self.text = text.clone(),
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        AppData { flag: false, value: 0.5, text: "Hello".to_string() }.build(cx);
+
+        VStack::new(cx, |cx| {
+            Button::new(cx, |cx| cx.emit(AppEvent::Toggle), |cx| Label::new(cx, "Toggle"));
+
+            Checkbox::new(cx, AppData::flag)
+                .on_toggle(|cx| cx.emit(AppEvent::Toggle))
+                .accessible_name("Flag");
+
+            Slider::new(cx, AppData::value)
+                .on_changing(|cx, value| cx.emit(AppEvent::SetValue(value)))
+                .accessible_name("Value");
+
+            Textbox::new(cx, AppData::text)
+                .on_edit(|cx, text| cx.emit(AppEvent::SetText(text)))
+                .accessible_name("Text")
+                .width(Pixels(200.0));
+        })
+        .row_between(Pixels(10.0))
+        .child_space(Pixels(10.0));
+    })
+    .accessibility_adapter(|_window, _proxy| Box::new(PrintAdapter))
+    .run();
+}
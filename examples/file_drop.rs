@@ -0,0 +1,70 @@
+//! This example shows the paths of files dragged onto the window from the operating system.
+//! Dropped files are only reported by the winit backend.
+
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+    .drop_zone {
+        border-width: 2px;
+        border-color: #c4c4c4;
+        border-radius: 5px;
+        child-space: 1s;
+    }
+
+    .drop_zone:checked {
+        border-color: #4c00ff;
+        background-color: #4c00ff22;
+    }
+"#;
+
+#[derive(Lens)]
+pub struct AppData {
+    hovering: bool,
+    files: Vec<String>,
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::FileHovered(_) => {
+                self.hovering = true;
+            }
+
+            WindowEvent::FileDropped(path) => {
+                self.hovering = false;
+                self.files.push(path.display().to_string());
+            }
+
+            WindowEvent::FileHoverCancelled => {
+                self.hovering = false;
+            }
+
+            _ => {}
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        AppData { hovering: false, files: Vec::new() }.build(cx);
+
+        VStack::new(cx, |cx| {
+            VStack::new(cx, |cx| {
+                Label::new(cx, "Drop files here");
+            })
+            .class("drop_zone")
+            .height(Pixels(100.0))
+            .checked(AppData::hovering);
+
+            List::new(cx, AppData::files, |cx, _, path| {
+                Label::new(cx, path);
+            });
+        })
+        .child_space(Pixels(20.0))
+        .row_between(Pixels(20.0));
+    })
+    .title("File Drop")
+    .run();
+}
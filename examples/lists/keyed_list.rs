@@ -0,0 +1,96 @@
+use vizia::prelude::*;
+
+#[derive(Debug, Clone, Lens, Data)]
+pub struct Item {
+    id: u32,
+    name: String,
+}
+
+#[derive(Lens)]
+pub struct AppData {
+    items: Vec<Item>,
+    next_id: u32,
+}
+
+#[derive(Debug)]
+pub enum AppEvent {
+    Insert,
+    Reverse,
+    Remove(u32),
+    Rename(u32, String),
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Insert => {
+                self.items
+                    .insert(0, Item { id: self.next_id, name: format!("Item {}", self.next_id) });
+                self.next_id += 1;
+            }
+
+            AppEvent::Reverse => {
+                self.items.reverse();
+            }
+
+            AppEvent::Remove(id) => {
+                self.items.retain(|item| item.id != *id);
+            }
+
+            AppEvent::Rename(id, name) => {
+                if let Some(item) = self.items.iter_mut().find(|item| item.id == *id) {
+                    item.name = name.clone();
+                }
+            }
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        let items = (0..5).map(|id| Item { id, name: format!("Item {}", id) }).collect();
+        AppData { items, next_id: 5 }.build(cx);
+
+        VStack::new(cx, |cx| {
+            HStack::new(cx, |cx| {
+                Button::new(
+                    cx,
+                    |cx| cx.emit(AppEvent::Insert),
+                    |cx| Label::new(cx, "Insert at top"),
+                );
+                Button::new(cx, |cx| cx.emit(AppEvent::Reverse), |cx| Label::new(cx, "Reverse"));
+            })
+            .col_between(Pixels(5.0))
+            .size(Auto);
+
+            // The rows keep their state, such as text being edited, when other rows are inserted,
+            // removed or moved
+            KeyedList::new(
+                cx,
+                AppData::items,
+                |item| item.id,
+                |cx, id, item| {
+                    HStack::new(cx, |cx| {
+                        Textbox::new(cx, item.then(Item::name))
+                            .on_submit(move |cx, text, _| cx.emit(AppEvent::Rename(id, text)))
+                            .width(Pixels(150.0));
+                        Button::new(
+                            cx,
+                            move |cx| cx.emit(AppEvent::Remove(id)),
+                            |cx| Label::new(cx, "Remove"),
+                        );
+                    })
+                    .col_between(Pixels(5.0))
+                    .size(Auto);
+                },
+            )
+            .row_between(Pixels(5.0));
+        })
+        .row_between(Pixels(10.0))
+        .size(Auto)
+        .space(Stretch(1.0))
+        .top(Pixels(100.0));
+    })
+    .title("Keyed List")
+    .run();
+}
@@ -1,13 +1,23 @@
 use vizia::prelude::*;
 
 const STYLE: &str = r#"
-    
+
     label {
         background-color: white;
     }
 
-    label:checked {
-        background-color: blue;
+    label:drop-target {
+        background-color: #c8c8ff;
+    }
+
+    vstack.bin {
+        border-width: 1px;
+        border-color: black;
+        child-space: 1s;
+    }
+
+    vstack.bin:drop-target {
+        background-color: #ffc8c8;
     }
 "#;
 
@@ -19,6 +29,8 @@ pub struct AppData {
 #[derive(Debug)]
 pub enum AppEvent {
     Sort,
+    Move(usize, usize),
+    Remove(usize),
 }
 
 impl Model for AppData {
@@ -27,6 +39,15 @@ impl Model for AppData {
             AppEvent::Sort => {
                 self.list.sort();
             }
+
+            AppEvent::Move(from, to) => {
+                let item = self.list.remove(*from);
+                self.list.insert(*to, item);
+            }
+
+            AppEvent::Remove(index) => {
+                self.list.remove(*index);
+            }
         });
     }
 }
@@ -37,23 +58,41 @@ fn main() {
 
         AppData { list: vec![12, 5, 65, 31, 18, 7] }.build(cx);
 
-        VStack::new(cx, |cx| {
-            Button::new(cx, |cx| cx.emit(AppEvent::Sort), |cx| Label::new(cx, "Sort"));
+        HStack::new(cx, |cx| {
+            VStack::new(cx, |cx| {
+                Button::new(cx, |cx| cx.emit(AppEvent::Sort), |cx| Label::new(cx, "Sort"));
+
+                // Rows can be dragged onto each other to reorder them
+                List::new(cx, AppData::list, move |cx, index, item| {
+                    Label::new(cx, item)
+                        .width(Pixels(100.0))
+                        .height(Pixels(30.0))
+                        .border_color(Color::black())
+                        .border_width(Pixels(1.0))
+                        .draggable(move |_| index)
+                        .drag_preview(move |cx| {
+                            Label::new(cx, item).width(Pixels(100.0)).height(Pixels(30.0));
+                        })
+                        .on_drop(move |cx, from: &usize| cx.emit(AppEvent::Move(*from, index)));
+                })
+                .row_between(Pixels(5.0));
+            })
+            .row_between(Pixels(5.0))
+            .size(Auto);
 
-            List::new(cx, AppData::list, move |cx, _, item| {
-                Label::new(cx, item)
-                    .width(Pixels(100.0))
-                    .height(Pixels(30.0))
-                    .border_color(Color::black())
-                    .border_width(Pixels(1.0));
+            // Dropping a row here removes it, except for the first row
+            VStack::new(cx, |cx| {
+                Label::new(cx, "Drop to remove").background_color(Color::transparent());
             })
-            .row_between(Pixels(5.0));
+            .class("bin")
+            .size(Pixels(150.0))
+            .accept_drop(|index: &usize| *index != 0)
+            .on_drop(|cx, index: &usize| cx.emit(AppEvent::Remove(*index)));
         })
-        .row_between(Pixels(5.0))
+        .col_between(Pixels(50.0))
         .size(Auto)
         .space(Stretch(1.0))
-        .top(Pixels(100.0))
-        .child_space(Stretch(1.0));
+        .top(Pixels(100.0));
     })
     .title("Sortable List")
     .run();
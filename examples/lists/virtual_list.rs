@@ -0,0 +1,85 @@
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+
+    label {
+        background-color: white;
+        child-left: 5px;
+    }
+
+    label.selected {
+        background-color: #c8c8ff;
+    }
+"#;
+
+#[derive(Lens)]
+pub struct AppData {
+    items: Vec<String>,
+    selected: usize,
+}
+
+#[derive(Debug)]
+pub enum AppEvent {
+    Select(usize),
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Select(index) => {
+                self.selected = *index;
+            }
+        });
+    }
+}
+
+// Selects a row and scrolls the list to it
+fn select(cx: &mut EventContext, index: usize) {
+    cx.emit(AppEvent::Select(index));
+    cx.emit(VirtualListEvent::ScrollTo(index));
+}
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        AppData { items: (0..50000).map(|i| format!("Entry {}", i)).collect(), selected: 0 }
+            .build(cx);
+
+        HStack::new(cx, |cx| {
+            // Rows with a fixed height, selectable with the arrow keys
+            VirtualList::new(cx, AppData::items, RowHeight::Fixed(30.0), |cx, index, item| {
+                Label::new(cx, item)
+                    .width(Stretch(1.0))
+                    .height(Pixels(30.0))
+                    .toggle_class(
+                        "selected",
+                        AppData::selected.map(move |selected| *selected == index),
+                    )
+                    .on_press(move |cx| cx.emit(AppEvent::Select(index)));
+            })
+            .on_increment(|cx| {
+                let last = AppData::items.map(|items| items.len()).get(cx).saturating_sub(1);
+                let selected = AppData::selected.get(cx);
+                select(cx, (selected + 1).min(last));
+            })
+            .on_decrement(|cx| {
+                let selected = AppData::selected.get(cx);
+                select(cx, selected.saturating_sub(1));
+            })
+            .width(Pixels(200.0));
+
+            // Rows with heights which are measured once they're built
+            VirtualList::new(cx, AppData::items, RowHeight::Measured(30.0), |cx, index, item| {
+                Label::new(cx, item)
+                    .width(Stretch(1.0))
+                    .height(Pixels(20.0 + (index % 5) as f32 * 10.0));
+            })
+            .width(Pixels(200.0));
+        })
+        .col_between(Pixels(20.0))
+        .space(Pixels(20.0));
+    })
+    .title("Virtual List")
+    .run();
+}
@@ -0,0 +1,73 @@
+use vizia::prelude::*;
+
+#[derive(Lens)]
+pub struct AppData {
+    show_window: bool,
+    count: i32,
+}
+
+pub enum AppEvent {
+    ShowWindow,
+    HideWindow,
+    Increment,
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::ShowWindow => {
+                self.show_window = true;
+            }
+
+            AppEvent::HideWindow => {
+                self.show_window = false;
+            }
+
+            AppEvent::Increment => {
+                self.count += 1;
+            }
+        });
+    }
+}
+
+#[cfg(feature = "baseview")]
+fn main() {
+    panic!("This example is not supported on baseview");
+}
+
+#[cfg(all(not(feature = "baseview")))]
+fn main() {
+    Application::new(|cx| {
+        AppData { show_window: false, count: 0 }.build(cx);
+
+        VStack::new(cx, |cx| {
+            Button::new(cx, |cx| cx.emit(AppEvent::ShowWindow), |cx| Label::new(cx, "Open Window"));
+            Label::new(cx, AppData::count);
+        })
+        .row_between(Pixels(20.0))
+        .child_space(Pixels(10.0));
+
+        Binding::new(cx, AppData::show_window, |cx, show_window| {
+            if show_window.get(cx) {
+                Window::new(cx, |cx| {
+                    VStack::new(cx, |cx| {
+                        Button::new(
+                            cx,
+                            |cx| cx.emit(AppEvent::Increment),
+                            |cx| Label::new(cx, "Increment"),
+                        );
+                        Label::new(cx, AppData::count);
+                    })
+                    .row_between(Pixels(20.0))
+                    .child_space(Pixels(10.0));
+                })
+                .title("Second Window")
+                .inner_size((300, 200))
+                .on_close(|cx| cx.emit(AppEvent::HideWindow));
+            }
+        });
+    })
+    .title("Multiple Windows")
+    .inner_size((400, 200))
+    .run();
+}
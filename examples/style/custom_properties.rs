@@ -0,0 +1,72 @@
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+    :root {
+        --background: #f5f5f5;
+        --foreground: #202020;
+        --accent: #3a86ff;
+    }
+
+    .dark {
+        --background: #202020;
+        --foreground: #f5f5f5;
+    }
+
+    .panel {
+        background-color: var(--background);
+        child-space: 20px;
+        row-between: 10px;
+    }
+
+    .panel label {
+        color: var(--foreground);
+    }
+
+    .swatch {
+        width: 100px;
+        height: 30px;
+        border-radius: 5px;
+        background-color: var(--accent, red);
+    }
+"#;
+
+#[derive(Lens)]
+pub struct AppData {
+    dark: bool,
+}
+
+pub enum AppEvent {
+    ToggleDark,
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::ToggleDark => self.dark ^= true,
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        AppData { dark: false }.build(cx);
+
+        VStack::new(cx, |cx| {
+            HStack::new(cx, |cx| {
+                Checkbox::new(cx, AppData::dark).on_toggle(|cx| cx.emit(AppEvent::ToggleDark));
+                Label::new(cx, "Dark theme");
+            })
+            .height(Auto)
+            .col_between(Pixels(5.0));
+
+            Element::new(cx).class("swatch");
+            Element::new(cx).class("swatch").variable("--accent", "#ff006e");
+        })
+        .class("panel")
+        .toggle_class("dark", AppData::dark);
+    })
+    .title("Custom Properties")
+    .run();
+}
@@ -0,0 +1,57 @@
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+    element {
+        width: 120px;
+        height: 120px;
+        border-radius: 10px;
+    }
+
+    .linear {
+        background-image: linear-gradient(45deg, #3a86ff, #ff006e);
+    }
+
+    .corner {
+        background-image: linear-gradient(to bottom right, white, #8338ec 70%, black);
+    }
+
+    .radial {
+        border-radius: 50%;
+        background-image: radial-gradient(circle at 35% 30%, white, #fb5607 40%, #3a0ca3);
+    }
+
+    .conic {
+        border-radius: 50%;
+        background-image: conic-gradient(from 90deg, red, yellow, lime, aqua, blue, fuchsia, red);
+    }
+
+    .stacked {
+        background-color: #202020;
+        background-image: radial-gradient(closest-side, #ffffff80, #ffffff00), linear-gradient(to right, #06d6a0, #118ab2);
+    }
+"#;
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        HStack::new(cx, |cx| {
+            Element::new(cx).class("linear");
+            Element::new(cx).class("corner");
+            Element::new(cx).class("radial");
+            Element::new(cx).class("conic");
+            Element::new(cx).class("stacked");
+            Element::new(cx).background_gradient(vec![RadialGradient::new(
+                RadialGradientSize::FarthestSide,
+            )
+            .add_stop(GradientStop::new(Units::Auto, Color::white()))
+            .add_stop(GradientStop::new(Units::Auto, Color::black()))
+            .into()]);
+        })
+        .child_space(Pixels(20.0))
+        .col_between(Pixels(20.0));
+    })
+    .title("Gradients")
+    .inner_size((900, 200))
+    .run();
+}
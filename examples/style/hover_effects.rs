@@ -0,0 +1,70 @@
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+    .card {
+        width: 160px;
+        height: 100px;
+        border-radius: 8px;
+        child-space: 1s;
+        scale: 1;
+        outer-shadow: 0px 2px 4px #00000040;
+        background-image: linear-gradient(to bottom, #ffffff, #e0e0e0);
+        transition: scale 200ms ease-out, outer-shadow 200ms ease-out, background-image 200ms;
+    }
+
+    .card:hover {
+        scale: 1.1;
+        outer-shadow: 0px 8px 16px #00000060;
+        background-image: linear-gradient(to bottom, #ffffff, #a0c4ff);
+        transition: scale 200ms ease-out, outer-shadow 200ms ease-out, background-image 200ms;
+    }
+
+    .pulse {
+        background-color: #3a86ff;
+        border-color: black;
+        outer-shadow: 0px 0px 0px #00000080;
+    }
+"#;
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        // Transitions
+        HStack::new(cx, |cx| {
+            for index in 0..3 {
+                Label::new(cx, &format!("Card {}", index + 1)).class("card");
+            }
+        })
+        .height(Auto)
+        .child_space(Pixels(40.0))
+        .col_between(Pixels(40.0));
+
+        // Animation
+        let animation = cx
+            .add_animation(std::time::Duration::from_secs(1))
+            .add_keyframe(0.0, |keyframe| {
+                keyframe
+                    .with_timing_function(TimingFunction::spring(120.0, 6.0))
+                    .set_scale((1.0, 1.0))
+                    .set_border_width(Pixels(0.0))
+                    .set_outer_shadow_blur(Pixels(0.0))
+            })
+            .add_keyframe(1.0, |keyframe| {
+                keyframe
+                    .set_scale((0.8, 0.8))
+                    .set_border_width(Pixels(8.0))
+                    .set_outer_shadow_blur(Pixels(24.0))
+            })
+            .build();
+
+        Element::new(cx)
+            .size(Pixels(100.0))
+            .left(Pixels(40.0))
+            .class("pulse")
+            .on_press(move |cx| cx.play_animation(animation));
+    })
+    .title("Hover Effects")
+    .inner_size((700, 400))
+    .run();
+}
@@ -0,0 +1,50 @@
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+    .content {
+        layout-type: row;
+        child-space: 10px;
+        col-between: 10px;
+        row-between: 10px;
+    }
+
+    .card {
+        width: 1s;
+        height: 100px;
+        border-radius: 5px;
+        background-color: #3a86ff;
+    }
+
+    @media (max-width: 500px) {
+        .content {
+            layout-type: column;
+        }
+
+        .card {
+            height: 50px;
+        }
+    }
+
+    @media (min-resolution: 2dppx) {
+        .card {
+            border-width: 1px;
+            border-color: #202020;
+        }
+    }
+"#;
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        HStack::new(cx, |cx| {
+            Element::new(cx).class("card");
+            Element::new(cx).class("card");
+            Element::new(cx).class("card");
+        })
+        .class("content");
+    })
+    .title("Media Queries")
+    .inner_size((800, 400))
+    .run();
+}
@@ -0,0 +1,58 @@
+use vizia::prelude::*;
+
+const STYLE: &str = r#"
+    .row {
+        height: 30px;
+        child-left: 10px;
+        child-top: 1s;
+        child-bottom: 1s;
+        background-color: #f0f0f0;
+    }
+
+    .row:nth-child(even) {
+        background-color: #d8d8d8;
+    }
+
+    .row:first-child {
+        border-top-left-radius: 5px;
+        border-top-right-radius: 5px;
+    }
+
+    .row:last-child {
+        border-bottom-left-radius: 5px;
+        border-bottom-right-radius: 5px;
+    }
+
+    .row + .row {
+        border-width: 1px;
+        border-color: #c0c0c0;
+    }
+
+    .row:not(:first-child):hover {
+        background-color: #b0c4de;
+    }
+"#;
+
+#[derive(Lens)]
+pub struct AppData {
+    items: Vec<String>,
+}
+
+impl Model for AppData {}
+
+fn main() {
+    Application::new(|cx| {
+        cx.add_theme(STYLE);
+
+        AppData { items: (1..=8).map(|i| format!("Item {}", i)).collect() }.build(cx);
+
+        List::new(cx, AppData::items, |cx, _, item| {
+            Label::new(cx, item).class("row").width(Stretch(1.0));
+        })
+        .width(Pixels(200.0))
+        .height(Auto)
+        .space(Pixels(20.0));
+    })
+    .title("Structural Selectors")
+    .run();
+}
@@ -0,0 +1,71 @@
+use vizia::prelude::*;
+
+const COLORS: [&str; 12] = [
+    "Red", "Orange", "Yellow", "Green", "Blue", "Indigo", "Violet", "Black", "White", "Grey",
+    "Brown", "Pink",
+];
+const THINGS: [&str; 20] = [
+    "Apple", "Bicycle", "Cloud", "Door", "Engine", "Feather", "Guitar", "House", "Island",
+    "Jacket", "Kite", "Lamp", "Mountain", "Notebook", "Ocean", "Pencil", "River", "Stone", "Tree",
+    "Umbrella",
+];
+
+#[derive(Lens)]
+pub struct AppData {
+    options: Vec<String>,
+    choice: String,
+    custom: String,
+}
+
+pub enum AppEvent {
+    Choose(usize),
+    SetCustom(String),
+    ChooseCustom(usize),
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Choose(index) => {
+                self.choice = self.options[*index].clone();
+            }
+
+            AppEvent::SetCustom(text) => {
+                self.custom = text.clone();
+            }
+
+            AppEvent::ChooseCustom(index) => {
+                self.custom = self.options[*index].clone();
+            }
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        // Enough options that finding one by scrolling would be tedious
+        let options = COLORS
+            .iter()
+            .flat_map(|color| THINGS.iter().map(move |thing| format!("{} {}", color, thing)))
+            .collect::<Vec<_>>();
+
+        AppData { choice: options[0].clone(), custom: String::new(), options }.build(cx);
+
+        VStack::new(cx, |cx| {
+            Label::new(cx, "Choose an option");
+            Combobox::new(cx, AppData::options, AppData::choice)
+                .on_select(|cx, index| cx.emit(AppEvent::Choose(index)))
+                .width(Pixels(250.0));
+
+            Label::new(cx, "Choose an option or type your own");
+            Combobox::new(cx, AppData::options, AppData::custom)
+                .on_select(|cx, index| cx.emit(AppEvent::ChooseCustom(index)))
+                .on_free_text(|cx, text| cx.emit(AppEvent::SetCustom(text)))
+                .width(Pixels(250.0));
+        })
+        .row_between(Pixels(10.0))
+        .child_space(Pixels(20.0));
+    })
+    .title("Combobox")
+    .run();
+}
@@ -17,8 +17,14 @@ fn main() {
                     cx,
                     |cx| Label::new(cx, "menu 1"),
                     |cx| {
-                        MenuButton::new_simple(cx, "option 1", |_| {});
-                        MenuButton::new_simple(cx, "option 2 looooooooooooong", |_| {});
+                        MenuButton::new_simple(cx, "option 1", |_| println!("option 1"))
+                            .mnemonic(Code::Digit1)
+                            .accelerator(KeyChord::new(Modifiers::CTRL, Code::KeyO));
+                        MenuButton::new_simple(cx, "option 2 looooooooooooong", |_| {
+                            println!("option 2")
+                        })
+                        .mnemonic(Code::Digit2)
+                        .accelerator(KeyChord::new(Modifiers::CTRL | Modifiers::SHIFT, Code::KeyO));
                         Menu::new(
                             cx,
                             |cx| Label::new(cx, "menu 1a"),
@@ -39,7 +45,8 @@ fn main() {
                             },
                         );
                     },
-                );
+                )
+                .mnemonic(Code::KeyM);
                 Menu::new(
                     cx,
                     |cx| Label::new(cx, "menu 2"),
@@ -66,7 +73,8 @@ fn main() {
                             },
                         );
                     },
-                );
+                )
+                .mnemonic(Code::KeyN);
             });
         });
     })
@@ -0,0 +1,59 @@
+use vizia::prelude::*;
+
+#[derive(Lens)]
+pub struct AppData {
+    sidebar: f32,
+    editor: f32,
+}
+
+pub enum AppEvent {
+    SetSidebar(f32),
+    SetEditor(f32),
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::SetSidebar(ratio) => self.sidebar = *ratio,
+            AppEvent::SetEditor(ratio) => self.editor = *ratio,
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        AppData { sidebar: 0.25, editor: 0.7 }.build(cx);
+
+        SplitView::new(
+            cx,
+            AppData::sidebar,
+            Orientation::Horizontal,
+            |cx| {
+                Label::new(cx, "Sidebar").space(Pixels(10.0));
+            },
+            |cx| {
+                // A vertical split nested in the second pane
+                SplitView::new(
+                    cx,
+                    AppData::editor,
+                    Orientation::Vertical,
+                    |cx| {
+                        Label::new(cx, "Editor").space(Pixels(10.0));
+                    },
+                    |cx| {
+                        Label::new(cx, "Console").space(Pixels(10.0));
+                    },
+                )
+                .min_pane_size(50.0)
+                .default_ratio(0.7)
+                .on_changing(|cx, ratio| cx.emit(AppEvent::SetEditor(ratio)));
+            },
+        )
+        .min_pane_size(100.0)
+        .max_pane_size(400.0)
+        .default_ratio(0.25)
+        .on_changing(|cx, ratio| cx.emit(AppEvent::SetSidebar(ratio)));
+    })
+    .title("Split View")
+    .run();
+}
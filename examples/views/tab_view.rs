@@ -0,0 +1,85 @@
+use vizia::prelude::*;
+
+#[derive(Debug, Clone, Lens, Data)]
+pub struct Document {
+    name: String,
+    text: String,
+}
+
+#[derive(Lens)]
+pub struct AppData {
+    documents: Vec<Document>,
+    selected: usize,
+    created: usize,
+}
+
+pub enum AppEvent {
+    Select(usize),
+    Close(usize),
+    New,
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Select(index) => {
+                self.selected = *index;
+            }
+
+            AppEvent::Close(index) => {
+                self.documents.remove(*index);
+                // Keep the selection on the same document, or the one before the closed one
+                if *index < self.selected || self.selected == self.documents.len() {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+            }
+
+            AppEvent::New => {
+                self.created += 1;
+                self.documents.push(Document {
+                    name: format!("Document {}", self.created),
+                    text: String::new(),
+                });
+                self.selected = self.documents.len() - 1;
+            }
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        AppData {
+            documents: (1..=3)
+                .map(|i| Document {
+                    name: format!("Document {}", i),
+                    text: format!("The text of document {}", i),
+                })
+                .collect(),
+            selected: 0,
+            created: 3,
+        }
+        .build(cx);
+
+        VStack::new(cx, |cx| {
+            Button::new(cx, |cx| cx.emit(AppEvent::New), |cx| Label::new(cx, "New Tab"));
+
+            TabView::new(
+                cx,
+                AppData::documents,
+                AppData::selected,
+                |cx, _, document| {
+                    Label::new(cx, document.then(Document::name));
+                },
+                |cx, _, document| {
+                    Label::new(cx, document.then(Document::text)).space(Pixels(10.0));
+                },
+            )
+            .on_select(|cx, index| cx.emit(AppEvent::Select(index)))
+            .on_close(|cx, index| cx.emit(AppEvent::Close(index)));
+        })
+        .row_between(Pixels(10.0))
+        .child_space(Pixels(10.0));
+    })
+    .title("Tab View")
+    .run();
+}
@@ -0,0 +1,25 @@
+use vizia::prelude::*;
+
+fn main() {
+    Application::new(|cx| {
+        cx.set_tooltip_delay(std::time::Duration::from_millis(300));
+
+        VStack::new(cx, |cx| {
+            Button::new(cx, |_| {}, |cx| Label::new(cx, "Hover me"))
+                .tooltip("A tooltip with some text");
+
+            Button::new(cx, |_| {}, |cx| Label::new(cx, "Rich tooltip")).tooltip_content(|cx| {
+                VStack::new(cx, |cx| {
+                    Label::new(cx, "Rich tooltip").class("h1");
+                    Label::new(cx, "Tooltips can contain any views.");
+                })
+                .size(Auto)
+                .row_between(Pixels(4.0));
+            });
+        })
+        .child_space(Stretch(1.0))
+        .row_between(Pixels(20.0));
+    })
+    .title("Tooltip")
+    .run();
+}
@@ -0,0 +1,89 @@
+use vizia::prelude::*;
+
+#[derive(Debug, Clone, Lens, Data)]
+pub struct Folder {
+    name: String,
+    children: Vec<Folder>,
+}
+
+impl Folder {
+    fn new(name: &str, children: Vec<Folder>) -> Self {
+        Self { name: name.to_owned(), children }
+    }
+}
+
+#[derive(Lens)]
+pub struct AppData {
+    folders: Vec<Folder>,
+    selected: String,
+}
+
+pub enum AppEvent {
+    Select(Vec<usize>),
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Select(path) => {
+                // Find the name of the selected folder from its path
+                let mut folders = &self.folders;
+                let mut names = Vec::new();
+                for index in path {
+                    if let Some(folder) = folders.get(*index) {
+                        names.push(folder.name.as_str());
+                        folders = &folder.children;
+                    }
+                }
+
+                self.selected = names.join("/");
+            }
+        });
+    }
+}
+
+fn main() {
+    Application::new(|cx| {
+        AppData {
+            folders: vec![
+                Folder::new(
+                    "Presets",
+                    vec![
+                        Folder::new(
+                            "Bass",
+                            vec![Folder::new("Sub", vec![]), Folder::new("Reese", vec![])],
+                        ),
+                        Folder::new("Leads", vec![Folder::new("Saw", vec![])]),
+                        Folder::new("Pads", vec![]),
+                    ],
+                ),
+                Folder::new(
+                    "Projects",
+                    vec![Folder::new("Demo", vec![Folder::new("Stems", vec![])])],
+                ),
+            ],
+            selected: String::new(),
+        }
+        .build(cx);
+
+        VStack::new(cx, |cx| {
+            TreeView::new(
+                cx,
+                AppData::folders,
+                |folder: &Folder| &folder.children,
+                |cx, _, folder| {
+                    Label::new(cx, folder.then(Folder::name));
+                },
+            )
+            .expand(&[0])
+            .on_select(|cx, path| cx.emit(AppEvent::Select(path.to_vec())))
+            .width(Pixels(300.0));
+
+            Label::new(cx, AppData::selected);
+        })
+        .row_between(Pixels(20.0))
+        .space(Pixels(20.0));
+    })
+    .title("Tree View")
+    .run();
+}
@@ -0,0 +1,453 @@
+use keyboard_types::{Code, Key};
+use vizia_core::cache::BoundingBox;
+use vizia_core::events::EventManager;
+use vizia_core::fonts;
+use vizia_core::prelude::*;
+
+#[cfg(feature = "snapshot")]
+use crate::snapshot::{self, OffscreenSurface, SnapshotError};
+#[cfg(feature = "snapshot")]
+use std::path::Path;
+
+static DEFAULT_THEME: &str = include_str!("../../core/resources/themes/default_theme.css");
+static DEFAULT_LAYOUT: &str = include_str!("../../core/resources/themes/default_layout.css");
+
+// The maximum number of update cycles run by `ApplicationRunner::update` before giving up on the
+// event queue becoming empty, e.g. because of a running animation or an event handler which
+// re-emits events every frame.
+const MAX_UPDATE_CYCLES: usize = 32;
+
+/// Builds a [`Context`] without a window or a rendering context.
+///
+/// This is mostly useful for testing views. Once built, the returned [`ApplicationRunner`] can be
+/// used to feed synthetic [`WindowEvent`]s to the application and to inspect the resulting layout,
+/// style and model state.
+///
+/// # Example
+///
+/// ```
+/// # use vizia_core::prelude::*;
+/// # use vizia_headless::Application;
+/// #
+/// let mut app = Application::new(|cx| {
+///     Label::new(cx, "Hello Vizia").id("label");
+/// })
+/// .inner_size((400, 300))
+/// .build();
+///
+/// let label = app.entity_by_id("label").unwrap();
+/// assert!(app.context().cache().get_width(label) > 0.0);
+/// ```
+pub struct Application {
+    context: Context,
+    builder: Option<Box<dyn FnOnce(&mut Context)>>,
+    window_description: WindowDescription,
+    scale_factor: f64,
+    #[cfg(feature = "snapshot")]
+    offscreen_rendering: bool,
+}
+
+impl Application {
+    pub fn new<F>(content: F) -> Self
+    where
+        F: 'static + FnOnce(&mut Context),
+    {
+        let mut context = Context::new();
+
+        context.set_current(Entity::root());
+
+        Self {
+            context,
+            builder: Some(Box::new(content)),
+            window_description: WindowDescription::new(),
+            scale_factor: 1.0,
+            #[cfg(feature = "snapshot")]
+            offscreen_rendering: false,
+        }
+    }
+
+    /// Sets the logical size of the simulated window.
+    pub fn inner_size(mut self, size: impl Into<WindowSize>) -> Self {
+        self.window_description.inner_size = size.into();
+
+        self
+    }
+
+    /// Sets the scale factor of the simulated window.
+    pub fn scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+
+        self
+    }
+
+    pub fn ignore_default_theme(mut self) -> Self {
+        self.context.ignore_default_theme = true;
+
+        self
+    }
+
+    /// Renders the application into an offscreen buffer so that snapshots can be taken with
+    /// [`ApplicationRunner::snapshot`].
+    #[cfg(feature = "snapshot")]
+    pub fn offscreen_rendering(mut self) -> Self {
+        self.offscreen_rendering = true;
+
+        self
+    }
+
+    /// Sets the background color of the simulated window.
+    pub fn background_color(mut self, color: Color) -> Self {
+        self.context.style().background_color.insert(Entity::root(), color);
+
+        self
+    }
+
+    /// Builds the application and runs a first update so that styling and layout have been
+    /// computed before any events are sent.
+    pub fn build(mut self) -> ApplicationRunner {
+        let mut context = self.context;
+
+        // The canvas needs to exist before the fonts are synchronized so that they get loaded
+        // into it as well as the text context.
+        #[cfg(feature = "snapshot")]
+        let surface = if self.offscreen_rendering {
+            let size = self.window_description.inner_size;
+            Some(OffscreenSurface::new(
+                &mut context,
+                (size.width as f64 * self.scale_factor).round() as u32,
+                (size.height as f64 * self.scale_factor).round() as u32,
+            ))
+        } else {
+            None
+        };
+
+        context.add_font_mem("roboto", fonts::ROBOTO_REGULAR);
+        context.add_font_mem("roboto-bold", fonts::ROBOTO_BOLD);
+        context.add_font_mem("icons", fonts::ENTYPO);
+        context.add_font_mem("emoji", fonts::OPEN_SANS_EMOJI);
+        context.add_font_mem("arabic", fonts::AMIRI_REGULAR);
+        context.add_font_mem("material", fonts::MATERIAL_ICONS_REGULAR);
+
+        context.style().default_font = "roboto".to_string();
+
+        context.synchronize_fonts();
+
+        context.style().pseudo_classes.insert(Entity::root(), PseudoClass::default()).unwrap();
+        context.style().disabled.insert(Entity::root(), false);
+        context.cache().set_opacity(Entity::root(), 1.0);
+
+        context.add_theme(DEFAULT_LAYOUT);
+
+        if !context.ignore_default_theme {
+            context.add_theme(DEFAULT_THEME);
+        }
+
+        let mut runner = ApplicationRunner {
+            context,
+            event_manager: EventManager::new(),
+            scale_factor: self.scale_factor,
+            #[cfg(feature = "snapshot")]
+            surface,
+        };
+
+        runner.set_size(self.window_description.inner_size);
+
+        if let Some(builder) = self.builder.take() {
+            (builder)(&mut runner.context);
+        }
+
+        runner.update();
+
+        runner
+    }
+}
+
+/// Drives a headless application.
+///
+/// Events sent through the runner are dispatched in the same way as events coming from a
+/// windowing backend, after which the data, style and visual updates are run.
+pub struct ApplicationRunner {
+    context: Context,
+    event_manager: EventManager,
+    scale_factor: f64,
+    #[cfg(feature = "snapshot")]
+    surface: Option<OffscreenSurface>,
+}
+
+impl ApplicationRunner {
+    /// The context of the application, used to inspect the tree, style and cache.
+    pub fn context(&mut self) -> &mut Context {
+        &mut self.context
+    }
+
+    /// Returns the model or view data of type `T` which is visible from `entity`.
+    pub fn data<T: 'static>(&mut self, entity: Entity) -> Option<&T> {
+        self.context.set_current(entity);
+        self.context.data::<T>()
+    }
+
+    /// Returns the first entity in the tree with the given id.
+    pub fn entity_by_id(&self, id: &str) -> Option<Entity> {
+        let tree = self.context.tree_ref();
+        tree.into_iter().find(|entity| {
+            self.context.style_ref().ids.get(*entity).map_or(false, |entity_id| entity_id == id)
+        })
+    }
+
+    /// Runs update cycles until there are no more queued events.
+    ///
+    /// Each cycle flushes the event queue and runs the data, style and visual updates, in the same
+    /// order as the windowing backends.
+    pub fn update(&mut self) {
+        for _ in 0..MAX_UPDATE_CYCLES {
+            self.update_cycle();
+
+            if !self.context.has_queued_events() {
+                break;
+            }
+        }
+    }
+
+    fn update_cycle(&mut self) {
+        self.context.synchronize_fonts();
+
+        while self.event_manager.flush_events(&mut self.context) {}
+
+        self.context.process_data_updates();
+        self.context.process_style_updates();
+
+        // Windows other than the main window have no graphics context to release their canvas in
+        self.context.take_closed_windows();
+
+        if self.context.has_animations() {
+            self.context.apply_animations();
+        }
+
+        self.context.process_visual_updates();
+    }
+
+    /// Dispatches a window event as if it came from a windowing backend and updates the
+    /// application.
+    pub fn send_event(&mut self, event: WindowEvent) {
+        self.context.dispatch_system_event(event);
+        self.update();
+    }
+
+    /// Moves the mouse cursor to the given physical position.
+    pub fn mouse_move(&mut self, x: f32, y: f32) {
+        self.send_event(WindowEvent::MouseMove(x, y));
+    }
+
+    pub fn mouse_down(&mut self, button: MouseButton) {
+        self.send_event(WindowEvent::MouseDown(button));
+    }
+
+    pub fn mouse_up(&mut self, button: MouseButton) {
+        self.send_event(WindowEvent::MouseUp(button));
+    }
+
+    pub fn mouse_scroll(&mut self, x: f32, y: f32) {
+        self.send_event(WindowEvent::MouseScroll(x, y));
+    }
+
+    /// Moves the mouse cursor to the given physical position and clicks the left mouse button.
+    pub fn click(&mut self, x: f32, y: f32) {
+        self.mouse_move(x, y);
+        self.mouse_down(MouseButton::Left);
+        self.mouse_up(MouseButton::Left);
+    }
+
+    /// Clicks the left mouse button over the center of an entity.
+    pub fn click_entity(&mut self, entity: Entity) {
+        let (x, y) = self.context.cache().get_bounds(entity).center();
+        self.click(x, y);
+    }
+
+    /// Sets the state of the keyboard modifiers.
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        *self.context.modifiers_mut() = modifiers;
+    }
+
+    pub fn key_down(&mut self, code: Code, key: Key) {
+        self.send_event(WindowEvent::KeyDown(code, Some(key)));
+    }
+
+    pub fn key_up(&mut self, code: Code, key: Key) {
+        self.send_event(WindowEvent::KeyUp(code, Some(key)));
+    }
+
+    /// Presses and releases a key.
+    pub fn key_press(&mut self, code: Code, key: Key) {
+        self.key_down(code, key.clone());
+        self.key_up(code, key);
+    }
+
+    /// Sends a character input event for each character of the given text.
+    pub fn type_text(&mut self, text: &str) {
+        for chr in text.chars() {
+            self.send_event(WindowEvent::CharInput(chr));
+        }
+    }
+
+    /// Resizes the simulated window to the given logical size.
+    pub fn resize(&mut self, size: impl Into<WindowSize>) {
+        let size = size.into();
+        self.set_size(size);
+
+        #[cfg(feature = "snapshot")]
+        if let Some(surface) = &mut self.surface {
+            surface.resize(
+                &mut self.context,
+                (size.width as f64 * self.scale_factor).round() as u32,
+                (size.height as f64 * self.scale_factor).round() as u32,
+            );
+        }
+
+        let physical_width = self.context.cache().get_width(Entity::root());
+        let physical_height = self.context.cache().get_height(Entity::root());
+        self.context.emit_custom(
+            Event::new(WindowEvent::WindowResize(physical_width, physical_height))
+                .target(Entity::root()),
+        );
+
+        self.update();
+    }
+
+    /// Draws the application and returns the rendered image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the application was not built with [`Application::offscreen_rendering`].
+    #[cfg(feature = "snapshot")]
+    pub fn snapshot(&mut self) -> image::RgbaImage {
+        assert!(self.surface.is_some(), "Snapshots require offscreen rendering to be enabled");
+
+        snapshot::render(&mut self.context)
+    }
+
+    /// Draws the application and compares the result against a golden PNG file.
+    ///
+    /// Channels may differ by up to `tolerance` to allow for small rasterization differences. A
+    /// missing golden file is an error, unless the `VIZIA_UPDATE_SNAPSHOTS` environment variable
+    /// is set, in which case the golden file is written from the rendered image instead.
+    #[cfg(feature = "snapshot")]
+    pub fn compare_snapshot(
+        &mut self,
+        golden: impl AsRef<Path>,
+        tolerance: u8,
+    ) -> Result<(), SnapshotError> {
+        let actual = self.snapshot();
+        let update = std::env::var_os(snapshot::UPDATE_SNAPSHOTS_VAR).is_some();
+        snapshot::compare(&actual, golden.as_ref(), tolerance, update)
+    }
+
+    /// Like [`compare_snapshot`](Self::compare_snapshot) with a tolerance of zero, but panics on a
+    /// mismatch.
+    #[cfg(feature = "snapshot")]
+    pub fn assert_snapshot(&mut self, golden: impl AsRef<Path>) {
+        if let Err(err) = self.compare_snapshot(golden, 0) {
+            panic!("{}", err);
+        }
+    }
+
+    fn set_size(&mut self, size: WindowSize) {
+        let physical_width = (size.width as f64 * self.scale_factor).round() as f32;
+        let physical_height = (size.height as f64 * self.scale_factor).round() as f32;
+
+        self.context.style().dpi_factor = self.scale_factor;
+
+        self.context.style().width.insert(Entity::root(), Units::Pixels(size.width as f32));
+        self.context.style().height.insert(Entity::root(), Units::Pixels(size.height as f32));
+
+        self.context.cache().set_width(Entity::root(), physical_width);
+        self.context.cache().set_height(Entity::root(), physical_height);
+
+        let mut bounding_box = BoundingBox::default();
+        bounding_box.w = physical_width;
+        bounding_box.h = physical_height;
+
+        self.context.cache().set_clip_region(Entity::root(), bounding_box);
+
+        self.context.need_restyle();
+        self.context.need_relayout();
+        self.context.need_redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Lens)]
+    struct AppData {
+        checked: bool,
+    }
+
+    enum AppEvent {
+        Toggle,
+    }
+
+    impl Model for AppData {
+        fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+            event.map(|app_event, _| match app_event {
+                AppEvent::Toggle => self.checked ^= true,
+            });
+        }
+    }
+
+    #[test]
+    fn click_toggles_checkbox() {
+        let mut app = Application::new(|cx| {
+            AppData { checked: false }.build(cx);
+
+            Checkbox::new(cx, AppData::checked)
+                .on_toggle(|cx| cx.emit(AppEvent::Toggle))
+                .id("checkbox");
+        })
+        .build();
+
+        let checkbox = app.entity_by_id("checkbox").unwrap();
+        assert!(!app.context().has_pseudo_class(checkbox, PseudoClass::CHECKED));
+
+        app.click_entity(checkbox);
+
+        assert!(app.data::<AppData>(checkbox).unwrap().checked);
+        assert!(app.context().has_pseudo_class(checkbox, PseudoClass::CHECKED));
+    }
+
+    #[test]
+    fn resize_relayouts_root_children() {
+        let mut app = Application::new(|cx| {
+            Element::new(cx).width(Stretch(1.0)).height(Pixels(20.0)).id("element");
+        })
+        .inner_size((200, 100))
+        .build();
+
+        let element = app.entity_by_id("element").unwrap();
+        assert_eq!(app.context().cache().get_width(element), 200.0);
+
+        app.resize((300, 100));
+
+        assert_eq!(app.context().cache().get_width(element), 300.0);
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[test]
+    fn snapshot_follows_resize() {
+        let mut app = Application::new(|cx| {
+            Element::new(cx).background_color(Color::red());
+        })
+        .inner_size((100, 50))
+        .offscreen_rendering()
+        .build();
+
+        assert_eq!(app.snapshot().dimensions(), (100, 50));
+
+        app.resize((150, 80));
+
+        let snapshot = app.snapshot();
+        assert_eq!(snapshot.dimensions(), (150, 80));
+        // The element stretches to fill the window, so the newly exposed corner is painted too
+        assert_eq!(snapshot.get_pixel(149, 79).0, [255, 0, 0, 255]);
+    }
+}
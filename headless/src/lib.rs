@@ -0,0 +1,48 @@
+//! A headless backend for vizia.
+//!
+//! This backend builds a [`Context`](vizia_core::prelude::Context) without creating a window or a
+//! rendering context, making it possible to test views by feeding them synthetic
+//! [`WindowEvent`](vizia_core::prelude::WindowEvent)s and inspecting the resulting layout, style
+//! and model state.
+//!
+//! With the `snapshot` feature enabled, the application can also be rendered offscreen into an
+//! image and compared against golden files.
+//!
+//! Offscreen rendering uses OSMesa through glutin's Unix platform support, so the `snapshot`
+//! feature is only available on Linux and the BSDs.
+
+// OSMesa contexts are only created by glutin on the same platforms as its `platform::unix` module
+#[cfg(all(
+    feature = "snapshot",
+    not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))
+))]
+compile_error!(
+    "The `snapshot` feature of vizia_headless renders through OSMesa and is only available on \
+     Linux and the BSDs"
+);
+
+mod application;
+#[cfg(all(
+    feature = "snapshot",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    )
+))]
+mod snapshot;
+
+pub use application::{Application, ApplicationRunner};
+#[cfg(feature = "snapshot")]
+pub use snapshot::{SnapshotError, UPDATE_SNAPSHOTS_VAR};
+
+#[cfg(feature = "snapshot")]
+pub use image;
@@ -0,0 +1,285 @@
+//! Offscreen rendering of a headless application into an RGBA image.
+//!
+//! Rendering uses the regular femtovg OpenGL renderer on top of an OSMesa context, which
+//! rasterizes on the CPU. This means snapshots can be taken on machines without a GPU, provided
+//! the OSMesa library (`libOSMesa`) is installed.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use femtovg::{renderer::OpenGl, Canvas};
+use glutin::dpi::PhysicalSize;
+use glutin::platform::unix::HeadlessContextExt;
+use glutin::{Api, ContextBuilder, GlProfile, GlRequest, PossiblyCurrent};
+use image::RgbaImage;
+use vizia_core::prelude::*;
+
+/// Setting this environment variable causes snapshot comparisons to write the golden files
+/// instead of comparing against them. Without it, a missing golden file is an error.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "VIZIA_UPDATE_SNAPSHOTS";
+
+/// An offscreen OpenGL context backed by the OSMesa software rasterizer.
+pub(crate) struct OffscreenSurface {
+    // Kept alive for as long as the canvas in the context is used
+    _context: glutin::Context<PossiblyCurrent>,
+}
+
+impl OffscreenSurface {
+    /// Creates the offscreen context and inserts a canvas for it into the context.
+    pub(crate) fn new(cx: &mut Context, width: u32, height: u32) -> Self {
+        let context = ContextBuilder::new()
+            .with_gl(GlRequest::Specific(Api::OpenGl, (3, 3)))
+            .with_gl_profile(GlProfile::Core)
+            .build_osmesa(PhysicalSize::new(width, height))
+            .expect("Failed to create OSMesa context, is libOSMesa installed?");
+
+        let context = unsafe { context.make_current().expect("Failed to make context current") };
+
+        let renderer = unsafe {
+            OpenGl::new_from_function(|s| context.get_proc_address(s) as *const _)
+                .expect("Cannot create renderer")
+        };
+        let mut canvas = Canvas::new(renderer).expect("Failed to create canvas");
+        canvas.set_size(width, height, 1.0);
+
+        cx.add_canvas(Entity::root(), canvas);
+
+        // The offscreen buffer keeps its contents between draws
+        cx.set_buffer_age(Entity::root(), Some(1));
+
+        Self { _context: context }
+    }
+
+    /// Replaces the offscreen buffer with one of the given size.
+    ///
+    /// OSMesa buffers can't be resized, so the context and its canvas are recreated. Fonts are
+    /// loaded into the new canvas, but images which were already uploaded to the old canvas are
+    /// not.
+    pub(crate) fn resize(&mut self, cx: &mut Context, width: u32, height: u32) {
+        // The old canvas is dropped while its own context is still the current one
+        cx.canvases.remove(&Entity::root());
+
+        *self = Self::new(cx, width, height);
+    }
+}
+
+/// Draws the application and reads the result back into an image.
+pub(crate) fn render(cx: &mut Context) -> RgbaImage {
+    cx.draw();
+
+    let canvas = cx.canvases.get_mut(&Entity::root()).expect("No canvas to render to");
+    let screenshot = canvas.screenshot().expect("Failed to read back rendered image");
+
+    let width = screenshot.width() as u32;
+    let height = screenshot.height() as u32;
+    let data = screenshot.pixels().flat_map(|pixel| [pixel.r, pixel.g, pixel.b, pixel.a]).collect();
+
+    RgbaImage::from_raw(width, height, data).expect("Screenshot has the wrong size")
+}
+
+/// Compares an image against a golden file, or writes the golden file if `update` is true.
+///
+/// On a mismatch, or if the golden file does not exist, the rendered image is saved next to the
+/// golden file with an `.actual.png` extension so the two can be reviewed side by side.
+pub(crate) fn compare(
+    actual: &RgbaImage,
+    golden: &Path,
+    tolerance: u8,
+    update: bool,
+) -> Result<(), SnapshotError> {
+    if update {
+        if let Some(parent) = golden.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual.save(golden)?;
+        return Ok(());
+    }
+
+    let actual_path = golden.with_extension("actual.png");
+
+    if !golden.exists() {
+        actual.save(&actual_path)?;
+        return Err(SnapshotError::MissingGolden { golden: golden.to_path_buf(), actual_path });
+    }
+
+    let expected = image::open(golden)?.to_rgba8();
+
+    if expected.dimensions() != actual.dimensions() {
+        actual.save(&actual_path)?;
+        return Err(SnapshotError::SizeMismatch {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+        });
+    }
+
+    let differing = expected
+        .pixels()
+        .zip(actual.pixels())
+        .filter(|(a, b)| a.0.iter().zip(b.0.iter()).any(|(a, b)| a.abs_diff(*b) > tolerance))
+        .count();
+
+    if differing != 0 {
+        actual.save(&actual_path)?;
+        return Err(SnapshotError::PixelMismatch { differing, actual_path });
+    }
+
+    if actual_path.exists() {
+        std::fs::remove_file(actual_path)?;
+    }
+
+    Ok(())
+}
+
+/// An error returned when comparing a snapshot against a golden file.
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    /// The golden file does not exist. The rendered image was saved to `actual_path`.
+    MissingGolden {
+        golden: PathBuf,
+        actual_path: PathBuf,
+    },
+    /// The rendered image and the golden file have different dimensions.
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// Some pixels differ by more than the tolerance. The rendered image was saved to
+    /// `actual_path`.
+    PixelMismatch {
+        differing: usize,
+        actual_path: PathBuf,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "Snapshot IO error: {}", err),
+            SnapshotError::Image(err) => write!(f, "Snapshot image error: {}", err),
+            SnapshotError::MissingGolden { golden, actual_path } => write!(
+                f,
+                "Snapshot golden file {} does not exist, rendered image saved to {}. Set {}=1 to \
+                 write the golden file",
+                golden.display(),
+                actual_path.display(),
+                UPDATE_SNAPSHOTS_VAR
+            ),
+            SnapshotError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Snapshot size mismatch: expected {}x{}, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            SnapshotError::PixelMismatch { differing, actual_path } => write!(
+                f,
+                "Snapshot mismatch: {} pixels differ, rendered image saved to {}",
+                differing,
+                actual_path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<image::ImageError> for SnapshotError {
+    fn from(err: image::ImageError) -> Self {
+        SnapshotError::Image(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn golden_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vizia-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.png", name));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("actual.png"));
+        path
+    }
+
+    fn filled(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn missing_golden_is_an_error() {
+        let golden = golden_path("missing");
+
+        match compare(&filled(4, 4, 100), &golden, 0, false) {
+            Err(SnapshotError::MissingGolden { actual_path, .. }) => {
+                assert_eq!(image::open(&actual_path).unwrap().to_rgba8(), filled(4, 4, 100));
+            }
+            result => panic!("Expected a missing golden file, got {:?}", result),
+        }
+
+        assert!(!golden.exists());
+    }
+
+    #[test]
+    fn golden_is_written_when_updating() {
+        let golden = golden_path("update");
+
+        compare(&filled(4, 4, 100), &golden, 0, true).unwrap();
+
+        assert!(golden.exists());
+        assert!(compare(&filled(4, 4, 100), &golden, 0, false).is_ok());
+        assert!(!golden.with_extension("actual.png").exists());
+    }
+
+    #[test]
+    fn differences_within_tolerance_match() {
+        let golden = golden_path("tolerance");
+        compare(&filled(4, 4, 100), &golden, 0, true).unwrap();
+
+        assert!(compare(&filled(4, 4, 102), &golden, 2, false).is_ok());
+        assert!(!golden.with_extension("actual.png").exists());
+    }
+
+    #[test]
+    fn differing_pixels_are_counted_and_saved() {
+        let golden = golden_path("pixels");
+        compare(&filled(4, 4, 100), &golden, 0, true).unwrap();
+
+        let mut actual = filled(4, 4, 100);
+        actual.put_pixel(1, 2, Rgba([0, 100, 100, 255]));
+        actual.put_pixel(3, 3, Rgba([100, 100, 100, 0]));
+
+        match compare(&actual, &golden, 2, false) {
+            Err(SnapshotError::PixelMismatch { differing, actual_path }) => {
+                assert_eq!(differing, 2);
+                assert_eq!(image::open(&actual_path).unwrap().to_rgba8(), actual);
+            }
+            result => panic!("Expected a pixel mismatch, got {:?}", result),
+        }
+
+        // A later match removes the image saved by the mismatch
+        compare(&filled(4, 4, 100), &golden, 0, false).unwrap();
+        assert!(!golden.with_extension("actual.png").exists());
+    }
+
+    #[test]
+    fn size_mismatch_is_reported() {
+        let golden = golden_path("size");
+        compare(&filled(4, 4, 100), &golden, 0, true).unwrap();
+
+        match compare(&filled(4, 3, 100), &golden, 255, false) {
+            Err(SnapshotError::SizeMismatch { expected, actual }) => {
+                assert_eq!(expected, (4, 4));
+                assert_eq!(actual, (4, 3));
+            }
+            result => panic!("Expected a size mismatch, got {:?}", result),
+        }
+    }
+}
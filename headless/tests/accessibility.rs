@@ -0,0 +1,36 @@
+mod common;
+
+use common::{app, AppData, AppEvent};
+use vizia_core::prelude::*;
+
+#[test]
+fn checkbox_is_exported_and_pressed_by_accessibility() {
+    let mut app = app(|cx| {
+        AppData { checked: false }.build(cx);
+
+        Checkbox::new(cx, AppData::checked)
+            .on_toggle(|cx| cx.emit(AppEvent::Toggle))
+            .accessible_name("Enable")
+            .id("checkbox");
+    });
+
+    app.context().enable_accessibility();
+    app.update();
+
+    let checkbox = app.entity_by_id("checkbox").unwrap();
+    let update = app.context().take_accessibility_update().unwrap();
+    let node = update.nodes.iter().find(|node| node.entity == checkbox).unwrap();
+    assert_eq!(node.role, Role::CheckBox);
+    assert_eq!(node.name.as_deref(), Some("Enable"));
+    assert_eq!(node.checked, Some(false));
+
+    let root = update.nodes.iter().find(|node| node.entity == Entity::root()).unwrap();
+    assert!(root.children.contains(&checkbox));
+
+    app.context().emit_to(checkbox, AccessAction::Press);
+    app.update();
+
+    assert_eq!(app.context().accessibility_node(checkbox).unwrap().checked, Some(true));
+    let update = app.context().take_accessibility_update().unwrap();
+    assert_eq!(update.nodes.len(), 1);
+}
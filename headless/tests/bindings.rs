@@ -0,0 +1,31 @@
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use common::{app, AppData, AppEvent};
+use vizia_core::prelude::*;
+
+#[test]
+fn outer_binding_is_rebuilt_before_inner_binding() {
+    let inner_builds = Rc::new(Cell::new(0));
+    let counter = inner_builds.clone();
+    let mut app = app(move |cx| {
+        AppData { checked: false }.build(cx);
+
+        Binding::new(cx, AppData::checked, move |cx, _| {
+            let counter = counter.clone();
+            Binding::new(cx, AppData::checked.map(|checked| *checked), move |_, _| {
+                counter.set(counter.get() + 1);
+            });
+        });
+    });
+
+    assert_eq!(inner_builds.get(), 1);
+
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    // The inner binding is replaced by the outer rebuild, so it doesn't run its own update
+    assert_eq!(inner_builds.get(), 2);
+}
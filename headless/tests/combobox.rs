@@ -0,0 +1,56 @@
+mod common;
+
+use common::{app, entities_with_class, entity_by_element};
+use vizia_core::prelude::*;
+
+#[derive(Lens)]
+struct ComboData {
+    options: Vec<String>,
+    value: String,
+}
+
+enum ComboEvent {
+    Select(usize),
+}
+
+impl Model for ComboData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|combo_event, _| match combo_event {
+            ComboEvent::Select(index) => self.value = self.options[*index].clone(),
+        });
+    }
+}
+
+#[test]
+fn highlighted_option_is_scrolled_into_view() {
+    let mut app = app(|cx| {
+        let options = (0..12).map(|index| format!("Option {}", index)).collect();
+        ComboData { options, value: String::new() }.build(cx);
+
+        Combobox::new(cx, ComboData::options, ComboData::value)
+            .on_select(|cx, index| cx.emit(ComboEvent::Select(index)))
+            .width(Pixels(150.0));
+    });
+
+    let arrow = entities_with_class(&mut app, "combobox_arrow")[0];
+    app.click_entity(arrow);
+
+    // Eight of the twelve options are visible, so highlighting the tenth scrolls by two rows
+    for _ in 0..10 {
+        app.key_press(Code::ArrowDown, Key::ArrowDown);
+    }
+
+    let scroll_view = entity_by_element(&mut app, "scrollview").unwrap();
+    assert_eq!(app.data::<ScrollData>(scroll_view).unwrap().scroll_y, 0.5);
+
+    let row = entities_with_class(&mut app, "combobox_option")[9];
+    assert!(app.context().has_pseudo_class(row, PseudoClass::CHECKED));
+    let view = app.context().cache().get_bounds(scroll_view);
+    let bounds = app.context().cache().get_bounds(row);
+    assert_eq!(view.intersection(&bounds), bounds);
+
+    app.key_press(Code::Enter, Key::Enter);
+    assert_eq!(app.data::<ComboData>(Entity::root()).unwrap().value, "Option 9");
+    let content = entities_with_class(&mut app, "textbox_content")[0];
+    assert_eq!(app.context().style_ref().text.get(content).map(String::as_str), Some("Option 9"));
+}
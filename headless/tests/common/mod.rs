@@ -0,0 +1,47 @@
+// Helpers shared by the integration tests, each of which only uses some of them
+#![allow(dead_code)]
+
+use vizia_core::prelude::*;
+use vizia_headless::{Application, ApplicationRunner};
+
+/// A model with a flag which is flipped by [`AppEvent::Toggle`].
+#[derive(Lens)]
+pub struct AppData {
+    pub checked: bool,
+}
+
+pub enum AppEvent {
+    Toggle,
+}
+
+impl Model for AppData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|app_event, _| match app_event {
+            AppEvent::Toggle => self.checked ^= true,
+        });
+    }
+}
+
+/// Builds an application with a 200x100 window.
+pub fn app(content: impl 'static + FnOnce(&mut Context)) -> ApplicationRunner {
+    Application::new(content).inner_size((200, 100)).build()
+}
+
+/// Returns the first view in the tree with the given element name.
+pub fn entity_by_element(app: &mut ApplicationRunner, element: &str) -> Option<Entity> {
+    let cx = app.context();
+    cx.tree_ref()
+        .into_iter()
+        .find(|entity| cx.views.get(entity).and_then(|view| view.element()) == Some(element))
+}
+
+/// Returns the views with a class, in tree order.
+pub fn entities_with_class(app: &mut ApplicationRunner, class: &str) -> Vec<Entity> {
+    let cx = app.context();
+    cx.tree_ref()
+        .into_iter()
+        .filter(|entity| {
+            cx.style_ref().classes.get(*entity).map_or(false, |classes| classes.contains(class))
+        })
+        .collect()
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::{app, AppData, AppEvent};
+use vizia_core::cache::BoundingBox;
+use vizia_core::prelude::*;
+
+#[test]
+fn class_change_damages_only_changed_view() {
+    let mut app = app(|cx| {
+        AppData { checked: false }.build(cx);
+
+        cx.add_theme(".on { background-color: red; }");
+
+        Element::new(cx).size(Pixels(20.0)).id("static");
+        Element::new(cx).size(Pixels(20.0)).toggle_class("on", AppData::checked).id("changed");
+    });
+
+    // The first frame repaints the whole window
+    assert!(app.context().has_damage());
+    app.context().draw();
+    assert!(!app.context().has_damage());
+
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    let changed = app.entity_by_id("changed").unwrap();
+    let damage = app.context().damage(Entity::root()).to_vec();
+    assert_eq!(damage.len(), 1);
+    let bounds = app.context().cache().get_bounds(changed);
+    assert!(damage[0].contains(&bounds));
+    assert!(damage[0].w < 200.0 && damage[0].h < 100.0);
+
+    let unchanged = app.entity_by_id("static").unwrap();
+    let bounds = app.context().cache().get_bounds(unchanged);
+    assert!(!damage[0].contains(&bounds));
+}
+
+#[test]
+fn changes_far_apart_damage_separate_regions() {
+    let mut app = app(|cx| {
+        AppData { checked: false }.build(cx);
+
+        cx.add_theme(".on { background-color: red; }");
+
+        Element::new(cx)
+            .size(Pixels(20.0))
+            .position_type(PositionType::SelfDirected)
+            .toggle_class("on", AppData::checked)
+            .id("first");
+        Element::new(cx)
+            .size(Pixels(20.0))
+            .position_type(PositionType::SelfDirected)
+            .left(Pixels(180.0))
+            .top(Pixels(80.0))
+            .toggle_class("on", AppData::checked)
+            .id("second");
+    });
+
+    app.context().draw();
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    let damage = app.context().damage(Entity::root()).to_vec();
+    assert_eq!(damage.len(), 2);
+
+    for id in ["first", "second"] {
+        let entity = app.entity_by_id(id).unwrap();
+        let bounds = app.context().cache().get_bounds(entity);
+        assert_eq!(
+            damage.iter().filter(|region| region.intersection(&bounds) == bounds).count(),
+            1
+        );
+    }
+
+    // The space between the views isn't repainted
+    assert!(damage.iter().all(|region| !region.intersects(&BoundingBox {
+        x: 90.0,
+        y: 40.0,
+        w: 20.0,
+        h: 20.0
+    })));
+}
+
+#[test]
+fn resizing_view_damages_views_it_moves() {
+    let mut app = app(|cx| {
+        AppData { checked: false }.build(cx);
+
+        cx.add_theme(".grows { width: 20px; } .grows.on { width: 50px; }");
+
+        HStack::new(cx, |cx| {
+            Element::new(cx)
+                .height(Pixels(20.0))
+                .class("grows")
+                .toggle_class("on", AppData::checked);
+            Element::new(cx).size(Pixels(20.0)).id("moved");
+        })
+        .height(Auto);
+        Element::new(cx).size(Pixels(20.0)).top(Pixels(10.0)).id("static");
+    });
+
+    app.context().draw();
+    let moved = app.entity_by_id("moved").unwrap();
+    let previous = app.context().cache().get_bounds(moved);
+
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    let bounds = app.context().cache().get_bounds(moved);
+    assert_ne!(bounds, previous);
+
+    let damage = app.context().damage(Entity::root()).to_vec();
+    for bounds in [previous, bounds] {
+        assert!(damage.iter().any(|region| region.intersection(&bounds) == bounds));
+    }
+
+    let unchanged = app.entity_by_id("static").unwrap();
+    let bounds = app.context().cache().get_bounds(unchanged);
+    assert!(damage.iter().all(|region| !region.intersects(&bounds)));
+}
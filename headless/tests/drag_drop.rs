@@ -0,0 +1,84 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{app, entity_by_element};
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+// A draggable view carrying the given number next to a drop target for numbers above five, which
+// records the numbers dropped onto it
+fn drag_app(payload: u32, dropped: Rc<RefCell<Vec<u32>>>) -> ApplicationRunner {
+    app(move |cx| {
+        cx.add_theme(
+            ".target { width: 50px; height: 50px; } .target:drop-target { height: 40px; }",
+        );
+
+        HStack::new(cx, move |cx| {
+            Element::new(cx).size(Pixels(50.0)).draggable(move |_| payload).id("source");
+            Element::new(cx)
+                .class("target")
+                .accept_drop(|number: &u32| *number > 5)
+                .on_drop(move |_, number: &u32| dropped.borrow_mut().push(*number))
+                .id("target");
+        });
+    })
+}
+
+// Presses the mouse on the source and drags it over the target
+fn drag_to_target(app: &mut ApplicationRunner) {
+    app.mouse_move(25.0, 25.0);
+    app.mouse_down(MouseButton::Left);
+    app.mouse_move(35.0, 25.0);
+    app.mouse_move(75.0, 25.0);
+}
+
+#[test]
+fn drag_drops_payload_onto_target() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let mut app = drag_app(7, dropped.clone());
+    let target = app.entity_by_id("target").unwrap();
+
+    drag_to_target(&mut app);
+    assert!(app.context().has_pseudo_class(target, PseudoClass::DROP_TARGET));
+    assert_eq!(app.context().cache().get_height(target), 40.0);
+
+    // The preview follows the cursor
+    assert!(entity_by_element(&mut app, "dragpreview").is_some());
+
+    app.mouse_up(MouseButton::Left);
+    assert_eq!(*dropped.borrow(), [7]);
+    assert!(!app.context().has_pseudo_class(target, PseudoClass::DROP_TARGET));
+    assert_eq!(app.context().cache().get_height(target), 50.0);
+    assert!(entity_by_element(&mut app, "dragpreview").is_none());
+}
+
+#[test]
+fn target_ignores_rejected_payload() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let mut app = drag_app(3, dropped.clone());
+    let target = app.entity_by_id("target").unwrap();
+
+    drag_to_target(&mut app);
+    assert!(!app.context().has_pseudo_class(target, PseudoClass::DROP_TARGET));
+
+    app.mouse_up(MouseButton::Left);
+    assert!(dropped.borrow().is_empty());
+}
+
+#[test]
+fn escape_cancels_drag() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let mut app = drag_app(7, dropped.clone());
+    let target = app.entity_by_id("target").unwrap();
+
+    drag_to_target(&mut app);
+    assert!(app.context().has_pseudo_class(target, PseudoClass::DROP_TARGET));
+
+    app.key_press(Code::Escape, Key::Escape);
+    assert!(!app.context().has_pseudo_class(target, PseudoClass::DROP_TARGET));
+
+    app.mouse_up(MouseButton::Left);
+    assert!(dropped.borrow().is_empty());
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::app;
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+// Records the file events which reach it, along with its name
+struct FileLog {
+    name: &'static str,
+    log: Rc<RefCell<Vec<(&'static str, String)>>>,
+}
+
+impl View for FileLog {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::FileHovered(_)
+            | WindowEvent::FileDropped(_)
+            | WindowEvent::FileHoverCancelled => {
+                self.log.borrow_mut().push((self.name, format!("{:?}", window_event)));
+            }
+
+            _ => {}
+        });
+    }
+}
+
+fn file_app(log: Rc<RefCell<Vec<(&'static str, String)>>>) -> ApplicationRunner {
+    app(move |cx| {
+        HStack::new(cx, move |cx| {
+            FileLog { name: "left", log: log.clone() }.build(cx, |_| {}).size(Pixels(50.0));
+            FileLog { name: "right", log }.build(cx, |_| {}).size(Pixels(50.0));
+        });
+    })
+}
+
+#[test]
+fn dropped_files_go_to_view_which_was_hovered() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut app = file_app(log.clone());
+
+    app.mouse_move(25.0, 25.0);
+    app.send_event(WindowEvent::FileHovered("a.txt".into()));
+    app.send_event(WindowEvent::FileHovered("b.txt".into()));
+
+    // The hovered view is stale during an OS drag, so it doesn't change the target
+    app.mouse_move(75.0, 25.0);
+    app.send_event(WindowEvent::FileDropped("a.txt".into()));
+    app.send_event(WindowEvent::FileDropped("b.txt".into()));
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("left", String::from("FileHovered(\"a.txt\")")),
+            ("left", String::from("FileHovered(\"b.txt\")")),
+            ("left", String::from("FileDropped(\"a.txt\")")),
+            ("left", String::from("FileDropped(\"b.txt\")")),
+        ]
+    );
+
+    // The next drag goes to the view which is hovered when it starts
+    log.borrow_mut().clear();
+    app.send_event(WindowEvent::FileHovered("c.txt".into()));
+    app.send_event(WindowEvent::FileDropped("c.txt".into()));
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("right", String::from("FileHovered(\"c.txt\")")),
+            ("right", String::from("FileDropped(\"c.txt\")")),
+        ]
+    );
+}
+
+#[test]
+fn cancelled_file_hover_goes_to_view_which_was_hovered() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut app = file_app(log.clone());
+
+    app.mouse_move(25.0, 25.0);
+    app.send_event(WindowEvent::FileHovered("a.txt".into()));
+    app.mouse_move(75.0, 25.0);
+    app.send_event(WindowEvent::FileHoverCancelled);
+    app.send_event(WindowEvent::FileHovered("a.txt".into()));
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("left", String::from("FileHovered(\"a.txt\")")),
+            ("left", String::from("FileHoverCancelled")),
+            ("right", String::from("FileHovered(\"a.txt\")")),
+        ]
+    );
+}
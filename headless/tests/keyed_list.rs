@@ -0,0 +1,98 @@
+mod common;
+
+use common::{app, entity_by_element};
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+#[derive(Debug, Clone, Data, Lens)]
+struct Row {
+    id: u32,
+    name: String,
+}
+
+#[derive(Lens)]
+struct RowData {
+    rows: Vec<Row>,
+}
+
+enum RowEvent {
+    Set(Vec<(u32, &'static str)>),
+}
+
+impl Model for RowData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|row_event, _| match row_event {
+            RowEvent::Set(rows) => {
+                self.rows =
+                    rows.iter().map(|(id, name)| Row { id: *id, name: name.to_string() }).collect();
+            }
+        });
+    }
+}
+
+// Returns the view and text of each row of a keyed list, in order
+fn keyed_rows(app: &mut ApplicationRunner) -> Vec<(Entity, String)> {
+    let list = entity_by_element(app, "list").unwrap();
+    let cx = app.context();
+    // Each row is an element followed by the binding which sets its text
+    list.child_iter(cx.tree_ref())
+        .filter(|row| cx.views.get(row).and_then(|view| view.element()) == Some("element"))
+        .map(|row| (row, cx.style_ref().text.get(row).cloned().unwrap_or_default()))
+        .collect()
+}
+
+#[test]
+fn keyed_list_keeps_views_of_items() {
+    let mut app = app(|cx| {
+        let rows = vec![(1, "a"), (2, "b"), (3, "c")];
+        RowData { rows: Vec::new() }.build(cx);
+        cx.emit(RowEvent::Set(rows));
+
+        KeyedList::new(
+            cx,
+            RowData::rows,
+            |row| row.id,
+            |cx, _, row| {
+                // The lens of a removed item has no value, so the row reads it fallibly
+                Element::new(cx).bind(row.then(Row::name), |handle, name| {
+                    if let Some(name) = name.get_fallible(handle.cx) {
+                        handle.text(name.as_str());
+                    }
+                });
+            },
+        );
+    });
+
+    let rows = keyed_rows(&mut app);
+    let names = rows.iter().map(|(_, name)| name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["a", "b", "c"]);
+    let (a, b, c) = (rows[0].0, rows[1].0, rows[2].0);
+
+    // Inserting an item builds only its view
+    app.context()
+        .emit_to(Entity::root(), RowEvent::Set(vec![(0, "z"), (1, "a"), (2, "b"), (3, "c")]));
+    app.update();
+    let rows = keyed_rows(&mut app);
+    assert_eq!(rows[0].1, "z");
+    assert_eq!(rows[1..].iter().map(|(row, _)| *row).collect::<Vec<_>>(), [a, b, c]);
+    let z = rows[0].0;
+
+    // Removing items removes only their views
+    app.context().emit_to(Entity::root(), RowEvent::Set(vec![(0, "z"), (3, "c")]));
+    app.update();
+    assert_eq!(keyed_rows(&mut app).iter().map(|(row, _)| *row).collect::<Vec<_>>(), [z, c]);
+    assert!(!app.context().tree_ref().into_iter().any(|entity| entity == a || entity == b));
+
+    // Moving items moves their views
+    app.context().emit_to(Entity::root(), RowEvent::Set(vec![(3, "c"), (0, "z")]));
+    app.update();
+    assert_eq!(keyed_rows(&mut app), [(c, String::from("c")), (z, String::from("z"))]);
+
+    // Changing an item updates its view in place, and a new key gets a new view
+    app.context().emit_to(Entity::root(), RowEvent::Set(vec![(3, "d"), (4, "z")]));
+    app.update();
+    let rows = keyed_rows(&mut app);
+    assert_eq!(rows[0], (c, String::from("d")));
+    assert_ne!(rows[1].0, z);
+    assert_eq!(rows[1].1, "z");
+}
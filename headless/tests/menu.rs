@@ -0,0 +1,189 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::app;
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+#[derive(Lens)]
+struct MenuModel {
+    version: u32,
+    text: String,
+}
+
+enum MenuModelEvent {
+    Rebuild,
+}
+
+impl Model for MenuModel {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|menu_event, _| match menu_event {
+            MenuModelEvent::Rebuild => self.version += 1,
+        });
+    }
+}
+
+// A menu bar with a File menu, whose buttons are rebuilt by a binding, and an Edit menu, next
+// to a textbox. The actions of the buttons are recorded in the log.
+fn menu_app(log: Rc<RefCell<Vec<&'static str>>>) -> ApplicationRunner {
+    app(move |cx| {
+        MenuModel { version: 0, text: String::new() }.build(cx);
+
+        let log = log.clone();
+        MenuController::new(cx, false, move |cx| {
+            MenuStack::new_horizontal(cx, move |cx| {
+                let file_log = log.clone();
+                Menu::new(
+                    cx,
+                    |cx| Label::new(cx, "File"),
+                    move |cx| {
+                        Binding::new(cx, MenuModel::version, move |cx, _| {
+                            let new_log = file_log.clone();
+                            MenuButton::new_simple(cx, "New", move |_| {
+                                new_log.borrow_mut().push("new")
+                            })
+                            .mnemonic(Code::KeyN)
+                            .accelerator(KeyChord::new(Modifiers::CTRL, Code::KeyN))
+                            .id("new");
+
+                            let open_log = file_log.clone();
+                            MenuButton::new_simple(cx, "Open", move |_| {
+                                open_log.borrow_mut().push("open")
+                            })
+                            .mnemonic(Code::KeyO)
+                            .id("open");
+                        });
+                    },
+                )
+                .mnemonic(Code::KeyF)
+                .id("file");
+
+                let edit_log = log.clone();
+                Menu::new(
+                    cx,
+                    |cx| Label::new(cx, "Edit"),
+                    move |cx| {
+                        MenuButton::new_simple(cx, "Copy", move |_| {
+                            edit_log.borrow_mut().push("copy")
+                        })
+                        .accelerator(KeyChord::new(Modifiers::CTRL, Code::KeyC))
+                        .id("copy");
+                    },
+                )
+                .mnemonic(Code::KeyE)
+                .id("edit");
+            });
+        });
+
+        Textbox::new(cx, MenuModel::text).size(Pixels(100.0)).id("textbox");
+    })
+}
+
+fn press_key(app: &mut ApplicationRunner, modifiers: Modifiers, code: Code, key: Key) {
+    app.set_modifiers(modifiers);
+    app.key_press(code, key);
+    app.set_modifiers(Modifiers::empty());
+}
+
+fn is_selected(app: &mut ApplicationRunner, id: &str) -> bool {
+    let entity = app.entity_by_id(id).unwrap();
+    app.context().has_pseudo_class(entity, PseudoClass::SELECTED)
+}
+
+#[test]
+fn menu_is_navigated_with_keyboard() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut app = menu_app(log.clone());
+
+    press_key(&mut app, Modifiers::ALT, Code::KeyF, Key::Character("f".into()));
+    assert!(is_selected(&mut app, "file"));
+    assert!(!is_selected(&mut app, "new"));
+
+    app.key_press(Code::ArrowDown, Key::ArrowDown);
+    assert!(is_selected(&mut app, "new"));
+    app.key_press(Code::ArrowDown, Key::ArrowDown);
+    assert!(is_selected(&mut app, "open"));
+    assert!(!is_selected(&mut app, "new"));
+
+    // The entries of a menu wrap around
+    app.key_press(Code::ArrowDown, Key::ArrowDown);
+    assert!(is_selected(&mut app, "new"));
+
+    // Escape leaves the submenu, and then closes the menu
+    app.key_press(Code::Escape, Key::Escape);
+    assert!(!is_selected(&mut app, "new"));
+    assert!(is_selected(&mut app, "file"));
+    app.key_press(Code::Escape, Key::Escape);
+    assert!(!is_selected(&mut app, "file"));
+
+    // Alt opens the first menu, and the arrow keys move along the menu bar
+    app.key_down(Code::AltLeft, Key::Alt);
+    app.key_up(Code::AltLeft, Key::Alt);
+    assert!(is_selected(&mut app, "file"));
+    app.key_press(Code::ArrowRight, Key::ArrowRight);
+    assert!(is_selected(&mut app, "edit"));
+    assert!(!is_selected(&mut app, "file"));
+
+    // Enter opens the submenu, and then presses the highlighted button
+    app.key_press(Code::Enter, Key::Enter);
+    assert!(is_selected(&mut app, "copy"));
+    app.key_press(Code::Enter, Key::Enter);
+    assert_eq!(*log.borrow(), ["copy"]);
+    assert!(!is_selected(&mut app, "edit"));
+}
+
+#[test]
+fn menu_mnemonics_choose_entries() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut app = menu_app(log.clone());
+
+    // Mnemonics of entries inside a menu only work while it's open
+    app.key_press(Code::KeyO, Key::Character("o".into()));
+    assert!(log.borrow().is_empty());
+
+    press_key(&mut app, Modifiers::ALT, Code::KeyE, Key::Character("e".into()));
+    assert!(is_selected(&mut app, "edit"));
+
+    // Entries of a closed menu can't be chosen
+    app.key_press(Code::KeyN, Key::Character("n".into()));
+    assert!(log.borrow().is_empty());
+
+    app.key_press(Code::Escape, Key::Escape);
+    press_key(&mut app, Modifiers::ALT, Code::KeyF, Key::Character("f".into()));
+    app.key_press(Code::KeyO, Key::Character("o".into()));
+    assert_eq!(*log.borrow(), ["open"]);
+    assert!(!is_selected(&mut app, "file"));
+}
+
+#[test]
+fn accelerators_skip_removed_buttons_and_text_fields() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut app = menu_app(log.clone());
+
+    press_key(&mut app, Modifiers::CTRL, Code::KeyN, Key::Character("n".into()));
+    assert_eq!(*log.borrow(), ["new"]);
+
+    // Rebuilt buttons replace the old ones in the menu and its accelerators
+    let old = app.entity_by_id("new").unwrap();
+    app.context().emit_to(Entity::root(), MenuModelEvent::Rebuild);
+    app.update();
+    assert_ne!(app.entity_by_id("new").unwrap(), old);
+
+    press_key(&mut app, Modifiers::CTRL, Code::KeyN, Key::Character("n".into()));
+    assert_eq!(*log.borrow(), ["new", "new"]);
+
+    press_key(&mut app, Modifiers::ALT, Code::KeyF, Key::Character("f".into()));
+    app.key_press(Code::ArrowDown, Key::ArrowDown);
+    assert!(is_selected(&mut app, "new"));
+    app.key_press(Code::Escape, Key::Escape);
+    app.key_press(Code::Escape, Key::Escape);
+
+    // A focused text field keeps its shortcuts
+    let textbox = app.entity_by_id("textbox").unwrap();
+    app.context().emit_to(textbox, TextEvent::StartEdit);
+    app.update();
+    press_key(&mut app, Modifiers::CTRL, Code::KeyC, Key::Character("c".into()));
+    assert_eq!(*log.borrow(), ["new", "new"]);
+}
@@ -0,0 +1,68 @@
+use vizia_core::prelude::*;
+use vizia_headless::{Application, ApplicationRunner};
+
+#[derive(Lens)]
+struct SplitData {
+    ratio: f32,
+}
+
+enum SplitEvent {
+    SetRatio(f32),
+}
+
+impl Model for SplitData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|split_event, _| match split_event {
+            SplitEvent::SetRatio(ratio) => self.ratio = *ratio,
+        });
+    }
+}
+
+#[test]
+fn dragging_splitter_resizes_panes() {
+    let mut app = Application::new(|cx| {
+        SplitData { ratio: 0.5 }.build(cx);
+
+        // The splitter is 4px wide, which leaves 200px for the panes
+        SplitView::new(
+            cx,
+            SplitData::ratio,
+            Orientation::Horizontal,
+            |cx| {
+                Element::new(cx).id("first");
+            },
+            |_| {},
+        )
+        .min_pane_size(20.0)
+        .default_ratio(0.25)
+        .on_changing(|cx, ratio| cx.emit(SplitEvent::SetRatio(ratio)))
+        .width(Pixels(204.0))
+        .height(Pixels(100.0));
+    })
+    .inner_size((300, 100))
+    .build();
+
+    let first = app.entity_by_id("first").unwrap().parent(app.context().tree_ref()).unwrap();
+    let ratio = |app: &mut ApplicationRunner| app.data::<SplitData>(Entity::root()).unwrap().ratio;
+    assert_eq!(app.context().cache().get_width(first), 100.0);
+
+    // The splitter keeps its offset from the cursor while it's dragged
+    app.mouse_move(102.0, 50.0);
+    app.mouse_down(MouseButton::Left);
+    app.mouse_move(152.0, 50.0);
+    assert_eq!(ratio(&mut app), 0.75);
+    assert_eq!(app.context().cache().get_width(first), 150.0);
+
+    // The panes are kept above their minimum size
+    app.mouse_move(5.0, 50.0);
+    assert_eq!(ratio(&mut app), 0.1);
+
+    app.mouse_up(MouseButton::Left);
+    app.mouse_move(100.0, 50.0);
+    assert_eq!(ratio(&mut app), 0.1);
+
+    // Double clicking the splitter resets the ratio
+    app.click(21.0, 50.0);
+    app.click(21.0, 50.0);
+    assert_eq!(ratio(&mut app), 0.25);
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{app, AppData, AppEvent};
+use vizia_core::prelude::*;
+
+#[test]
+fn class_change_restyles_descendants_and_siblings() {
+    let mut app = app(|cx| {
+        cx.add_theme(".open > element { width: 50px; } .open + element { height: 30px; }");
+
+        AppData { checked: false }.build(cx);
+
+        VStack::new(cx, |cx| {
+            Element::new(cx).id("child");
+        })
+        .toggle_class("open", AppData::checked);
+
+        Element::new(cx).id("sibling");
+    });
+
+    let child = app.entity_by_id("child").unwrap();
+    let sibling = app.entity_by_id("sibling").unwrap();
+    assert_ne!(app.context().cache().get_width(child), 50.0);
+    assert_ne!(app.context().cache().get_height(sibling), 30.0);
+
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    assert_eq!(app.context().cache().get_width(child), 50.0);
+    assert_eq!(app.context().cache().get_height(sibling), 30.0);
+
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    assert_ne!(app.context().cache().get_width(child), 50.0);
+    assert_ne!(app.context().cache().get_height(sibling), 30.0);
+}
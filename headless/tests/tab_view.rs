@@ -0,0 +1,124 @@
+mod common;
+
+use common::entities_with_class;
+use vizia_core::prelude::*;
+use vizia_headless::{Application, ApplicationRunner};
+
+#[derive(Lens)]
+struct TabData {
+    tabs: Vec<String>,
+    selected: usize,
+}
+
+enum TabEvent {
+    Select(usize),
+    Close(usize),
+}
+
+impl Model for TabData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|tab_event, _| match tab_event {
+            TabEvent::Select(index) => self.selected = *index,
+            TabEvent::Close(index) => {
+                self.tabs.remove(*index);
+                self.selected = self.selected.min(self.tabs.len().saturating_sub(1));
+            }
+        });
+    }
+}
+
+fn tab_app() -> ApplicationRunner {
+    Application::new(|cx| {
+        let tabs = ["a", "b", "c"].iter().map(|tab| tab.to_string()).collect();
+        TabData { tabs, selected: 0 }.build(cx);
+
+        TabView::new(
+            cx,
+            TabData::tabs,
+            TabData::selected,
+            |cx, _, tab| {
+                Element::new(cx).width(Pixels(30.0)).bind(tab, |handle, tab| {
+                    if let Some(tab) = tab.get_fallible(handle.cx) {
+                        handle.text(tab.as_str());
+                    }
+                });
+            },
+            |cx, _, tab| {
+                Element::new(cx).class("content").bind(tab, |handle, tab| {
+                    if let Some(tab) = tab.get_fallible(handle.cx) {
+                        handle.text(tab.as_str());
+                    }
+                });
+            },
+        )
+        .on_select(|cx, index| cx.emit(TabEvent::Select(index)))
+        .on_close(|cx, index| cx.emit(TabEvent::Close(index)))
+        .size(Pixels(300.0));
+    })
+    .inner_size((300, 300))
+    .build()
+}
+
+// Returns the number of tabs and the text of the content
+fn tab_state(app: &mut ApplicationRunner) -> (usize, String) {
+    let tabs = entities_with_class(app, "tab").len();
+    let content = entities_with_class(app, "content");
+    let text = content
+        .first()
+        .and_then(|content| app.context().style_ref().text.get(*content).cloned())
+        .unwrap_or_default();
+    (tabs, text)
+}
+
+#[test]
+fn pressing_tabs_and_keys_select_tabs() {
+    let mut app = tab_app();
+    assert_eq!(tab_state(&mut app), (3, String::from("a")));
+
+    let tabs = entities_with_class(&mut app, "tab");
+    app.click_entity(tabs[1]);
+    assert_eq!(tab_state(&mut app), (3, String::from("b")));
+    assert!(app.context().has_pseudo_class(tabs[1], PseudoClass::CHECKED));
+    assert!(!app.context().has_pseudo_class(tabs[0], PseudoClass::CHECKED));
+
+    // The arrow keys move the selection once a tab has been pressed
+    app.key_press(Code::ArrowRight, Key::ArrowRight);
+    assert_eq!(tab_state(&mut app), (3, String::from("c")));
+
+    app.set_modifiers(Modifiers::CTRL);
+    app.key_press(Code::PageDown, Key::PageDown);
+    assert_eq!(tab_state(&mut app), (3, String::from("a")));
+}
+
+#[test]
+fn tab_is_closed_when_close_button_is_released() {
+    let mut app = tab_app();
+    let close = entities_with_class(&mut app, "close");
+    let (x, y) = app.context().cache().get_bounds(close[1]).center();
+
+    // Pressing the close button neither closes nor selects the tab
+    app.mouse_move(x, y);
+    app.mouse_down(MouseButton::Left);
+    assert_eq!(tab_state(&mut app), (3, String::from("a")));
+
+    // Releasing the mouse away from the button doesn't close the tab
+    app.mouse_move(x, y + 100.0);
+    app.mouse_up(MouseButton::Left);
+    assert_eq!(tab_state(&mut app), (3, String::from("a")));
+
+    app.mouse_move(x, y);
+    app.mouse_down(MouseButton::Left);
+    app.mouse_up(MouseButton::Left);
+    assert_eq!(tab_state(&mut app), (2, String::from("a")));
+
+    let tabs = entities_with_class(&mut app, "tab");
+    let texts = tabs
+        .iter()
+        .map(|tab| {
+            let cx = app.context();
+            let header = tab.child_iter(cx.tree_ref()).next().unwrap();
+            cx.style_ref().text.get(header).cloned().unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["a", "c"]);
+}
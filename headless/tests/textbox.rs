@@ -0,0 +1,139 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{app, entities_with_class};
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+#[derive(Lens)]
+struct TextData {
+    text: String,
+}
+
+enum TextDataEvent {
+    SetText(String),
+}
+
+impl Model for TextData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|text_event, _| match text_event {
+            TextDataEvent::SetText(text) => self.text = text.clone(),
+        });
+    }
+}
+
+// Records the input method requests which are sent to the window by the views within it
+#[derive(Default)]
+struct ImeRequests {
+    allowed: Vec<bool>,
+    areas: Vec<(f32, f32, f32, f32)>,
+}
+
+struct ImeLog {
+    requests: Rc<RefCell<ImeRequests>>,
+}
+
+impl View for ImeLog {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::SetImeAllowed(allowed) => {
+                self.requests.borrow_mut().allowed.push(*allowed);
+            }
+
+            WindowEvent::SetImeCursorArea(x, y, w, h) => {
+                self.requests.borrow_mut().areas.push((*x, *y, *w, *h));
+            }
+
+            _ => {}
+        });
+    }
+}
+
+// A textbox editing "abc", which has started editing with all of its text selected
+fn textbox_app(requests: Rc<RefCell<ImeRequests>>) -> (ApplicationRunner, Entity) {
+    let mut app = app(move |cx| {
+        TextData { text: String::from("abc") }.build(cx);
+
+        ImeLog { requests }.build(cx, |cx| {
+            Textbox::new(cx, TextData::text)
+                .size(Pixels(100.0))
+                .on_edit(|cx, text| cx.emit(TextDataEvent::SetText(text)))
+                .id("textbox");
+        });
+    });
+
+    let textbox = app.entity_by_id("textbox").unwrap();
+    app.context().emit_to(textbox, TextEvent::StartEdit);
+    app.update();
+    (app, textbox)
+}
+
+// Returns the text shown by a textbox
+fn shown_text(app: &mut ApplicationRunner) -> String {
+    let content = entities_with_class(app, "textbox_content")[0];
+    app.context().style_ref().text.get(content).cloned().unwrap_or_default()
+}
+
+#[test]
+fn ime_commit_is_inserted_into_textbox() {
+    let requests = Rc::new(RefCell::new(ImeRequests::default()));
+    let (mut app, textbox) = textbox_app(requests.clone());
+    assert_eq!(requests.borrow().areas.len(), 1);
+
+    // The committed text replaces the selection like typed text
+    app.send_event(WindowEvent::ImeCommit(String::from("日本")));
+    assert_eq!(app.data::<TextData>(textbox).unwrap().text, "日本");
+
+    app.send_event(WindowEvent::ImeCommit(String::from("語")));
+    assert_eq!(app.data::<TextData>(textbox).unwrap().text, "日本語");
+
+    // The area is only sent again when the caret moves
+    let sent = requests.borrow().areas.len();
+    app.key_press(Code::End, Key::End);
+    assert_eq!(requests.borrow().areas.len(), sent);
+
+    app.key_press(Code::Home, Key::Home);
+    let areas = &requests.borrow().areas;
+    assert_eq!(areas.len(), sent + 1);
+    let (end, home) = (areas[sent - 1], areas[sent]);
+    assert!(home.0 < end.0);
+    assert_eq!(home.1, end.1);
+}
+
+#[test]
+fn ime_preedit_is_shown_underlined_until_committed() {
+    let requests = Rc::new(RefCell::new(ImeRequests::default()));
+    let (mut app, textbox) = textbox_app(requests.clone());
+    assert_eq!(requests.borrow().allowed, [true]);
+    assert!(entities_with_class(&mut app, "textbox_preedit").is_empty());
+
+    // The composition replaces the selection, but isn't an edit until it's committed
+    app.send_event(WindowEvent::ImePreedit(String::from("にほ"), Some((6, 6))));
+    assert_eq!(shown_text(&mut app), "にほ");
+    assert_eq!(app.data::<TextData>(textbox).unwrap().text, "abc");
+    let underline = entities_with_class(&mut app, "textbox_preedit");
+    assert_eq!(underline.len(), 1);
+    assert!(app.context().cache().get_width(underline[0]) > 0.0);
+
+    // Keys are left to the input method while composing
+    app.key_press(Code::Backspace, Key::Backspace);
+    assert_eq!(shown_text(&mut app), "にほ");
+
+    // An empty composition clears it
+    app.send_event(WindowEvent::ImePreedit(String::new(), None));
+    assert_eq!(shown_text(&mut app), "");
+    assert!(entities_with_class(&mut app, "textbox_preedit").is_empty());
+
+    app.send_event(WindowEvent::ImePreedit(String::from("にほん"), None));
+    app.send_event(WindowEvent::ImeCommit(String::from("日本")));
+    assert_eq!(shown_text(&mut app), "日本");
+    assert_eq!(app.data::<TextData>(textbox).unwrap().text, "日本");
+    assert!(entities_with_class(&mut app, "textbox_preedit").is_empty());
+
+    // Input methods are disallowed again once editing ends
+    app.context().emit_to(textbox, TextEvent::EndEdit);
+    app.update();
+    assert_eq!(requests.borrow().allowed, [true, false]);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{app, entity_by_element};
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+fn tooltip_app(delay: Duration) -> ApplicationRunner {
+    app(move |cx| {
+        cx.set_tooltip_delay(delay);
+
+        HStack::new(cx, |cx| {
+            Element::new(cx).size(Pixels(50.0)).tooltip("Save").id("save");
+            Element::new(cx).size(Pixels(50.0)).id("plain");
+        });
+    })
+}
+
+#[test]
+fn tooltip_is_shown_after_delay_and_hidden_on_leave() {
+    let mut app = tooltip_app(Duration::from_millis(50));
+
+    app.mouse_move(25.0, 25.0);
+    assert!(entity_by_element(&mut app, "tooltip").is_none());
+    assert!(app.context().tooltip_deadline().is_some());
+
+    std::thread::sleep(Duration::from_millis(60));
+    app.update();
+
+    let tooltip = entity_by_element(&mut app, "tooltip").unwrap();
+    assert!(app.context().tooltip_deadline().is_none());
+    let label = tooltip.child_iter(app.context().tree_ref()).next().unwrap();
+    assert_eq!(app.context().style_ref().text.get(label).map(String::as_str), Some("Save"));
+
+    // Moving onto a view without a tooltip hides it
+    app.mouse_move(75.0, 25.0);
+    assert!(entity_by_element(&mut app, "tooltip").is_none());
+    assert!(app.context().tooltip_deadline().is_none());
+}
+
+#[test]
+fn mouse_press_dismisses_tooltip_until_cursor_leaves() {
+    let mut app = tooltip_app(Duration::ZERO);
+
+    app.mouse_move(25.0, 25.0);
+    app.update();
+    assert!(entity_by_element(&mut app, "tooltip").is_some());
+
+    app.mouse_down(MouseButton::Left);
+    app.mouse_up(MouseButton::Left);
+    app.mouse_move(30.0, 30.0);
+    app.update();
+    assert!(entity_by_element(&mut app, "tooltip").is_none());
+    assert!(app.context().tooltip_deadline().is_none());
+
+    // Hovering the view again shows the tooltip again
+    app.mouse_move(75.0, 25.0);
+    app.mouse_move(25.0, 25.0);
+    app.update();
+    assert!(entity_by_element(&mut app, "tooltip").is_some());
+}
+
+#[test]
+fn tooltip_delay_can_be_changed() {
+    let mut app = tooltip_app(Duration::from_secs(3600));
+
+    app.mouse_move(25.0, 25.0);
+    app.update();
+    assert!(entity_by_element(&mut app, "tooltip").is_none());
+    let deadline = app.context().tooltip_deadline().unwrap();
+    assert!(deadline > Instant::now() + Duration::from_secs(3000));
+
+    app.context().set_tooltip_delay(Duration::ZERO);
+    assert!(app.context().tooltip_deadline().unwrap() <= Instant::now());
+    app.update();
+    assert!(entity_by_element(&mut app, "tooltip").is_some());
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use common::{app, entity_by_element};
+use vizia_core::prelude::*;
+use vizia_headless::ApplicationRunner;
+
+#[derive(Debug, Clone, Lens)]
+struct Node {
+    name: String,
+    children: Vec<Node>,
+}
+
+fn node(name: &str, children: Vec<Node>) -> Node {
+    Node { name: name.to_owned(), children }
+}
+
+#[derive(Lens)]
+struct TreeData {
+    nodes: Vec<Node>,
+}
+
+enum TreeEvent {
+    Set(Vec<Node>),
+}
+
+impl Model for TreeData {
+    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
+        event.map(|tree_event, _| match tree_event {
+            TreeEvent::Set(nodes) => self.nodes = nodes.clone(),
+        });
+    }
+}
+
+fn tree_app(nodes: Vec<Node>) -> (ApplicationRunner, Entity) {
+    let mut app = app(move |cx| {
+        TreeData { nodes }.build(cx);
+
+        TreeView::new(
+            cx,
+            TreeData::nodes,
+            |node: &Node| &node.children,
+            |cx, _, node| {
+                Element::new(cx).bind(node.then(Node::name), |handle, name| {
+                    if let Some(name) = name.get_fallible(handle.cx) {
+                        handle.text(name.as_str());
+                    }
+                });
+            },
+        );
+    });
+
+    let tree = entity_by_element(&mut app, "treeview").unwrap();
+    (app, tree)
+}
+
+// Returns the name of each node which is built, in order, and whether its row is selected
+fn tree_rows(app: &mut ApplicationRunner) -> Vec<(String, bool)> {
+    let cx = app.context();
+    cx.tree_ref()
+        .into_iter()
+        .filter(|entity| cx.views.get(entity).and_then(|view| view.element()) == Some("element"))
+        .map(|entity| {
+            let row = entity.parent(cx.tree_ref()).unwrap();
+            let name = cx.style_ref().text.get(entity).cloned().unwrap_or_default();
+            (name, cx.has_pseudo_class(row, PseudoClass::CHECKED))
+        })
+        .collect()
+}
+
+fn tree_names(app: &mut ApplicationRunner) -> Vec<String> {
+    tree_rows(app).into_iter().map(|(name, _)| name).collect()
+}
+
+#[test]
+fn tree_view_builds_children_of_expanded_nodes() {
+    let (mut app, tree) = tree_app(vec![
+        node("a", vec![node("a1", vec![node("a1x", vec![])]), node("a2", vec![])]),
+        node("b", vec![node("b1", vec![])]),
+    ]);
+    assert_eq!(tree_names(&mut app), ["a", "b"]);
+
+    app.context().emit_to(tree, TreeViewEvent::Expand(vec![0]));
+    app.update();
+    assert_eq!(tree_names(&mut app), ["a", "a1", "a2", "b"]);
+
+    // The children of a collapsed node stay unbuilt when its parent is expanded
+    app.context().emit_to(tree, TreeViewEvent::Toggle(vec![1]));
+    app.update();
+    assert_eq!(tree_names(&mut app), ["a", "a1", "a2", "b", "b1"]);
+
+    app.context().emit_to(tree, TreeViewEvent::Collapse(vec![0]));
+    app.update();
+    assert_eq!(tree_names(&mut app), ["a", "b", "b1"]);
+
+    // Expanding a node again shows its nested expanded nodes
+    app.context().emit_to(tree, TreeViewEvent::Expand(vec![0, 0]));
+    app.context().emit_to(tree, TreeViewEvent::Expand(vec![0]));
+    app.update();
+    assert_eq!(tree_names(&mut app), ["a", "a1", "a1x", "a2", "b", "b1"]);
+}
+
+#[test]
+fn tree_view_state_is_kept_by_path_when_nodes_change() {
+    let (mut app, tree) = tree_app(vec![
+        node("a", vec![node("a1", vec![])]),
+        node("b", vec![node("b1", vec![]), node("b2", vec![])]),
+    ]);
+
+    app.context().emit_to(tree, TreeViewEvent::Expand(vec![1]));
+    app.context().emit_to(tree, TreeViewEvent::Select(vec![1, 1]));
+    app.update();
+    assert_eq!(
+        tree_rows(&mut app),
+        [
+            (String::from("a"), false),
+            (String::from("b"), false),
+            (String::from("b1"), false),
+            (String::from("b2"), true)
+        ]
+    );
+
+    // Changing a node updates its row and its children in place
+    app.context().emit_to(
+        Entity::root(),
+        TreeEvent::Set(vec![
+            node("a", vec![node("a1", vec![])]),
+            node("c", vec![node("c1", vec![]), node("c2", vec![]), node("c3", vec![])]),
+        ]),
+    );
+    app.update();
+    assert_eq!(
+        tree_rows(&mut app),
+        [
+            (String::from("a"), false),
+            (String::from("c"), false),
+            (String::from("c1"), false),
+            (String::from("c2"), true),
+            (String::from("c3"), false)
+        ]
+    );
+
+    // The expanded and selected paths aren't moved when a node is removed
+    app.context().emit_to(
+        Entity::root(),
+        TreeEvent::Set(vec![node("c", vec![node("c1", vec![]), node("c2", vec![])])]),
+    );
+    app.update();
+    assert_eq!(tree_rows(&mut app), [(String::from("c"), false)]);
+
+    app.context().emit_to(
+        Entity::root(),
+        TreeEvent::Set(vec![
+            node("d", vec![node("d1", vec![])]),
+            node("c", vec![node("c1", vec![]), node("c2", vec![])]),
+        ]),
+    );
+    app.update();
+    assert_eq!(
+        tree_rows(&mut app),
+        [
+            (String::from("d"), false),
+            (String::from("c"), false),
+            (String::from("c1"), false),
+            (String::from("c2"), true)
+        ]
+    );
+}
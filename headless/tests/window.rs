@@ -0,0 +1,60 @@
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use common::{app, AppData, AppEvent};
+use vizia_core::cache::BoundingBox;
+use vizia_core::prelude::*;
+
+#[test]
+fn removing_window_view_closes_window() {
+    let closed = Rc::new(Cell::new(false));
+    let on_close = closed.clone();
+    let mut app = app(move |cx| {
+        AppData { checked: true }.build(cx);
+
+        Binding::new(cx, AppData::checked, move |cx, show| {
+            if show.get(cx) {
+                let on_close = on_close.clone();
+                Window::new(cx, |cx| {
+                    Element::new(cx).id("content");
+                })
+                .inner_size((120, 80))
+                .on_close(move |cx| {
+                    on_close.set(true);
+                    cx.emit(AppEvent::Toggle);
+                })
+                .id("window");
+            }
+        });
+    });
+
+    let window = app.entity_by_id("window").unwrap();
+    let content = app.entity_by_id("content").unwrap();
+    assert_eq!(app.context().windows().map(|(entity, _)| entity).collect::<Vec<_>>(), [window]);
+    assert_eq!(app.context().window_of(content), window);
+
+    // The contents are laid out at the size of the window, from its origin
+    assert_eq!(
+        app.context().cache().get_bounds(window),
+        BoundingBox::from_min_max(0.0, 0.0, 120.0, 80.0)
+    );
+
+    app.context().emit_to(window, WindowEvent::WindowClose);
+    app.update();
+
+    assert!(closed.get());
+    assert_eq!(app.context().windows().count(), 0);
+    assert!(app.entity_by_id("window").is_none());
+    assert!(app.context().take_closed_windows().is_empty());
+
+    // Opening the window again creates a new one
+    app.context().emit_to(Entity::root(), AppEvent::Toggle);
+    app.update();
+
+    let reopened = app.entity_by_id("window").unwrap();
+    let content = app.entity_by_id("content").unwrap();
+    assert_eq!(app.context().windows().count(), 1);
+    assert_eq!(app.context().window_of(content), reopened);
+}
@@ -15,3 +15,9 @@ pub mod prelude {
     #[cfg(all(not(feature = "winit"), feature = "baseview"))]
     pub use vizia_baseview::Application;
 }
+
+/// A backend without a window, used for testing views.
+#[cfg(feature = "headless")]
+pub mod headless {
+    pub use vizia_headless::*;
+}
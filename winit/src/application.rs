@@ -3,6 +3,9 @@ use crate::{
     window::Window,
 };
 use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use vizia_core::accessibility::AccessibilityAdapter;
 use vizia_core::cache::BoundingBox;
 #[cfg(not(target_arch = "wasm32"))]
 use vizia_core::context::EventProxy;
@@ -10,10 +13,13 @@ use vizia_core::events::EventManager;
 use vizia_core::fonts;
 use vizia_core::prelude::*;
 use vizia_core::window::Position;
+#[cfg(not(target_arch = "wasm32"))]
+use winit::event_loop::EventLoopWindowTarget;
 use winit::{
-    dpi::LogicalSize,
-    event::VirtualKeyCode,
-    event_loop::{ControlFlow, EventLoop, EventLoopProxy},
+    dpi::{LogicalSize, PhysicalSize},
+    event::{Ime, VirtualKeyCode},
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy},
+    window::WindowId,
 };
 
 static DEFAULT_THEME: &str = include_str!("../../core/resources/themes/default_theme.css");
@@ -26,6 +32,10 @@ pub struct Application {
     on_idle: Option<Box<dyn Fn(&mut Context)>>,
     window_description: WindowDescription,
     should_poll: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    accessibility_adapter: Option<
+        Box<dyn FnOnce(&winit::window::Window, ContextProxy) -> Box<dyn AccessibilityAdapter>>,
+    >,
 }
 
 // TODO uhhhhhhhhhhhhhhhhhhhhhh I think it's a winit bug that EventLoopProxy isn't Send on web
@@ -55,7 +65,7 @@ impl Application {
         #[allow(unused_mut)]
         let mut context = Context::new();
 
-        let event_loop = EventLoop::with_user_event();
+        let event_loop = EventLoopBuilder::with_user_event().build();
         #[cfg(not(target_arch = "wasm32"))]
         {
             let event_proxy_obj = event_loop.create_proxy();
@@ -71,6 +81,8 @@ impl Application {
             on_idle: None,
             window_description: WindowDescription::new(),
             should_poll: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            accessibility_adapter: None,
         }
     }
 
@@ -106,6 +118,21 @@ impl Application {
         self
     }
 
+    /// Attaches an accessibility adapter to the window, which enables the accessibility tree.
+    ///
+    /// The closure is called with the window once it has been created, along with a proxy which
+    /// the adapter can use to send [`AccessAction`] events to views. The adapter then receives
+    /// the changes to the accessibility tree after every update.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn accessibility_adapter<F>(mut self, adapter: F) -> Self
+    where
+        F: 'static + FnOnce(&winit::window::Window, ContextProxy) -> Box<dyn AccessibilityAdapter>,
+    {
+        self.accessibility_adapter = Some(Box::new(adapter));
+
+        self
+    }
+
     pub fn ignore_default_theme(mut self) -> Self {
         self.context.ignore_default_theme = true;
 
@@ -129,7 +156,24 @@ impl Application {
         let mut context = self.context;
         let event_loop = self.event_loop;
 
-        let window = Window::new(&mut context, &event_loop, &self.window_description);
+        let window =
+            Window::new(&mut context, &event_loop, &self.window_description, Entity::root());
+
+        // The entities of the open windows, including the main window at the root
+        let mut window_entities = HashMap::new();
+        window_entities.insert(window.id, Entity::root());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut accessibility_adapter = self.accessibility_adapter.take().map(|adapter| {
+            context.enable_accessibility();
+
+            let proxy = ContextProxy {
+                current: Entity::root(),
+                event_proxy: Some(Box::new(WinitEventProxy(event_loop.create_proxy()))),
+            };
+
+            (adapter)(window.window(), proxy)
+        });
 
         let regular_font = fonts::ROBOTO_REGULAR;
         let bold_font = fonts::ROBOTO_BOLD;
@@ -208,7 +252,7 @@ impl Application {
         let default_should_poll = self.should_poll;
         let stored_control_flow = RefCell::new(ControlFlow::Poll);
 
-        event_loop.run(move |event, _, control_flow| {
+        event_loop.run(move |event, target, control_flow| {
             match event {
                 winit::event::Event::UserEvent(event) => {
                     context.emit_custom(event);
@@ -226,34 +270,44 @@ impl Application {
                     context.process_data_updates();
                     context.process_style_updates();
 
+                    // Close the windows whose views have been removed and open any new ones
+                    close_windows(&mut context, &mut window_entities);
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    create_windows(&mut context, target, &mut window_entities);
+                    #[cfg(target_arch = "wasm32")]
+                    let _ = target;
+
                     if context.has_animations() {
                         *stored_control_flow.borrow_mut() = ControlFlow::Poll;
 
                         //context.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
                         event_loop_proxy.send_event(Event::new(WindowEvent::Redraw)).unwrap();
                         //window.handle.window().request_redraw();
-                        if let Some(window_event_handler) = context.views.remove(&Entity::root()) {
-                            if let Some(window) = window_event_handler.downcast_ref::<Window>() {
-                                window.window().request_redraw();
-                            }
-
-                            context.views.insert(Entity::root(), window_event_handler);
-                        }
+                        request_redraws(&mut context, &window_entities);
 
                         context.apply_animations();
                     }
 
+                    // Wake up to show a pending tooltip
+                    if let Some(deadline) = context.tooltip_deadline() {
+                        let mut control_flow = stored_control_flow.borrow_mut();
+                        if *control_flow == ControlFlow::Wait {
+                            *control_flow = ControlFlow::WaitUntil(deadline);
+                        }
+                    }
+
                     context.process_visual_updates();
 
-                    if let Some(window_view) = context.views.remove(&Entity::root()) {
-                        if let Some(window) = window_view.downcast_ref::<Window>() {
-                            if context.style().needs_redraw {
-                                window.window().request_redraw();
-                                context.style().needs_redraw = false;
-                            }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(adapter) = &mut accessibility_adapter {
+                        if let Some(update) = context.take_accessibility_update() {
+                            adapter.update(update);
                         }
+                    }
 
-                        context.views.insert(Entity::root(), window_view);
+                    if context.has_damage() {
+                        request_redraws(&mut context, &window_entities);
                     }
 
                     if let Some(idle_callback) = &on_idle {
@@ -267,39 +321,42 @@ impl Application {
                     }
                 }
 
-                winit::event::Event::RedrawRequested(_) => {
+                winit::event::Event::RedrawRequested(window_id) => {
                     // Redraw here
-                    context_draw(&mut context);
+                    if let Some(&window_entity) = window_entities.get(&window_id) {
+                        context_draw(&mut context, window_entity);
+                    }
                 }
 
-                winit::event::Event::WindowEvent { window_id: _, event } => {
+                winit::event::Event::WindowEvent { window_id, event } => {
+                    // Ignore events from windows which are already closing
+                    let window_entity = if let Some(&entity) = window_entities.get(&window_id) {
+                        entity
+                    } else {
+                        *control_flow = *stored_control_flow.borrow();
+                        return;
+                    };
+
                     match event {
                         winit::event::WindowEvent::CloseRequested => {
-                            *stored_control_flow.borrow_mut() = ControlFlow::Exit;
+                            if window_entity == Entity::root() {
+                                *stored_control_flow.borrow_mut() = ControlFlow::Exit;
+                            } else {
+                                context.emit_to(window_entity, WindowEvent::WindowClose);
+                            }
                         }
 
                         winit::event::WindowEvent::ScaleFactorChanged {
                             scale_factor,
                             new_inner_size,
                         } => {
-                            context.style().dpi_factor = scale_factor;
-                            context.cache().set_width(Entity::root(), new_inner_size.width as f32);
-                            context
-                                .cache()
-                                .set_height(Entity::root(), new_inner_size.height as f32);
-
-                            let logical_size: LogicalSize<f32> =
-                                new_inner_size.to_logical(context.style().dpi_factor);
-
-                            context
-                                .style()
-                                .width
-                                .insert(Entity::root(), Units::Pixels(logical_size.width as f32));
-
-                            context
-                                .style()
-                                .height
-                                .insert(Entity::root(), Units::Pixels(logical_size.height as f32));
+                            // Views in every window are laid out with the scale factor of the
+                            // main window
+                            if window_entity == Entity::root() {
+                                context.style().dpi_factor = scale_factor;
+                            }
+
+                            resize_window(&mut context, window_entity, *new_inner_size);
                         }
 
                         #[allow(deprecated)]
@@ -308,10 +365,10 @@ impl Application {
                             position,
                             modifiers: _,
                         } => {
-                            context.dispatch_system_event(WindowEvent::MouseMove(
-                                position.x as f32,
-                                position.y as f32,
-                            ));
+                            context.dispatch_window_event(
+                                window_entity,
+                                WindowEvent::MouseMove(position.x as f32, position.y as f32),
+                            );
                         }
 
                         #[allow(deprecated)]
@@ -337,7 +394,7 @@ impl Application {
                                 }
                             };
 
-                            context.dispatch_system_event(event);
+                            context.dispatch_window_event(window_entity, event);
                         }
 
                         winit::event::WindowEvent::MouseWheel { delta, phase: _, .. } => {
@@ -353,7 +410,7 @@ impl Application {
                                 }
                             };
 
-                            context.dispatch_system_event(out_event);
+                            context.dispatch_window_event(window_entity, out_event);
                         }
 
                         winit::event::WindowEvent::KeyboardInput {
@@ -380,53 +437,56 @@ impl Application {
                                 }
                             };
 
-                            context.dispatch_system_event(event);
+                            context.dispatch_window_event(window_entity, event);
                         }
 
                         winit::event::WindowEvent::ReceivedCharacter(character) => {
-                            context.dispatch_system_event(WindowEvent::CharInput(character));
+                            context.dispatch_window_event(
+                                window_entity,
+                                WindowEvent::CharInput(character),
+                            );
                         }
 
-                        winit::event::WindowEvent::Resized(physical_size) => {
-                            if let Some(mut window_view) = context.views.remove(&Entity::root()) {
-                                if let Some(window) = window_view.downcast_mut::<Window>() {
-                                    window.resize(physical_size);
+                        // Input methods only send these while allowed with `SetImeAllowed`
+                        winit::event::WindowEvent::Ime(ime) => {
+                            let event = match ime {
+                                Ime::Preedit(text, cursor) => {
+                                    Some(WindowEvent::ImePreedit(text, cursor))
                                 }
+                                Ime::Commit(text) => Some(WindowEvent::ImeCommit(text)),
+                                // Any text left over from a composition is cleared
+                                Ime::Disabled => Some(WindowEvent::ImePreedit(String::new(), None)),
+                                Ime::Enabled => None,
+                            };
 
-                                context.views.insert(Entity::root(), window_view);
+                            if let Some(event) = event {
+                                context.dispatch_window_event(window_entity, event);
                             }
+                        }
 
-                            let logical_size: LogicalSize<f32> =
-                                physical_size.to_logical(context.style().dpi_factor);
-
-                            context
-                                .style()
-                                .width
-                                .insert(Entity::root(), Units::Pixels(logical_size.width as f32));
-
-                            context
-                                .style()
-                                .height
-                                .insert(Entity::root(), Units::Pixels(logical_size.height as f32));
-
-                            context.cache().set_width(Entity::root(), physical_size.width as f32);
-                            context.cache().set_height(Entity::root(), physical_size.height as f32);
-
-                            let mut bounding_box = BoundingBox::default();
-                            bounding_box.w = physical_size.width as f32;
-                            bounding_box.h = physical_size.height as f32;
-
-                            context.cache().set_clip_region(Entity::root(), bounding_box);
+                        winit::event::WindowEvent::HoveredFile(path) => {
+                            context.dispatch_window_event(
+                                window_entity,
+                                WindowEvent::FileHovered(path),
+                            );
+                        }
 
-                            context.need_restyle();
-                            context.need_relayout();
-                            context.need_redraw();
+                        winit::event::WindowEvent::DroppedFile(path) => {
+                            context.dispatch_window_event(
+                                window_entity,
+                                WindowEvent::FileDropped(path),
+                            );
+                        }
 
-                            // let mut bounding_box = BoundingBox::default();
-                            // bounding_box.w = size.width as f32;
-                            // bounding_box.h = size.height as f32;
+                        winit::event::WindowEvent::HoveredFileCancelled => {
+                            context.dispatch_window_event(
+                                window_entity,
+                                WindowEvent::FileHoverCancelled,
+                            );
+                        }
 
-                            // context.cache.set_clip_region(Entity::root(), bounding_box);
+                        winit::event::WindowEvent::Resized(physical_size) => {
+                            resize_window(&mut context, window_entity, physical_size);
                         }
 
                         winit::event::WindowEvent::ModifiersChanged(modifiers_state) => {
@@ -586,13 +646,131 @@ impl WindowModifiers for Application {
 //     }
 // }
 
-fn context_draw(cx: &mut Context) {
-    if let Some(mut window_view) = cx.views.remove(&Entity::root()) {
-        if let Some(window) = window_view.downcast_mut::<Window>() {
-            cx.draw();
-            window.swap_buffers();
+fn context_draw(cx: &mut Context, window_entity: Entity) {
+    with_window(cx, window_entity, |window, cx| {
+        window.make_current();
+        // glutin can't tell the age of the back buffer, so the whole window is copied to it on
+        // every draw and only the compositor is told what changed
+        let damage = cx.draw_window(window_entity);
+        window.swap_buffers(&damage);
+    });
+
+    // The graphics context of the main window is current outside of drawing
+    if window_entity != Entity::root() {
+        with_window(cx, Entity::root(), |window, _| window.make_current());
+    }
+}
+
+/// Resizes the surface of a window and lays out its contents at the new size.
+fn resize_window(cx: &mut Context, window_entity: Entity, physical_size: PhysicalSize<u32>) {
+    with_window(cx, window_entity, |window, _| {
+        window.resize(physical_size);
+    });
+
+    let logical_size: LogicalSize<f32> = physical_size.to_logical(cx.style().dpi_factor);
+
+    cx.style().width.insert(window_entity, Units::Pixels(logical_size.width as f32));
+    cx.style().height.insert(window_entity, Units::Pixels(logical_size.height as f32));
+
+    if window_entity == Entity::root() {
+        cx.cache().set_width(Entity::root(), physical_size.width as f32);
+        cx.cache().set_height(Entity::root(), physical_size.height as f32);
+
+        let mut bounding_box = BoundingBox::default();
+        bounding_box.w = physical_size.width as f32;
+        bounding_box.h = physical_size.height as f32;
+
+        cx.cache().set_clip_region(Entity::root(), bounding_box);
+    }
+
+    cx.need_restyle();
+    cx.need_relayout();
+    cx.need_redraw();
+}
+
+/// Closes the windows whose views have been removed.
+fn close_windows(cx: &mut Context, window_entities: &mut HashMap<WindowId, Entity>) {
+    let closed_windows = cx.take_closed_windows();
+    if closed_windows.is_empty() {
+        return;
+    }
+
+    for (mut view, canvas) in closed_windows {
+        // The canvas releases its GPU resources in the context of its own window, which is
+        // closed when the view is dropped afterwards
+        if let Some(window) = view.downcast_mut::<Window>() {
+            window.make_current();
         }
 
-        cx.views.insert(Entity::root(), window_view);
+        drop(canvas);
+    }
+
+    window_entities.retain(|_, entity| cx.is_window(*entity));
+
+    with_window(cx, Entity::root(), |window, _| window.make_current());
+}
+
+fn request_redraws(cx: &mut Context, window_entities: &HashMap<WindowId, Entity>) {
+    for window_entity in window_entities.values() {
+        with_window(cx, *window_entity, |window, _| window.window().request_redraw());
     }
 }
+
+/// Opens a window for every `Window` view which doesn't have one yet.
+#[cfg(not(target_arch = "wasm32"))]
+fn create_windows(
+    cx: &mut Context,
+    target: &EventLoopWindowTarget<Event>,
+    window_entities: &mut HashMap<WindowId, Entity>,
+) {
+    let new_windows = cx
+        .windows()
+        .filter(|(entity, _)| !window_entities.values().any(|open| open == entity))
+        .map(|(entity, description)| (entity, description.clone()))
+        .collect::<Vec<_>>();
+
+    if new_windows.is_empty() {
+        return;
+    }
+
+    for (entity, description) in new_windows {
+        let mut window = Window::new(cx, target, &description, entity);
+
+        let physical_size = window.window().inner_size();
+        let clear_color = cx.style().background_color.get(entity).cloned().unwrap_or_default();
+        if let Some(canvas) = cx.canvases.get_mut(&entity) {
+            canvas.set_size(physical_size.width as u32, physical_size.height as u32, 1.0);
+            canvas.clear_rect(
+                0,
+                0,
+                physical_size.width as u32,
+                physical_size.height as u32,
+                clear_color.into(),
+            );
+        }
+
+        if let Some(view) = cx.views.remove(&entity) {
+            window.set_view(view);
+        }
+
+        window_entities.insert(window.id, entity);
+        cx.views.insert(entity, Box::new(window));
+    }
+
+    cx.need_relayout();
+    cx.need_redraw();
+
+    with_window(cx, Entity::root(), |window, _| window.make_current());
+}
+
+fn with_window<T>(
+    cx: &mut Context,
+    window_entity: Entity,
+    f: impl FnOnce(&mut Window, &mut Context) -> T,
+) -> Option<T> {
+    let mut window_view = cx.views.remove(&window_entity)?;
+    let result = window_view.downcast_mut::<Window>().map(|window| (f)(window, cx));
+    cx.views.insert(window_entity, window_view);
+
+    result
+}
@@ -2,26 +2,33 @@ use crate::convert::cursor_icon_to_cursor_icon;
 use femtovg::{renderer::OpenGl, Canvas, Color};
 #[cfg(not(target_arch = "wasm32"))]
 use glutin::ContextBuilder;
+use vizia_core::cache::BoundingBox;
+use vizia_core::events::ViewHandler;
 use vizia_core::prelude::*;
-use winit::event_loop::EventLoop;
-use winit::window::WindowBuilder;
+use winit::event_loop::EventLoopWindowTarget;
+use winit::window::{CursorGrabMode, WindowBuilder};
 use winit::{dpi::*, window::WindowId};
 
 pub struct Window {
     pub id: WindowId,
 
+    // Only taken while the context is being made current
     #[cfg(not(target_arch = "wasm32"))]
-    handle: glutin::WindowedContext<glutin::PossiblyCurrent>,
+    handle: Option<glutin::WindowedContext<glutin::PossiblyCurrent>>,
     #[cfg(target_arch = "wasm32")]
     handle: winit::window::Window,
+
+    // The view of a `Window` created with the `Window` view, which this window wraps
+    view: Option<Box<dyn ViewHandler>>,
 }
 
 #[cfg(target_arch = "wasm32")]
 impl Window {
     pub fn new(
         cx: &mut Context,
-        events_loop: &EventLoop<Event>,
+        events_loop: &EventLoopWindowTarget<Event>,
         window_description: &WindowDescription,
+        entity: Entity,
     ) -> Self {
         let window_builder = WindowBuilder::new();
 
@@ -63,10 +70,10 @@ impl Window {
         canvas.set_size(size.width as u32, size.height as u32, 1.0);
         canvas.clear_rect(0, 0, size.width as u32, size.height as u32, Color::rgb(255, 80, 80));
 
-        cx.canvases.insert(Entity::root(), canvas);
+        cx.add_canvas(entity, canvas);
 
         // Build our window
-        let window = Window { id: handle.id(), handle };
+        let window = Window { id: handle.id(), handle, view: None };
 
         window
     }
@@ -75,11 +82,15 @@ impl Window {
         &self.handle
     }
 
+    pub fn make_current(&mut self) {
+        // Intentional no-op
+    }
+
     pub fn resize(&self, _size: PhysicalSize<u32>) {
         // TODO?
     }
 
-    pub fn swap_buffers(&self) {
+    pub fn swap_buffers(&self, _damage: &[BoundingBox]) {
         // Intentional no-op
     }
 }
@@ -88,8 +99,9 @@ impl Window {
 impl Window {
     pub fn new(
         cx: &mut Context,
-        events_loop: &EventLoop<Event>,
+        events_loop: &EventLoopWindowTarget<Event>,
         window_description: &WindowDescription,
+        entity: Entity,
     ) -> Self {
         let window_builder = WindowBuilder::new();
 
@@ -114,8 +126,11 @@ impl Window {
             unsafe { handle.make_current().unwrap() }
         };
 
-        // Build the femtovg renderer
-        let renderer = OpenGl::new_from_glutin_context(&handle).expect("Cannot create renderer");
+        // Build the femtovg renderer, loading the OpenGL functions through glutin
+        let renderer = unsafe {
+            OpenGl::new_from_function(|s| handle.get_proc_address(s) as *const _)
+                .expect("Cannot create renderer")
+        };
 
         let mut canvas = Canvas::new(renderer).expect("Failed to create canvas");
 
@@ -123,98 +138,177 @@ impl Window {
         canvas.set_size(size.width as u32, size.height as u32, 1.0);
         canvas.clear_rect(0, 0, size.width as u32, size.height as u32, Color::rgb(255, 80, 80));
 
-        cx.canvases.insert(Entity::root(), canvas);
+        cx.add_canvas(entity, canvas);
 
         // Build our window
-        let window = Window { id: handle.window().id(), handle };
+        let window = Window { id: handle.window().id(), handle: Some(handle), view: None };
 
         window
     }
 
     pub fn window(&self) -> &winit::window::Window {
-        self.handle.window()
+        self.handle().window()
+    }
+
+    /// Makes the graphics context of the window current, which is required before drawing into
+    /// its canvas when there are multiple windows.
+    pub fn make_current(&mut self) {
+        if self.handle().is_current() {
+            return;
+        }
+
+        if let Some(handle) = self.handle.take() {
+            let handle = unsafe { handle.make_current() }
+                .map_err(|(_, err)| err)
+                .expect("Failed to make context current");
+            self.handle = Some(handle);
+        }
     }
 
     pub fn resize(&self, size: PhysicalSize<u32>) {
-        self.handle.resize(size);
+        self.handle().resize(size);
+    }
+
+    /// Presents the frame which was drawn into the window. `damage` is the regions which changed
+    /// since the last frame, which lets the compositor skip the rest of the window on platforms
+    /// which support it.
+    pub fn swap_buffers(&self, damage: &[BoundingBox]) {
+        let handle = self.handle();
+        if damage.is_empty() || !handle.swap_buffers_with_damage_supported() {
+            handle.swap_buffers().expect("Failed to swap buffers");
+            return;
+        }
+
+        // Damage rectangles start from the bottom left corner of the window
+        let height = handle.window().inner_size().height;
+        let rects: Vec<glutin::Rect> = damage
+            .iter()
+            .map(|damage| glutin::Rect {
+                x: damage.x as u32,
+                y: height.saturating_sub((damage.y + damage.h) as u32),
+                width: damage.w as u32,
+                height: damage.h as u32,
+            })
+            .collect();
+
+        handle.swap_buffers_with_damage(&rects).expect("Failed to swap buffers");
+    }
+
+    fn handle(&self) -> &glutin::WindowedContext<glutin::PossiblyCurrent> {
+        self.handle.as_ref().expect("Window has no context")
     }
+}
 
-    pub fn swap_buffers(&self) {
-        self.handle.swap_buffers().expect("Failed to swap buffers");
+impl Window {
+    /// Wraps the view of a `Window` view, so that the view keeps receiving its events.
+    pub fn set_view(&mut self, view: Box<dyn ViewHandler>) {
+        self.view = Some(view);
     }
 }
 
 impl View for Window {
-    fn event(&mut self, _: &mut EventContext, event: &mut Event) {
-        event.map(|window_event, _| match window_event {
-            WindowEvent::GrabCursor(flag) => {
-                self.window().set_cursor_grab(*flag).expect("Failed to set cursor grab");
-            }
+    fn element(&self) -> Option<&'static str> {
+        self.view.as_ref().and_then(|view| view.element())
+    }
 
-            WindowEvent::SetCursorPosition(x, y) => {
-                self.window()
-                    .set_cursor_position(winit::dpi::Position::Physical(PhysicalPosition::new(
-                        *x as i32, *y as i32,
-                    )))
-                    .expect("Failed to set cursor position");
-            }
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        if let Some(view) = &mut self.view {
+            view.event(cx, event);
+        }
+
+        event.map(|window_event, meta| {
+            match window_event {
+                WindowEvent::GrabCursor(flag) => {
+                    let grab = if *flag {
+                        // macOS can only lock the cursor in place rather than confine it
+                        self.window()
+                            .set_cursor_grab(CursorGrabMode::Confined)
+                            .or_else(|_| self.window().set_cursor_grab(CursorGrabMode::Locked))
+                    } else {
+                        self.window().set_cursor_grab(CursorGrabMode::None)
+                    };
+                    grab.expect("Failed to set cursor grab");
+                }
 
-            WindowEvent::SetCursor(cursor) => {
-                if let Some(icon) = cursor_icon_to_cursor_icon(*cursor) {
-                    self.window().set_cursor_visible(true);
-                    self.window().set_cursor_icon(icon);
-                } else {
-                    self.window().set_cursor_visible(false);
+                WindowEvent::SetCursorPosition(x, y) => {
+                    self.window()
+                        .set_cursor_position(winit::dpi::Position::Physical(PhysicalPosition::new(
+                            *x as i32, *y as i32,
+                        )))
+                        .expect("Failed to set cursor position");
                 }
-            }
 
-            WindowEvent::SetTitle(title) => {
-                self.window().set_title(title);
-            }
+                WindowEvent::SetImeAllowed(allowed) => {
+                    self.window().set_ime_allowed(*allowed);
+                }
 
-            WindowEvent::SetSize(size) => {
-                self.window().set_inner_size(LogicalSize::new(size.width, size.height));
-            }
+                WindowEvent::SetImeCursorArea(x, y, _, height) => {
+                    // The candidate window is placed at this position, so put it below the caret
+                    self.window().set_ime_position(LogicalPosition::new(*x, *y + *height));
+                }
 
-            WindowEvent::SetMinSize(size) => {
-                self.window()
-                    .set_min_inner_size(size.map(|size| LogicalSize::new(size.width, size.height)));
-            }
+                WindowEvent::SetCursor(cursor) => {
+                    if let Some(icon) = cursor_icon_to_cursor_icon(*cursor) {
+                        self.window().set_cursor_visible(true);
+                        self.window().set_cursor_icon(icon);
+                    } else {
+                        self.window().set_cursor_visible(false);
+                    }
+                }
 
-            WindowEvent::SetMaxSize(size) => {
-                self.window()
-                    .set_max_inner_size(size.map(|size| LogicalSize::new(size.width, size.height)));
-            }
+                WindowEvent::SetTitle(title) => {
+                    self.window().set_title(title);
+                }
 
-            WindowEvent::SetPosition(pos) => {
-                self.window().set_outer_position(LogicalPosition::new(pos.x, pos.y));
-            }
+                WindowEvent::SetSize(size) => {
+                    self.window().set_inner_size(LogicalSize::new(size.width, size.height));
+                }
 
-            WindowEvent::SetResizable(flag) => {
-                self.window().set_resizable(*flag);
-            }
+                WindowEvent::SetMinSize(size) => {
+                    self.window().set_min_inner_size(
+                        size.map(|size| LogicalSize::new(size.width, size.height)),
+                    );
+                }
 
-            WindowEvent::SetMinimized(flag) => {
-                self.window().set_minimized(*flag);
-            }
+                WindowEvent::SetMaxSize(size) => {
+                    self.window().set_max_inner_size(
+                        size.map(|size| LogicalSize::new(size.width, size.height)),
+                    );
+                }
 
-            WindowEvent::SetMaximized(flag) => {
-                self.window().set_maximized(*flag);
-            }
+                WindowEvent::SetPosition(pos) => {
+                    self.window().set_outer_position(LogicalPosition::new(pos.x, pos.y));
+                }
 
-            WindowEvent::SetVisible(flag) => {
-                self.window().set_visible(*flag);
-            }
+                WindowEvent::SetResizable(flag) => {
+                    self.window().set_resizable(*flag);
+                }
 
-            WindowEvent::SetDecorations(flag) => {
-                self.window().set_decorations(*flag);
-            }
+                WindowEvent::SetMinimized(flag) => {
+                    self.window().set_minimized(*flag);
+                }
+
+                WindowEvent::SetMaximized(flag) => {
+                    self.window().set_maximized(*flag);
+                }
+
+                WindowEvent::SetVisible(flag) => {
+                    self.window().set_visible(*flag);
+                }
+
+                WindowEvent::SetDecorations(flag) => {
+                    self.window().set_decorations(*flag);
+                }
+
+                WindowEvent::SetAlwaysOnTop(flag) => {
+                    self.window().set_always_on_top(*flag);
+                }
 
-            WindowEvent::SetAlwaysOnTop(flag) => {
-                self.window().set_always_on_top(*flag);
+                _ => return,
             }
 
-            _ => {}
+            // Stop the event from also reaching the windows further up the tree
+            meta.consume();
         })
     }
 }